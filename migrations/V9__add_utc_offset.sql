@@ -0,0 +1,4 @@
+-- V9: Record the UTC offset (in seconds east of UTC) that was in effect
+-- when an entry was started, so that reports can show it as recorded.
+-- Entries created before this migration have no offset (NULL).
+ALTER TABLE schedulables ADD COLUMN utc_offset INTEGER CHECK (utc_offset BETWEEN -86399 AND 86399);
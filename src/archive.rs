@@ -0,0 +1,50 @@
+use crate::persistence::{PersistenceError, Repository};
+use crate::plural;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// The archive database next to `data.db` in the given root.
+pub fn path(root: &Path) -> PathBuf {
+    root.join("archive.db")
+}
+
+/// What [`archive`] moved.
+#[derive(Debug, Default, PartialEq)]
+pub struct Summary {
+    pub entries: usize,
+    pub annotations: usize,
+    pub interrupts: usize,
+}
+
+/// Move the pomodori and breaks that started before `before` and have ended
+/// from `repo` into the archive database at `path`, together with their
+/// annotations and interrupts. The archive is created if it does not exist,
+/// and brought up to the current schema otherwise. Running entries are never
+/// archived.
+pub fn archive(repo: &Repository, path: &Path, before: i64) -> Result<Summary, PersistenceError> {
+    Repository::create_or_migrate(path)?;
+    repo.move_ended_before(path, before)
+}
+
+/// Make the entries in the archive database at `path` visible to all queries
+/// on `repo`, if the archive exists. The archive is only read, so one written
+/// by a newer version is rejected rather than migrated. Meant for reports;
+/// `repo` must not be written to afterwards.
+pub fn include(repo: &Repository, path: &Path) -> Result<(), PersistenceError> {
+    if !path.is_file() {
+        return Ok(());
+    }
+    repo.attach_read_only(path)
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}, {} and {}",
+            plural(self.entries, "entry", "entries"),
+            plural(self.annotations, "annotation", "annotations"),
+            plural(self.interrupts, "interrupt", "interrupts"),
+        )
+    }
+}
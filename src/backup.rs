@@ -0,0 +1,111 @@
+use crate::persistence::Repository;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// File name prefix for backups written into `$RUSTOMATO_ROOT/backups/`.
+const PREFIX: &str = "data-";
+
+/// The directory that holds rotated backups.
+pub fn backups_dir(root: &Path) -> PathBuf {
+    root.join("backups")
+}
+
+/// Path for a new backup in `dir`, named after the given Unix timestamp in UTC
+/// (e.g. `data-20260529T101500Z.db`), so that lexical order is chronological.
+pub fn timestamped_path(dir: &Path, ts: i64) -> PathBuf {
+    let stamp = chrono::DateTime::from_timestamp(ts, 0)
+        .map(|dt| dt.format("%Y%m%dT%H%M%SZ").to_string())
+        .unwrap_or_else(|| ts.to_string());
+    dir.join(format!("{}{}.db", PREFIX, stamp))
+}
+
+/// Back up the database into a new timestamped file in `dir`, creating the
+/// directory if needed. If `keep` is given, only the `keep` most recent
+/// backups are retained. Returns the new backup and the removed ones.
+pub fn backup_rotated(
+    repo: &Repository,
+    dir: &Path,
+    keep: Option<usize>,
+) -> Result<(PathBuf, Vec<PathBuf>), String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("cannot create {}: {}", dir.display(), e))?;
+
+    let path = timestamped_path(dir, crate::now());
+    repo.backup_to(&path).map_err(|e| e.to_string())?;
+
+    let removed = match keep {
+        Some(keep) => rotate(dir, keep).map_err(|e| format!("cannot rotate backups: {}", e))?,
+        None => Vec::new(),
+    };
+
+    Ok((path, removed))
+}
+
+/// Delete all but the `keep` most recent backups in `dir`.
+/// Files that do not look like rotated backups are left alone.
+pub fn rotate(dir: &Path, keep: usize) -> io::Result<Vec<PathBuf>> {
+    let mut backups: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| is_rotated_backup(p))
+        .collect();
+    backups.sort();
+
+    let excess = backups.len().saturating_sub(keep);
+    let removed: Vec<PathBuf> = backups.into_iter().take(excess).collect();
+    for path in &removed {
+        std::fs::remove_file(path)?;
+    }
+    Ok(removed)
+}
+
+fn is_rotated_backup(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with(PREFIX) && n.ends_with(".db"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_timestamped_path_is_utc() {
+        let path = timestamped_path(Path::new("/tmp"), 1_780_048_800);
+        assert_eq!(path, PathBuf::from("/tmp/data-20260529T100000Z.db"));
+    }
+
+    #[test]
+    fn test_rotate_keeps_most_recent() {
+        let dir = tempdir().unwrap();
+        for ts in [1000, 2000, 3000, 4000] {
+            std::fs::write(timestamped_path(dir.path(), ts), "").unwrap();
+        }
+
+        let removed = rotate(dir.path(), 2).unwrap();
+
+        assert_eq!(
+            removed,
+            vec![
+                timestamped_path(dir.path(), 1000),
+                timestamped_path(dir.path(), 2000)
+            ]
+        );
+        assert!(timestamped_path(dir.path(), 3000).exists());
+        assert!(timestamped_path(dir.path(), 4000).exists());
+    }
+
+    #[test]
+    fn test_rotate_ignores_foreign_files() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "").unwrap();
+        std::fs::write(timestamped_path(dir.path(), 1000), "").unwrap();
+
+        let removed = rotate(dir.path(), 0).unwrap();
+
+        assert_eq!(removed, vec![timestamped_path(dir.path(), 1000)]);
+        assert!(dir.path().join("notes.txt").exists());
+    }
+}
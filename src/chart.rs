@@ -0,0 +1,295 @@
+//! SVG bar and line charts for `report chart`.
+//!
+//! The output depends only on the data: no timestamps, random IDs or
+//! locale-dependent number formatting, so charts can be compared byte by byte.
+
+use std::fmt::Write;
+
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 360.0;
+const MARGIN_LEFT: f64 = 48.0;
+const MARGIN_RIGHT: f64 = 16.0;
+const MARGIN_TOP: f64 = 56.0;
+const MARGIN_BOTTOM: f64 = 40.0;
+
+/// More labels than this on the x axis would overlap; only every n-th is shown.
+const MAX_LABELS: usize = 16;
+
+/// The bars of a label are at most this wide together, even with few labels.
+const MAX_BAR_GROUP: f64 = 64.0;
+
+pub const GREEN: &str = "#43a047";
+pub const RED: &str = "#e53935";
+pub const ORANGE: &str = "#fb8c00";
+pub const BLUE: &str = "#1e88e5";
+
+/// How the series of a [`Chart`] are drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Style {
+    /// The series side by side
+    Bars,
+    /// The series on top of each other
+    StackedBars,
+    /// One line per series
+    Lines,
+}
+
+/// One value per label, drawn in one colour.
+#[derive(Debug)]
+pub struct Series {
+    pub name: &'static str,
+    pub colour: &'static str,
+    pub values: Vec<f64>,
+}
+
+/// A chart with a category on the x axis and counts on the y axis.
+#[derive(Debug)]
+pub struct Chart {
+    pub title: String,
+    pub style: Style,
+    /// One per category, e.g. a day or an hour
+    pub labels: Vec<String>,
+    pub series: Vec<Series>,
+}
+
+/// The smallest "round" step (1, 2 or 5 times a power of ten) that covers
+/// `max` in at most five steps, and the resulting top of the axis.
+fn axis(max: f64) -> (f64, f64) {
+    let max = max.max(1.0);
+    let mut magnitude = 1.0;
+    loop {
+        for step in [1.0, 2.0, 5.0].map(|s| s * magnitude) {
+            if max / step <= 5.0 {
+                return (step, (max / step).ceil() * step);
+            }
+        }
+        magnitude *= 10.0;
+    }
+}
+
+/// Escape text for SVG and HTML.
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl Chart {
+    /// The chart as a standalone SVG document.
+    pub fn svg(&self) -> String {
+        let mut svg = String::new();
+        let (plot_w, plot_h) = (
+            WIDTH - MARGIN_LEFT - MARGIN_RIGHT,
+            HEIGHT - MARGIN_TOP - MARGIN_BOTTOM,
+        );
+        let bottom = MARGIN_TOP + plot_h;
+        let count = self.labels.len().max(1);
+        let slot = plot_w / count as f64;
+
+        let max = (0..self.labels.len())
+            .map(|i| {
+                let values = self.series.iter().map(|s| s.values[i]);
+                match self.style {
+                    Style::StackedBars => values.sum(),
+                    _ => values.fold(0.0, f64::max),
+                }
+            })
+            .fold(0.0, f64::max);
+        let (step, top) = axis(max);
+        let y = |value: f64| bottom - value / top * plot_h;
+
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="11">"#,
+            w = WIDTH,
+            h = HEIGHT
+        );
+        let _ = writeln!(
+            svg,
+            r#"<rect width="{}" height="{}" fill="white"/>"#,
+            WIDTH, HEIGHT
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="20" font-size="14" font-weight="bold">{}</text>"#,
+            MARGIN_LEFT,
+            escape(&self.title)
+        );
+
+        // Legend
+        let mut x = MARGIN_LEFT;
+        for series in &self.series {
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{:.1}" y="30" width="10" height="10" fill="{}"/>"#,
+                x, series.colour
+            );
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.1}" y="39">{}</text>"#,
+                x + 14.0,
+                escape(series.name)
+            );
+            x += 14.0 + 7.0 * series.name.len() as f64 + 16.0;
+        }
+
+        // Grid and y axis
+        let mut tick = 0.0;
+        while tick <= top {
+            let _ = writeln!(
+                svg,
+                r##"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="#e0e0e0"/>"##,
+                MARGIN_LEFT,
+                y(tick),
+                MARGIN_LEFT + plot_w,
+                y(tick)
+            );
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}" text-anchor="end">{}</text>"#,
+                MARGIN_LEFT - 6.0,
+                y(tick) + 4.0,
+                tick
+            );
+            tick += step;
+        }
+
+        // Data
+        let group = (slot * 0.8).min(MAX_BAR_GROUP);
+        let offset = (slot - group) / 2.0;
+        let bar_w = match self.style {
+            Style::Bars => group / self.series.len().max(1) as f64,
+            _ => group,
+        };
+        for (s, series) in self.series.iter().enumerate() {
+            match self.style {
+                Style::Lines => {
+                    let points: Vec<String> = series
+                        .values
+                        .iter()
+                        .enumerate()
+                        .map(|(i, v)| {
+                            format!("{:.1},{:.1}", MARGIN_LEFT + slot * (i as f64 + 0.5), y(*v))
+                        })
+                        .collect();
+                    let _ = writeln!(
+                        svg,
+                        r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="2"/>"#,
+                        points.join(" "),
+                        series.colour
+                    );
+                    for (i, v) in series.values.iter().enumerate() {
+                        let _ = writeln!(
+                            svg,
+                            r#"<circle cx="{:.1}" cy="{:.1}" r="3" fill="{}"><title>{} {}: {}</title></circle>"#,
+                            MARGIN_LEFT + slot * (i as f64 + 0.5),
+                            y(*v),
+                            series.colour,
+                            escape(&self.labels[i]),
+                            escape(series.name),
+                            v
+                        );
+                    }
+                }
+                Style::Bars | Style::StackedBars => {
+                    for (i, v) in series.values.iter().enumerate() {
+                        if *v == 0.0 {
+                            continue;
+                        }
+                        let (x, base) = match self.style {
+                            Style::Bars => (
+                                MARGIN_LEFT + slot * i as f64 + offset + bar_w * s as f64,
+                                0.0,
+                            ),
+                            _ => (
+                                MARGIN_LEFT + slot * i as f64 + offset,
+                                self.series[..s].iter().map(|p| p.values[i]).sum(),
+                            ),
+                        };
+                        let _ = writeln!(
+                            svg,
+                            r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"><title>{} {}: {}</title></rect>"#,
+                            x,
+                            y(base + v),
+                            bar_w,
+                            y(base) - y(base + v),
+                            series.colour,
+                            escape(&self.labels[i]),
+                            escape(series.name),
+                            v
+                        );
+                    }
+                }
+            }
+        }
+
+        // x axis
+        let _ = writeln!(
+            svg,
+            r##"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="#616161"/>"##,
+            MARGIN_LEFT,
+            bottom,
+            MARGIN_LEFT + plot_w,
+            bottom
+        );
+        let every = self.labels.len().div_ceil(MAX_LABELS).max(1);
+        for (i, label) in self.labels.iter().enumerate().step_by(every) {
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+                MARGIN_LEFT + slot * (i as f64 + 0.5),
+                bottom + 16.0,
+                escape(label)
+            );
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_axis() {
+        assert_eq!(axis(0.0), (1.0, 1.0));
+        assert_eq!(axis(4.0), (1.0, 4.0));
+        assert_eq!(axis(7.0), (2.0, 8.0));
+        assert_eq!(axis(23.0), (5.0, 25.0));
+        assert_eq!(axis(120.0), (50.0, 150.0));
+    }
+
+    #[test]
+    fn test_svg() {
+        let chart = Chart {
+            title: "Q&A".to_string(),
+            style: Style::StackedBars,
+            labels: vec!["09".to_string(), "10".to_string()],
+            series: vec![
+                Series {
+                    name: "Internal",
+                    colour: ORANGE,
+                    values: vec![1.0, 0.0],
+                },
+                Series {
+                    name: "External",
+                    colour: BLUE,
+                    values: vec![2.0, 1.0],
+                },
+            ],
+        };
+
+        let svg = chart.svg();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains(">Q&amp;A</text>"));
+        // 3 on top of 1 at 09:00, and only the external one at 10:00
+        assert_eq!(svg.matches("<rect x=").count(), 2 + 3);
+        assert!(svg.contains("<title>09 External: 2</title>"));
+        assert!(!svg.contains("10 Internal"));
+        assert_eq!(svg, chart.svg());
+    }
+}
@@ -0,0 +1,463 @@
+use crate::hooks::{HookEvent, sample_hook_content};
+use crate::persistence::{PersistenceError, Repository};
+use crate::{Kind, Schedulable, SqlUuid, Status};
+use serde::Serialize;
+use std::fmt;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+/// A single consistency problem found by [`diagnose`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "check", rename_all = "kebab-case")]
+pub enum Problem {
+    /// `schedulables.interruptions` disagrees with the number of `interrupt_log` rows.
+    InterruptCountMismatch {
+        uuid: String,
+        counter: i64,
+        logged: i64,
+    },
+    /// An annotation refers to a schedulable that does not exist.
+    OrphanedAnnotation {
+        uuid: String,
+        schedulable_uuid: String,
+    },
+    /// An interrupt log entry refers to a schedulable that does not exist.
+    OrphanedInterrupt {
+        uuid: String,
+        schedulable_uuid: String,
+    },
+    /// An entry still holds the PID of a process that is gone.
+    StalePid {
+        uuid: String,
+        kind: String,
+        pid: u32,
+    },
+    /// A finished or cancelled entry that ends at or before its start.
+    EmptyTimeRange {
+        uuid: String,
+        kind: String,
+        elapsed_secs: i64,
+    },
+    /// A hook was customized but is not executable, so it never runs.
+    HookNotExecutable { path: PathBuf },
+    /// A hook is executable but cannot be read, so the interpreter fails.
+    HookNotReadable { path: PathBuf },
+    /// A file in the hooks directory does not match any hook event.
+    UnknownHook { path: PathBuf },
+}
+
+impl Problem {
+    /// Whether `doctor --fix` knows how to repair this problem. Empty time
+    /// ranges and unknown hooks are only reported: the only repair would be
+    /// deleting recorded work or the user's files, which is left to the user.
+    pub fn is_fixable(&self) -> bool {
+        !matches!(
+            self,
+            Problem::EmptyTimeRange { .. } | Problem::UnknownHook { .. }
+        )
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::InterruptCountMismatch {
+                uuid,
+                counter,
+                logged,
+            } => write!(
+                f,
+                "pomodoro {} has an interruption counter of {} but {} logged",
+                uuid, counter, logged
+            ),
+            Problem::OrphanedAnnotation {
+                uuid,
+                schedulable_uuid,
+            } => write!(
+                f,
+                "annotation {} refers to missing entry {}",
+                uuid, schedulable_uuid
+            ),
+            Problem::OrphanedInterrupt {
+                uuid,
+                schedulable_uuid,
+            } => write!(
+                f,
+                "interrupt {} refers to missing entry {}",
+                uuid, schedulable_uuid
+            ),
+            Problem::StalePid { uuid, kind, pid } => {
+                write!(f, "{} {} is stale (pid {} does not exist)", kind, uuid, pid)
+            }
+            Problem::EmptyTimeRange {
+                uuid,
+                kind,
+                elapsed_secs,
+            } => write!(f, "{} {} lasted {} seconds", kind, uuid, elapsed_secs),
+            Problem::HookNotExecutable { path } => write!(
+                f,
+                "hook {} was customized but is not executable",
+                path.display()
+            ),
+            Problem::HookNotReadable { path } => {
+                write!(f, "hook {} is executable but not readable", path.display())
+            }
+            Problem::UnknownHook { path } => write!(
+                f,
+                "{} is not a known hook name and will never run",
+                path.display()
+            ),
+        }
+    }
+}
+
+/// A problem together with the outcome of trying to fix it.
+#[derive(Debug, Serialize)]
+pub struct Finding {
+    #[serde(flatten)]
+    pub problem: Problem,
+    pub message: String,
+    pub fixable: bool,
+    pub fixed: bool,
+}
+
+/// Check the database and the hooks directory under `root` for inconsistencies.
+pub fn diagnose(repo: &Repository, root: &Path) -> Result<Vec<Problem>, PersistenceError> {
+    let mut problems = Vec::new();
+
+    for (s, logged) in repo.interrupt_count_mismatches()? {
+        problems.push(Problem::InterruptCountMismatch {
+            uuid: s.uuid.to_string(),
+            counter: s.interruptions,
+            logged,
+        });
+    }
+
+    for a in repo.orphaned_annotations()? {
+        problems.push(Problem::OrphanedAnnotation {
+            uuid: a.uuid.to_string(),
+            schedulable_uuid: a.schedulable_uuid.to_string(),
+        });
+    }
+
+    for i in repo.orphaned_interrupts()? {
+        problems.push(Problem::OrphanedInterrupt {
+            uuid: i.uuid.to_string(),
+            schedulable_uuid: i.schedulable_uuid.to_string(),
+        });
+    }
+
+    if let Some(s) = repo.active()?
+        && matches!(s.status(), Status::Stale)
+    {
+        problems.push(Problem::StalePid {
+            uuid: s.uuid.to_string(),
+            kind: s.kind.to_string(),
+            pid: s.pid,
+        });
+    }
+
+    for s in repo.empty_entries()? {
+        problems.push(Problem::EmptyTimeRange {
+            uuid: s.uuid.to_string(),
+            kind: s.kind.to_string(),
+            elapsed_secs: s.end() - s.started_at,
+        });
+    }
+
+    problems.extend(check_hooks(&root.join("hooks")));
+
+    Ok(problems)
+}
+
+/// Inspect the hooks directory. A missing directory is not a problem.
+fn check_hooks(hooks_dir: &Path) -> Vec<Problem> {
+    let Ok(dir) = std::fs::read_dir(hooks_dir) else {
+        return Vec::new();
+    };
+
+    let mut paths: Vec<PathBuf> = dir
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .collect();
+    paths.sort();
+
+    let mut problems = Vec::new();
+    for path in paths {
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if !HookEvent::ALL.contains(&name) {
+            problems.push(Problem::UnknownHook { path });
+            continue;
+        }
+
+        let Ok(metadata) = path.metadata() else {
+            continue;
+        };
+        let mode = metadata.permissions().mode();
+
+        if mode & 0o111 != 0 {
+            if mode & 0o400 == 0 {
+                problems.push(Problem::HookNotReadable { path });
+            }
+        } else if std::fs::read_to_string(&path)
+            .map(|content| content != sample_hook_content(name))
+            .unwrap_or(false)
+        {
+            problems.push(Problem::HookNotExecutable { path });
+        }
+    }
+    problems
+}
+
+/// Repair a single problem.
+pub fn fix(repo: &Repository, problem: &Problem) -> Result<(), String> {
+    match problem {
+        Problem::InterruptCountMismatch { uuid, logged, .. } => repo
+            .set_interruptions(parse_uuid(uuid)?, *logged)
+            .map_err(|e| e.to_string()),
+        Problem::OrphanedAnnotation { uuid, .. } => repo
+            .delete_annotation(parse_uuid(uuid)?)
+            .map_err(|e| e.to_string()),
+        Problem::OrphanedInterrupt { uuid, .. } => repo
+            .delete_interrupt(parse_uuid(uuid)?)
+            .map_err(|e| e.to_string()),
+        Problem::StalePid { uuid, .. } => {
+            let mut s = repo
+                .find_by_uuid(parse_uuid(uuid)?)
+                .map_err(|e| e.to_string())?;
+            close_stale(repo, &mut s)
+        }
+        Problem::HookNotExecutable { path } => add_mode(path, 0o111),
+        Problem::HookNotReadable { path } => add_mode(path, 0o400),
+        Problem::EmptyTimeRange { .. } | Problem::UnknownHook { .. } => {
+            Err("cannot be fixed automatically".to_string())
+        }
+    }
+}
+
+/// Diagnose, optionally fix, and return one finding per problem.
+pub fn run(repo: &Repository, root: &Path, apply_fixes: bool) -> Result<Vec<Finding>, String> {
+    let problems = diagnose(repo, root).map_err(|e| e.to_string())?;
+
+    let mut findings = Vec::new();
+    for problem in problems {
+        let fixable = problem.is_fixable();
+        let mut message = problem.to_string();
+        let fixed = if apply_fixes && fixable {
+            match fix(repo, &problem) {
+                Ok(()) => true,
+                Err(e) => {
+                    message = format!("{} (fix failed: {})", message, e);
+                    false
+                }
+            }
+        } else {
+            false
+        };
+        findings.push(Finding {
+            problem,
+            message,
+            fixable,
+            fixed,
+        });
+    }
+    Ok(findings)
+}
+
+/// Render findings as human-readable text. `fixing` tells whether fixes were
+/// attempted, so that the hint to run `--fix` is only shown when they were not.
+pub fn format_text(findings: &[Finding], fixing: bool) -> String {
+    use std::fmt::Write;
+
+    if findings.is_empty() {
+        return "No problems found.\n".to_string();
+    }
+
+    let mut out = String::new();
+    let noun = if findings.len() == 1 {
+        "problem"
+    } else {
+        "problems"
+    };
+    let _ = writeln!(out, "{} {} found:", findings.len(), noun);
+    for finding in findings {
+        let marker = if finding.fixed {
+            "\u{2713}"
+        } else {
+            "\u{2717}"
+        };
+        let suffix = if finding.fixed { " (fixed)" } else { "" };
+        let _ = writeln!(out, "  {} {}{}", marker, finding.message, suffix);
+    }
+
+    let unfixed = findings.iter().filter(|f| f.fixable && !f.fixed).count();
+    if unfixed > 0 && !fixing {
+        let _ = writeln!(
+            out,
+            "\nRun `rustomato doctor --fix` to repair {} of them.",
+            unfixed
+        );
+    }
+    out
+}
+
+/// Render findings as a JSON document.
+pub fn format_json(findings: &[Finding]) -> String {
+    #[derive(Serialize)]
+    struct Document<'a> {
+        healthy: bool,
+        problems: &'a [Finding],
+    }
+
+    let healthy = findings.iter().all(|f| f.fixed);
+    serde_json::to_string_pretty(&Document {
+        healthy,
+        problems: findings,
+    })
+    .expect("serializing doctor findings")
+}
+
+/// Close out a stale entry the same way the timer would have: pomodori are
+/// cancelled, breaks are finished. The end is placed at the planned end (or
+/// now, if that is earlier), since the time the process died is unknown.
+fn close_stale(repo: &Repository, s: &mut Schedulable) -> Result<(), String> {
+    let end = (s.started_at + s.duration * 60).min(crate::now());
+    match s.kind {
+        Kind::Pomodoro => s.cancelled_at = end,
+        Kind::Break => s.finished_at = end,
+    }
+    repo.save(s).map(|_| ()).map_err(|e| e.to_string())
+}
+
+fn add_mode(path: &Path, bits: u32) -> Result<(), String> {
+    let mode = path
+        .metadata()
+        .map_err(|e| e.to_string())?
+        .permissions()
+        .mode();
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode | bits))
+        .map_err(|e| e.to_string())
+}
+
+fn parse_uuid(s: &str) -> Result<SqlUuid, String> {
+    uuid::Uuid::parse_str(s)
+        .map(SqlUuid)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write_hook(dir: &Path, name: &str, content: &str, mode: u32) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_missing_hooks_dir_is_fine() {
+        let dir = tempdir().unwrap();
+        assert!(check_hooks(&dir.path().join("hooks")).is_empty());
+    }
+
+    #[test]
+    fn test_untouched_sample_hook_is_fine() {
+        let dir = tempdir().unwrap();
+        let name = "after-finish-pomodoro";
+        write_hook(dir.path(), name, &sample_hook_content(name), 0o644);
+        assert!(check_hooks(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_customized_hook_without_exec_bit() {
+        let dir = tempdir().unwrap();
+        let path = write_hook(
+            dir.path(),
+            "after-finish-pomodoro",
+            "#!/bin/sh\necho done\n",
+            0o644,
+        );
+        assert_eq!(
+            check_hooks(dir.path()),
+            vec![Problem::HookNotExecutable { path }]
+        );
+    }
+
+    #[test]
+    fn test_executable_hook_without_read_bit() {
+        let dir = tempdir().unwrap();
+        let path = write_hook(dir.path(), "after-finish-pomodoro", "#!/bin/sh\n", 0o311);
+        assert_eq!(
+            check_hooks(dir.path()),
+            vec![Problem::HookNotReadable { path }]
+        );
+    }
+
+    #[test]
+    fn test_unknown_hook_name() {
+        let dir = tempdir().unwrap();
+        let path = write_hook(dir.path(), "after-finish-pomodoro.sh", "#!/bin/sh\n", 0o755);
+        let problems = check_hooks(dir.path());
+        assert_eq!(problems, vec![Problem::UnknownHook { path }]);
+        assert!(!problems[0].is_fixable());
+    }
+
+    #[test]
+    fn test_fix_makes_hook_executable() {
+        let dir = tempdir().unwrap();
+        let path = write_hook(
+            dir.path(),
+            "after-finish-pomodoro",
+            "#!/bin/sh\necho done\n",
+            0o644,
+        );
+        let repo = Repository::new("file::memory:");
+        fix(&repo, &Problem::HookNotExecutable { path: path.clone() }).unwrap();
+        assert_eq!(path.metadata().unwrap().permissions().mode() & 0o777, 0o755);
+    }
+
+    #[test]
+    fn test_format_text_healthy() {
+        assert_eq!(format_text(&[], false), "No problems found.\n");
+    }
+
+    #[test]
+    fn test_format_text_suggests_fix() {
+        let findings = vec![Finding {
+            problem: Problem::UnknownHook {
+                path: PathBuf::from("x"),
+            },
+            message: "x is odd".to_string(),
+            fixable: true,
+            fixed: false,
+        }];
+        let text = format_text(&findings, false);
+        assert!(text.contains("1 problem found"));
+        assert!(text.contains("doctor --fix"));
+        assert!(!format_text(&findings, true).contains("doctor --fix"));
+    }
+
+    #[test]
+    fn test_format_json_tags_check() {
+        let findings = vec![Finding {
+            problem: Problem::StalePid {
+                uuid: "abc".to_string(),
+                kind: "pomodoro".to_string(),
+                pid: 42,
+            },
+            message: "stale".to_string(),
+            fixable: true,
+            fixed: true,
+        }];
+        let json: serde_json::Value = serde_json::from_str(&format_json(&findings)).unwrap();
+        assert_eq!(json["healthy"], true);
+        assert_eq!(json["problems"][0]["check"], "stale-pid");
+        assert_eq!(json["problems"][0]["pid"], 42);
+    }
+}
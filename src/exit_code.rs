@@ -0,0 +1,33 @@
+//! Process exit codes. They are part of the command-line interface and
+//! documented in the man page, so existing values must never change.
+
+/// Success; for `pomodoro start`, the pomodoro was finished.
+pub const SUCCESS: i32 = 0;
+
+/// Any error without a more specific code; for `pomodoro start` and
+/// `pomodoro cancel`, the pomodoro was cancelled.
+pub const ERROR: i32 = 1;
+
+/// Invalid command-line usage. Reported by the argument parser.
+pub const USAGE: i32 = 2;
+
+/// Another pomodoro or break is already running.
+pub const ALREADY_RUNNING: i32 = 3;
+
+/// A `before-*` hook rejected the operation.
+pub const HOOK_REJECTED: i32 = 4;
+
+/// The requested entry or record does not exist.
+pub const NOT_FOUND: i32 = 5;
+
+/// The time range overlaps with an existing entry (Rule #1).
+pub const OVERLAP: i32 = 6;
+
+/// The data violates a database constraint, e.g. a duration out of range.
+pub const CONSTRAINT_VIOLATION: i32 = 7;
+
+/// The database is locked by another process.
+pub const DATABASE_LOCKED: i32 = 8;
+
+/// The database or another file could not be read or written.
+pub const IO: i32 = 9;
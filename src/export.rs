@@ -1,10 +1,327 @@
-use crate::persistence::Repository;
-use crate::{Annotation, Schedulable};
-use chrono::{Local, NaiveDate, TimeZone};
-use std::fmt::Write;
+use crate::persistence::{PersistenceError, Repository};
+use crate::timezone;
+use crate::{Annotation, InterruptLog, Kind, Schedulable, Status};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Version of the JSON and NDJSON export format, documented in
+/// `doc/export/schema/v1.json`. Bump it for changes that could break readers,
+/// such as renaming or removing a field; adding a field does not need a bump.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The header row of the CSV format. `rustomato import` expects exactly these columns.
+pub const CSV_HEADER: &str = "uuid,kind,planned_duration,started_at,finished_at,cancelled_at,\
+                              status,interruptions,elapsed_min,annotations";
+
+/// The columns of the CSV format of schedulables, in their default order.
+pub const COLUMNS: [&str; 10] = [
+    "uuid",
+    "kind",
+    "planned_duration",
+    "started_at",
+    "finished_at",
+    "cancelled_at",
+    "status",
+    "interruptions",
+    "elapsed_min",
+    "annotations",
+];
+
+/// The columns of the CSV format of the interrupt log.
+pub const INTERRUPT_COLUMNS: [&str; 5] = [
+    "uuid",
+    "schedulable_uuid",
+    "schedulable_kind",
+    "kind",
+    "created_at",
+];
+
+/// The columns of the CSV format of annotations.
+pub const ANNOTATION_COLUMNS: [&str; 5] = [
+    "uuid",
+    "schedulable_uuid",
+    "schedulable_kind",
+    "created_at",
+    "body",
+];
+
+/// The datasets the CSV format can be written for. Interrupts and
+/// annotations are those of the exported schedulables, one row each.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Table {
+    Schedulables,
+    Interrupts,
+    Annotations,
+}
+
+impl Table {
+    pub const ALL: [Table; 3] = [Table::Schedulables, Table::Interrupts, Table::Annotations];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Table::Schedulables => "schedulables",
+            Table::Interrupts => "interrupts",
+            Table::Annotations => "annotations",
+        }
+    }
+
+    pub fn columns(self) -> &'static [&'static str] {
+        match self {
+            Table::Schedulables => &COLUMNS,
+            Table::Interrupts => &INTERRUPT_COLUMNS,
+            Table::Annotations => &ANNOTATION_COLUMNS,
+        }
+    }
+}
+
+/// Output format of [`cmd_export`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Format {
+    /// One row per schedulable, annotations as a JSON column, or one row
+    /// per interrupt or annotation
+    Csv(Table, Dialect),
+    /// A single JSON document with all entries
+    Json,
+    /// One JSON object per line and schedulable
+    Ndjson,
+    /// An iCalendar file with one event per schedulable
+    Ics,
+    /// hledger/ledger timeclock sessions, one per pomodoro
+    Timeclock(crate::timeclock::Options),
+    /// An org-mode tree grouped by day, with clock lines
+    Org,
+}
+
+/// How the CSV format is written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dialect {
+    /// The columns to write, in this order; all if empty
+    pub columns: Vec<&'static str>,
+    pub delimiter: char,
+    pub header: bool,
+    /// Write timestamps in UTC (`Z`) instead of with the offset of the clock
+    pub utc: bool,
+}
+
+impl Default for Dialect {
+    fn default() -> Self {
+        Self {
+            columns: Vec::new(),
+            delimiter: ',',
+            header: true,
+            utc: false,
+        }
+    }
+}
+
+impl Dialect {
+    /// Select and order the columns of `table` by a comma-separated list of
+    /// their names.
+    pub fn with_columns(mut self, table: Table, names: &str) -> Result<Self, String> {
+        self.columns = names
+            .split(',')
+            .map(|name| {
+                table
+                    .columns()
+                    .iter()
+                    .find(|c| **c == name.trim())
+                    .copied()
+                    .ok_or_else(|| {
+                        format!(
+                            "unknown column '{}'; expected some of {}",
+                            name.trim(),
+                            table.columns().join(",")
+                        )
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(self)
+    }
+
+    fn columns(&self, table: Table) -> Vec<&'static str> {
+        if self.columns.is_empty() {
+            table.columns().to_vec()
+        } else {
+            self.columns.clone()
+        }
+    }
+
+    fn timestamp(&self, ts: i64) -> String {
+        if self.utc && ts != 0 {
+            chrono::DateTime::from_timestamp(ts, 0)
+                .map(|dt| dt.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+                .unwrap_or_default()
+        } else {
+            format_ts(ts)
+        }
+    }
+
+    /// Quote and join the fields of a row.
+    fn row(&self, fields: Vec<String>) -> String {
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|field| csv_quote(field, self.delimiter))
+            .collect();
+        fields.join(&self.delimiter.to_string())
+    }
+
+    /// Separate fields by `delimiter`, a single character or `tab`.
+    pub fn with_delimiter(mut self, delimiter: &str) -> Result<Self, String> {
+        let mut chars = delimiter.chars();
+        self.delimiter = match (delimiter, chars.next(), chars.next()) {
+            ("tab" | "\\t", _, _) => '\t',
+            (_, Some(c), None) if !matches!(c, '"' | '\n' | '\r') => c,
+            _ => {
+                return Err(format!(
+                    "invalid delimiter '{}'; expected a single character other than a quote, or 'tab'",
+                    delimiter
+                ));
+            }
+        };
+        Ok(self)
+    }
+}
+
+/// Which entries [`cmd_export`] writes, besides the date range. The default
+/// lets every entry pass.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Filter {
+    pub kind: Option<Kind>,
+    /// Any of these; all if empty
+    pub statuses: Vec<Status>,
+    pub min_interruptions: i64,
+    /// Text that one of the annotations must contain, ignoring case
+    pub grep: Option<String>,
+}
+
+impl Filter {
+    pub fn matches(&self, s: &Schedulable, annotations: &[Annotation]) -> bool {
+        self.kind.is_none_or(|kind| s.kind == kind)
+            && (self.statuses.is_empty() || self.statuses.contains(&s.status()))
+            && s.interruptions >= self.min_interruptions
+            && self.grep.as_ref().is_none_or(|text| {
+                let text = text.to_lowercase();
+                annotations
+                    .iter()
+                    .any(|a| a.body.to_lowercase().contains(&text))
+            })
+    }
+}
+
+/// A schedulable as written by the JSON and NDJSON formats.
+#[derive(Debug, Serialize)]
+pub struct Entry {
+    pub uuid: String,
+    pub kind: String,
+    pub status: &'static str,
+    pub planned_duration: i64,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+    pub cancelled_at: Option<String>,
+    pub elapsed_seconds: Option<i64>,
+    pub interruptions: i64,
+    pub annotations: Vec<AnnotationEntry>,
+    pub interrupts: Vec<InterruptEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnnotationEntry {
+    pub uuid: String,
+    pub body: String,
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InterruptEntry {
+    pub uuid: String,
+    pub kind: &'static str,
+    pub created_at: Option<String>,
+}
+
+/// Export the entries that pass `filter` to stdout in the given format.
+pub fn cmd_export(
+    repo: &Repository,
+    from: Option<&str>,
+    to: Option<&str>,
+    filter: &Filter,
+    format: Format,
+) {
+    if let Format::Timeclock(options) = &format
+        && let Err(e) = crate::timeclock::check_template(&options.account)
+    {
+        eprintln!("Error: invalid --account: {}.", e);
+        std::process::exit(1);
+    }
+
+    let entries = matching_entries(repo, from, to, filter);
 
-/// Export entries as CSV to stdout.
-pub fn cmd_export(repo: &Repository, from: Option<&str>, to: Option<&str>) {
+    let result = match format {
+        Format::Csv(table, dialect) => {
+            format_table(repo, &entries, table, &dialect).map(|csv| print!("{}", csv))
+        }
+        Format::Json => to_entries(repo, &entries).map(|e| println!("{}", format_json(&e))),
+        Format::Ndjson => to_entries(repo, &entries).map(|e| print!("{}", format_ndjson(&e))),
+        Format::Ics => {
+            with_annotations(repo, entries).map(|e| print!("{}", crate::ics::calendar(&e)))
+        }
+        Format::Org => with_annotations(repo, entries).map(|e| print!("{}", crate::org::tree(&e))),
+        Format::Timeclock(options) => with_annotations(repo, entries)
+            .map(|e| print!("{}", crate::timeclock::sessions(&e, &options))),
+    };
+    if let Err(e) = result {
+        eprintln!("Error: {}.", e);
+        std::process::exit(e.exit_code());
+    }
+}
+
+/// Export the entries that pass `filter`, their interrupts and their
+/// annotations as one CSV file per [`Table`] into `dir`, which is created if
+/// needed. Returns the paths written.
+pub fn cmd_export_bundle(
+    repo: &Repository,
+    from: Option<&str>,
+    to: Option<&str>,
+    filter: &Filter,
+    dialect: &Dialect,
+    dir: &Path,
+) -> Vec<PathBuf> {
+    let entries = matching_entries(repo, from, to, filter);
+    let tables = Table::ALL
+        .iter()
+        .map(|table| Ok((table, format_table(repo, &entries, *table, dialect)?)))
+        .collect::<Result<Vec<_>, PersistenceError>>()
+        .unwrap_or_else(|e| {
+            eprintln!("Error: {}.", e);
+            std::process::exit(e.exit_code());
+        });
+
+    let write = |path: &Path, csv: &str| {
+        std::fs::create_dir_all(dir)
+            .and_then(|_| std::fs::write(path, csv))
+            .unwrap_or_else(|e| {
+                eprintln!("Error: cannot write {}: {}.", path.display(), e);
+                std::process::exit(crate::exit_code::IO);
+            })
+    };
+    tables
+        .into_iter()
+        .map(|(table, csv)| {
+            let path = dir.join(format!("{}.csv", table.name()));
+            write(&path, &csv);
+            path
+        })
+        .collect()
+}
+
+/// The entries in the date range from `from` to `to` that pass `filter`.
+/// Exits on invalid dates and database errors.
+fn matching_entries(
+    repo: &Repository,
+    from: Option<&str>,
+    to: Option<&str>,
+    filter: &Filter,
+) -> Vec<Schedulable> {
     let start_ts = match from {
         Some(date_str) => {
             let date = NaiveDate::parse_from_str(date_str, "%Y-%m-%d").unwrap_or_else(|e| {
@@ -36,37 +353,189 @@ pub fn cmd_export(repo: &Repository, from: Option<&str>, to: Option<&str>) {
             .as_secs() as i64,
     };
 
-    let entries = repo.entries_between(start_ts, end_ts).unwrap_or_else(|e| {
-        eprintln!("Error: {}.", e);
-        std::process::exit(1);
-    });
+    repo.entries_between(start_ts, end_ts)
+        .and_then(|entries| apply_filter(repo, entries, filter))
+        .unwrap_or_else(|e| {
+            eprintln!("Error: {}.", e);
+            std::process::exit(e.exit_code());
+        })
+}
+
+/// Build the JSON representation of `entries`, including their annotations
+/// and interrupts.
+pub fn to_entries(
+    repo: &Repository,
+    entries: &[Schedulable],
+) -> Result<Vec<Entry>, PersistenceError> {
+    entries
+        .iter()
+        .map(|s| {
+            Ok(to_entry(
+                s,
+                &repo.annotations_for(s.uuid)?,
+                &repo.interrupts_for(s.uuid)?,
+            ))
+        })
+        .collect()
+}
+
+fn apply_filter(
+    repo: &Repository,
+    entries: Vec<Schedulable>,
+    filter: &Filter,
+) -> Result<Vec<Schedulable>, PersistenceError> {
+    if *filter == Filter::default() {
+        return Ok(entries);
+    }
+    let mut matching = Vec::new();
+    for s in entries {
+        // Only the text filter needs the annotations
+        let annotations = match filter.grep {
+            Some(_) => repo.annotations_for(s.uuid)?,
+            None => Vec::new(),
+        };
+        if filter.matches(&s, &annotations) {
+            matching.push(s);
+        }
+    }
+    Ok(matching)
+}
+
+/// Pair every entry with its annotations.
+fn with_annotations(
+    repo: &Repository,
+    entries: Vec<Schedulable>,
+) -> Result<Vec<(Schedulable, Vec<Annotation>)>, PersistenceError> {
+    entries
+        .into_iter()
+        .map(|s| {
+            let annotations = repo.annotations_for(s.uuid)?;
+            Ok((s, annotations))
+        })
+        .collect()
+}
+
+fn to_entry(s: &Schedulable, annotations: &[Annotation], interrupts: &[InterruptLog]) -> Entry {
+    Entry {
+        uuid: s.uuid.to_string(),
+        kind: s.kind.to_string(),
+        status: status_str(s),
+        planned_duration: s.duration,
+        started_at: optional_ts(s.started_at),
+        finished_at: optional_ts(s.finished_at),
+        cancelled_at: optional_ts(s.cancelled_at),
+        elapsed_seconds: elapsed_secs(s),
+        interruptions: s.interruptions,
+        annotations: annotations.iter().map(to_annotation_entry).collect(),
+        interrupts: interrupts
+            .iter()
+            .map(|i| InterruptEntry {
+                uuid: i.uuid.to_string(),
+                kind: i.kind.as_str(),
+                created_at: optional_ts(i.created_at),
+            })
+            .collect(),
+    }
+}
+
+fn to_annotation_entry(a: &Annotation) -> AnnotationEntry {
+    AnnotationEntry {
+        uuid: a.uuid.to_string(),
+        body: a.body.clone(),
+        created_at: optional_ts(a.created_at),
+    }
+}
+
+/// Render entries as a single, pretty-printed JSON document.
+pub fn format_json(entries: &[Entry]) -> String {
+    #[derive(Serialize)]
+    struct Document<'a> {
+        schema_version: u32,
+        entries: &'a [Entry],
+    }
+
+    serde_json::to_string_pretty(&Document {
+        schema_version: SCHEMA_VERSION,
+        entries,
+    })
+    .expect("serializing export")
+}
+
+/// Render entries as newline-delimited JSON, one object per line.
+pub fn format_ndjson(entries: &[Entry]) -> String {
+    #[derive(Serialize)]
+    struct Line<'a> {
+        schema_version: u32,
+        #[serde(flatten)]
+        entry: &'a Entry,
+    }
+
+    let mut out = String::new();
+    for entry in entries {
+        out.push_str(
+            &serde_json::to_string(&Line {
+                schema_version: SCHEMA_VERSION,
+                entry,
+            })
+            .expect("serializing export"),
+        );
+        out.push('\n');
+    }
+    out
+}
 
-    // CSV header
-    println!(
-        "uuid,kind,planned_duration,started_at,finished_at,cancelled_at,\
-         status,interruptions,elapsed_min,annotations"
-    );
+/// Render `table` for `entries` as CSV, including the header unless the
+/// dialect leaves it out.
+fn format_table(
+    repo: &Repository,
+    entries: &[Schedulable],
+    table: Table,
+    dialect: &Dialect,
+) -> Result<String, PersistenceError> {
+    let columns = dialect.columns(table);
+    let mut out = String::new();
+    if dialect.header {
+        out.push_str(&columns.join(&dialect.delimiter.to_string()));
+        out.push('\n');
+    }
 
-    for entry in &entries {
-        let annotations = repo.annotations_for(entry.uuid).unwrap_or_default();
-        println!("{}", format_row(entry, &annotations));
+    for entry in entries {
+        match table {
+            Table::Schedulables => {
+                let annotations = repo.annotations_for(entry.uuid)?;
+                out.push_str(&format_row(entry, &annotations, dialect));
+                out.push('\n');
+            }
+            Table::Interrupts => {
+                for interrupt in repo.interrupts_for(entry.uuid)? {
+                    out.push_str(&format_interrupt_row(entry, &interrupt, &columns, dialect));
+                    out.push('\n');
+                }
+            }
+            Table::Annotations => {
+                for annotation in repo.annotations_for(entry.uuid)? {
+                    out.push_str(&format_annotation_row(
+                        entry,
+                        &annotation,
+                        &columns,
+                        dialect,
+                    ));
+                    out.push('\n');
+                }
+            }
+        }
     }
+    Ok(out)
 }
 
 // ── Helpers ────────────────────────────────────────────────────
 
 fn day_start_ts(date: NaiveDate) -> i64 {
-    date.and_hms_opt(0, 0, 0)
-        .and_then(|dt| dt.and_local_timezone(Local).earliest())
-        .map(|dt| dt.timestamp())
-        .unwrap_or(0)
+    timezone::clock().day_bounds(date).0
 }
 
 fn day_end_ts(date: NaiveDate) -> i64 {
-    date.and_hms_opt(23, 59, 59)
-        .and_then(|dt| dt.and_local_timezone(Local).earliest())
-        .map(|dt| dt.timestamp())
-        .unwrap_or(i64::MAX)
+    timezone::clock().day_bounds(date).1
 }
 
 /// Format a Unix timestamp as ISO 8601 with timezone offset, or empty string for 0.
@@ -74,33 +543,36 @@ fn format_ts(ts: i64) -> String {
     if ts == 0 {
         return String::new();
     }
-    Local
-        .timestamp_opt(ts, 0)
-        .single()
-        .map(|dt| dt.format("%+").to_string())
-        .unwrap_or_else(|| ts.to_string())
+    timezone::clock().format(ts, "%+")
+}
+
+fn optional_ts(ts: i64) -> Option<String> {
+    (ts != 0).then(|| format_ts(ts))
 }
 
 fn status_str(s: &Schedulable) -> &'static str {
     s.status().as_str()
 }
 
-/// Compute elapsed time in minutes (based on finished_at or cancelled_at).
-/// Returns empty if the entry is still active or new.
-fn elapsed_min(s: &Schedulable) -> String {
+/// Compute elapsed time in seconds (based on finished_at or cancelled_at).
+/// Returns `None` if the entry is still active or new.
+fn elapsed_secs(s: &Schedulable) -> Option<i64> {
     let end = if s.finished_at != 0 {
         s.finished_at
     } else if s.cancelled_at != 0 {
         s.cancelled_at
     } else {
-        return String::new();
+        return None;
     };
-    let secs = end - s.started_at;
-    if secs >= 0 {
-        (secs / 60).to_string()
-    } else {
-        String::new()
-    }
+    Some(end - s.started_at).filter(|secs| *secs >= 0)
+}
+
+/// Compute elapsed time in minutes (based on finished_at or cancelled_at).
+/// Returns empty if the entry is still active or new.
+fn elapsed_min(s: &Schedulable) -> String {
+    elapsed_secs(s)
+        .map(|secs| (secs / 60).to_string())
+        .unwrap_or_default()
 }
 
 /// Build a JSON array of annotation objects. Empty string when there are no annotations.
@@ -111,48 +583,17 @@ fn format_annotations_json(annotations: &[Annotation]) -> String {
         return String::new();
     }
 
-    let mut json = String::from('[');
-    for (i, ann) in annotations.iter().enumerate() {
-        if i > 0 {
-            json.push(',');
-        }
-        json.push('{');
-        write!(
-            json,
-            r#""uuid":"{}","body":"{}","created_at":"{}""#,
-            ann.uuid,
-            escape_json_string(&ann.body),
-            format_ts(ann.created_at),
-        )
-        .unwrap();
-        json.push('}');
-    }
-    json.push(']');
-    json
-}
-
-/// Escape a string for JSON. Handles quotes, backslashes, newlines, and other control chars.
-fn escape_json_string(s: &str) -> String {
-    let mut escaped = String::with_capacity(s.len());
-    for ch in s.chars() {
-        match ch {
-            '"' => escaped.push_str(r#"\""#),
-            '\\' => escaped.push_str(r#"\\"#),
-            '\n' => escaped.push_str(r#"\n"#),
-            '\r' => escaped.push_str(r#"\r"#),
-            '\t' => escaped.push_str(r#"\t"#),
-            c if c.is_control() => {
-                let _ = write!(escaped, "\\u{:04x}", c as u32);
-            }
-            c => escaped.push(c),
-        }
-    }
-    escaped
+    let entries: Vec<AnnotationEntry> = annotations.iter().map(to_annotation_entry).collect();
+    serde_json::to_string(&entries).expect("serializing annotations")
 }
 
-/// Quote a CSV field if it contains commas, double quotes, or newlines.
-fn csv_quote(field: &str) -> String {
-    if field.contains(',') || field.contains('"') || field.contains('\n') {
+/// Quote a CSV field if it contains the delimiter, double quotes, or line breaks.
+fn csv_quote(field: &str, delimiter: char) -> String {
+    if field.contains(delimiter)
+        || field.contains('"')
+        || field.contains('\n')
+        || field.contains('\r')
+    {
         let mut escaped = String::with_capacity(field.len() + 2);
         escaped.push('"');
         for ch in field.chars() {
@@ -170,21 +611,67 @@ fn csv_quote(field: &str) -> String {
 }
 
 /// Format one CSV row from a schedulable and its annotations.
-fn format_row(s: &Schedulable, annotations: &[Annotation]) -> String {
-    let ann_json = format_annotations_json(annotations);
-    format!(
-        "{},{},{},{},{},{},{},{},{},{}",
-        s.uuid,
-        s.kind,
-        s.duration,
-        format_ts(s.started_at),
-        format_ts(s.finished_at),
-        format_ts(s.cancelled_at),
-        status_str(s),
-        s.interruptions,
-        elapsed_min(s),
-        csv_quote(&ann_json),
-    )
+fn format_row(s: &Schedulable, annotations: &[Annotation], dialect: &Dialect) -> String {
+    let fields = dialect
+        .columns(Table::Schedulables)
+        .iter()
+        .map(|column| match *column {
+            "uuid" => s.uuid.to_string(),
+            "kind" => s.kind.to_string(),
+            "planned_duration" => s.duration.to_string(),
+            "started_at" => dialect.timestamp(s.started_at),
+            "finished_at" => dialect.timestamp(s.finished_at),
+            "cancelled_at" => dialect.timestamp(s.cancelled_at),
+            "status" => status_str(s).to_string(),
+            "interruptions" => s.interruptions.to_string(),
+            "elapsed_min" => elapsed_min(s),
+            "annotations" => format_annotations_json(annotations),
+            other => unreachable!("unknown column {}", other),
+        })
+        .collect();
+    dialect.row(fields)
+}
+
+/// Format one CSV row from an interrupt and the schedulable it belongs to.
+fn format_interrupt_row(
+    s: &Schedulable,
+    interrupt: &InterruptLog,
+    columns: &[&str],
+    dialect: &Dialect,
+) -> String {
+    let fields = columns
+        .iter()
+        .map(|column| match *column {
+            "uuid" => interrupt.uuid.to_string(),
+            "schedulable_uuid" => s.uuid.to_string(),
+            "schedulable_kind" => s.kind.to_string(),
+            "kind" => interrupt.kind.as_str().to_string(),
+            "created_at" => dialect.timestamp(interrupt.created_at),
+            other => unreachable!("unknown column {}", other),
+        })
+        .collect();
+    dialect.row(fields)
+}
+
+/// Format one CSV row from an annotation and the schedulable it belongs to.
+fn format_annotation_row(
+    s: &Schedulable,
+    annotation: &Annotation,
+    columns: &[&str],
+    dialect: &Dialect,
+) -> String {
+    let fields = columns
+        .iter()
+        .map(|column| match *column {
+            "uuid" => annotation.uuid.to_string(),
+            "schedulable_uuid" => s.uuid.to_string(),
+            "schedulable_kind" => s.kind.to_string(),
+            "created_at" => dialect.timestamp(annotation.created_at),
+            "body" => annotation.body.clone(),
+            other => unreachable!("unknown column {}", other),
+        })
+        .collect();
+    dialect.row(fields)
 }
 
 #[cfg(test)]
@@ -224,31 +711,17 @@ mod tests {
         assert_eq!(status_str(&s), "new");
     }
 
-    #[test]
-    fn test_escape_json_simple() {
-        assert_eq!(escape_json_string("hello"), "hello");
-    }
-
-    #[test]
-    fn test_escape_json_quotes() {
-        assert_eq!(escape_json_string("say \"hi\""), r#"say \"hi\""#);
-    }
-
-    #[test]
-    fn test_escape_json_newline() {
-        assert_eq!(escape_json_string("a\nb"), r"a\nb");
-    }
-
     #[test]
     fn test_csv_quote_needed() {
-        assert_eq!(csv_quote("a,b"), r#""a,b""#);
-        assert_eq!(csv_quote("say \"hi\""), r#""say ""hi""""#);
+        assert_eq!(csv_quote("a,b", ','), r#""a,b""#);
+        assert_eq!(csv_quote("say \"hi\"", ','), r#""say ""hi""""#);
+        assert_eq!(csv_quote("line\r\nbreak", ','), "\"line\r\nbreak\"");
     }
 
     #[test]
     fn test_csv_quote_not_needed() {
-        assert_eq!(csv_quote("hello"), "hello");
-        assert_eq!(csv_quote("42"), "42");
+        assert_eq!(csv_quote("hello", ','), "hello");
+        assert_eq!(csv_quote("42", ','), "42");
     }
 
     #[test]
@@ -287,7 +760,7 @@ mod tests {
         let mut s = Schedulable::new(42, crate::Kind::Pomodoro, 25);
         s.started_at = 1000;
         s.finished_at = 2500;
-        let row = format_row(&s, &[]);
+        let row = format_row(&s, &[], &Dialect::default());
         assert!(row.contains("pomodoro"));
         assert!(row.contains("25")); // planned_duration
         assert!(row.contains("finished"));
@@ -304,9 +777,144 @@ mod tests {
             body: "note".to_string(),
             created_at: 1500,
         };
-        let row = format_row(&s, &[ann]);
+        let row = format_row(&s, &[ann], &Dialect::default());
         assert!(row.contains("note"));
         // JSON keys are CSV-escaped (""body""), so check for the body value only
         assert!(!row.contains("\"body\":\"note\"")); // would be raw JSON, not CSV
     }
+
+    #[test]
+    fn test_columns_match_header() {
+        assert_eq!(COLUMNS.join(","), CSV_HEADER);
+    }
+
+    #[test]
+    fn test_format_row_dialect() {
+        let mut s = Schedulable::new(1, crate::Kind::Pomodoro, 25);
+        s.started_at = 1_780_048_800;
+        s.finished_at = s.started_at + 1500;
+        let dialect = Dialect {
+            utc: true,
+            ..Dialect::default()
+        }
+        .with_columns(Table::Schedulables, "status, started_at,elapsed_min")
+        .unwrap()
+        .with_delimiter("tab")
+        .unwrap();
+
+        assert_eq!(
+            format_row(&s, &[], &dialect),
+            "finished\t2026-05-29T10:00:00Z\t25"
+        );
+    }
+
+    #[test]
+    fn test_dialect_rejects_unknown_column_and_bad_delimiter() {
+        assert!(
+            Dialect::default()
+                .with_columns(Table::Schedulables, "uuid,project")
+                .is_err()
+        );
+        assert!(
+            Dialect::default()
+                .with_columns(Table::Interrupts, "schedulable_kind,kind")
+                .is_ok()
+        );
+        assert!(Dialect::default().with_delimiter("\"").is_err());
+        assert!(Dialect::default().with_delimiter(";;").is_err());
+        assert_eq!(
+            Dialect::default().with_delimiter(";").unwrap().delimiter,
+            ';'
+        );
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let mut s = Schedulable::new(1, crate::Kind::Pomodoro, 25);
+        s.started_at = 1000;
+        s.finished_at = 2500;
+        s.interruptions = 2;
+        let ann = Annotation {
+            uuid: crate::SqlUuid::default(),
+            schedulable_uuid: s.uuid,
+            body: "Fixed the Parser".to_string(),
+            created_at: 2500,
+        };
+
+        assert!(Filter::default().matches(&s, &[]));
+        let filter = Filter {
+            kind: Some(Kind::Pomodoro),
+            statuses: vec![Status::Finished, Status::Cancelled],
+            min_interruptions: 2,
+            grep: Some("parser".to_string()),
+        };
+        assert!(filter.matches(&s, std::slice::from_ref(&ann)));
+        assert!(!filter.matches(&s, &[]));
+        assert!(
+            !Filter {
+                kind: Some(Kind::Break),
+                ..Filter::default()
+            }
+            .matches(&s, &[])
+        );
+        assert!(
+            !Filter {
+                min_interruptions: 3,
+                ..Filter::default()
+            }
+            .matches(&s, &[])
+        );
+        assert!(
+            !Filter {
+                statuses: vec![Status::Cancelled],
+                ..Filter::default()
+            }
+            .matches(&s, &[])
+        );
+    }
+
+    fn finished_entry() -> Entry {
+        let mut s = Schedulable::new(1, crate::Kind::Pomodoro, 25);
+        s.started_at = 1000;
+        s.finished_at = 2500;
+        let ann = Annotation {
+            uuid: crate::SqlUuid::default(),
+            schedulable_uuid: s.uuid,
+            body: "say \"hi\"\n".to_string(),
+            created_at: 1500,
+        };
+        let interrupt = InterruptLog {
+            uuid: crate::SqlUuid::default(),
+            schedulable_uuid: s.uuid,
+            kind: crate::InterruptionKind::External,
+            created_at: 1200,
+        };
+        to_entry(&s, &[ann], &[interrupt])
+    }
+
+    #[test]
+    fn test_format_json_document() {
+        let json: serde_json::Value =
+            serde_json::from_str(&format_json(&[finished_entry()])).unwrap();
+
+        assert_eq!(json["schema_version"], SCHEMA_VERSION);
+        let entry = &json["entries"][0];
+        assert_eq!(entry["kind"], "pomodoro");
+        assert_eq!(entry["status"], "finished");
+        assert_eq!(entry["elapsed_seconds"], 1500);
+        assert!(entry["cancelled_at"].is_null());
+        assert_eq!(entry["annotations"][0]["body"], "say \"hi\"\n");
+        assert_eq!(entry["interrupts"][0]["kind"], "external");
+    }
+
+    #[test]
+    fn test_format_ndjson_one_line_per_entry() {
+        let ndjson = format_ndjson(&[finished_entry(), finished_entry()]);
+        let lines: Vec<&str> = ndjson.lines().collect();
+        assert_eq!(lines.len(), 2);
+
+        let first: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first["schema_version"], SCHEMA_VERSION);
+        assert_eq!(first["planned_duration"], 25);
+    }
 }
@@ -324,7 +324,7 @@ pub fn init(root: &Path) -> std::io::Result<()> {
     Ok(())
 }
 
-fn sample_hook_content(hook_name: &str) -> String {
+pub(crate) fn sample_hook_content(hook_name: &str) -> String {
     format!(
         r#"#!/usr/bin/env sh
 # rustomato hook: {}
@@ -0,0 +1,164 @@
+//! iCalendar (RFC 5545) output for `export --format ics`.
+
+use crate::{Annotation, Kind, Schedulable, Status};
+
+/// Render a VCALENDAR with one VEVENT per schedulable. Times are written in
+/// UTC, so calendar apps show them in their own zone.
+pub fn calendar(entries: &[(Schedulable, Vec<Annotation>)]) -> String {
+    let mut out = String::new();
+    line(&mut out, "BEGIN:VCALENDAR");
+    line(&mut out, "VERSION:2.0");
+    line(
+        &mut out,
+        &format!(
+            "PRODID:-//rustomato//rustomato {}//EN",
+            env!("CARGO_PKG_VERSION")
+        ),
+    );
+    line(&mut out, "CALSCALE:GREGORIAN");
+    for (s, annotations) in entries {
+        event(&mut out, s, annotations);
+    }
+    line(&mut out, "END:VCALENDAR");
+    out
+}
+
+fn event(out: &mut String, s: &Schedulable, annotations: &[Annotation]) {
+    let kind = match s.kind {
+        Kind::Pomodoro => "Pomodoro",
+        Kind::Break => "Break",
+    };
+    let (end, summary, status) = match s.status() {
+        Status::Finished => (s.finished_at, kind.to_string(), "CONFIRMED"),
+        Status::Cancelled => (s.cancelled_at, format!("{} (cancelled)", kind), "CANCELLED"),
+        // Still running (or never ended); show the planned time
+        _ => (
+            s.started_at + s.duration * 60,
+            format!("{} ({})", kind, s.status().as_str()),
+            "TENTATIVE",
+        ),
+    };
+
+    line(out, "BEGIN:VEVENT");
+    line(out, &format!("UID:{}", s.uuid));
+    // The entry does not change after it has ended, so its end is a stable
+    // stamp that keeps repeated exports identical.
+    line(out, &format!("DTSTAMP:{}", utc(end)));
+    line(out, &format!("DTSTART:{}", utc(s.started_at)));
+    line(out, &format!("DTEND:{}", utc(end)));
+    line(out, &format!("SUMMARY:{}", escape_text(&summary)));
+    line(out, &format!("STATUS:{}", status));
+    line(out, &format!("CATEGORIES:{}", s.kind));
+    if !annotations.is_empty() {
+        let description: Vec<&str> = annotations.iter().map(|a| a.body.as_str()).collect();
+        line(
+            out,
+            &format!("DESCRIPTION:{}", escape_text(&description.join("\n"))),
+        );
+    }
+    line(out, "END:VEVENT");
+}
+
+/// Format a Unix timestamp as an iCalendar UTC date-time, e.g. `20260529T100000Z`.
+fn utc(ts: i64) -> String {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|dt| dt.format("%Y%m%dT%H%M%SZ").to_string())
+        .unwrap_or_else(|| ts.to_string())
+}
+
+/// Escape a TEXT value: backslashes, semicolons, commas and newlines.
+fn escape_text(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Append a content line, folded into chunks of at most 75 octets and
+/// terminated by CRLF. Continuation lines start with a space.
+fn line(out: &mut String, content: &str) {
+    let mut len = 0;
+    for ch in content.chars() {
+        if len + ch.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            len = 1;
+        }
+        out.push(ch);
+        len += ch.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pomodoro(started_at: i64) -> Schedulable {
+        let mut s = Schedulable::new(1, Kind::Pomodoro, 25);
+        s.started_at = started_at;
+        s
+    }
+
+    #[test]
+    fn test_escape_text() {
+        assert_eq!(escape_text("a;b,c\\d\ne"), "a\\;b\\,c\\\\d\\ne");
+    }
+
+    #[test]
+    fn test_fold_long_lines() {
+        let mut out = String::new();
+        line(&mut out, &format!("DESCRIPTION:{}", "ä".repeat(50)));
+
+        for physical in out.split("\r\n").filter(|l| !l.is_empty()) {
+            assert!(physical.len() <= 75, "{:?} is too long", physical);
+        }
+        assert_eq!(
+            out.replace("\r\n ", ""),
+            format!("DESCRIPTION:{}\r\n", "ä".repeat(50))
+        );
+    }
+
+    #[test]
+    fn test_finished_event() {
+        let mut s = pomodoro(1_780_048_800); // 2026-05-29T10:00:00Z
+        s.finished_at = s.started_at + 1500;
+        let ann = Annotation {
+            uuid: crate::SqlUuid::default(),
+            schedulable_uuid: s.uuid,
+            body: "wrote tests, fixed bugs".to_string(),
+            created_at: s.finished_at,
+        };
+
+        let ics = calendar(&[(s.clone(), vec![ann])]);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains(&format!("UID:{}\r\n", s.uuid)));
+        assert!(ics.contains("DTSTART:20260529T100000Z\r\n"));
+        assert!(ics.contains("DTEND:20260529T102500Z\r\n"));
+        assert!(ics.contains("SUMMARY:Pomodoro\r\n"));
+        assert!(ics.contains("STATUS:CONFIRMED\r\n"));
+        assert!(ics.contains("DESCRIPTION:wrote tests\\, fixed bugs\r\n"));
+    }
+
+    #[test]
+    fn test_cancelled_event() {
+        let mut s = pomodoro(1_780_048_800);
+        s.cancelled_at = s.started_at + 600;
+
+        let ics = calendar(&[(s, vec![])]);
+
+        assert!(ics.contains("DTEND:20260529T101000Z\r\n"));
+        assert!(ics.contains("SUMMARY:Pomodoro (cancelled)\r\n"));
+        assert!(ics.contains("STATUS:CANCELLED\r\n"));
+        assert!(!ics.contains("DESCRIPTION"));
+    }
+}
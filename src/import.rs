@@ -0,0 +1,588 @@
+use crate::export::{AnnotationEntry, CSV_HEADER};
+use crate::persistence::{PersistenceError, Repository};
+use crate::{
+    Annotation, InterruptLog, InterruptionKind, Kind, Schedulable, SqlUuid, Status, plural,
+    timeclock, timewarrior, timezone,
+};
+use std::fmt;
+use uuid::Uuid;
+
+/// The longest entry the database accepts, in minutes.
+const MAX_MINUTES: i64 = 480;
+
+/// The formats [`parse`] understands.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// CSV as written by `rustomato export`
+    Rustomato,
+    /// A Timewarrior data file, e.g. `~/.timewarrior/data/2026-05.data`
+    Timewarrior,
+    /// An hledger or ledger timeclock file
+    Timeclock,
+    /// CSV with the columns `start,end,kind,note`
+    Csv,
+}
+
+/// A schedulable read from an export, with its annotations.
+#[derive(Debug)]
+pub struct Record {
+    /// Line in the file where the record starts, for error messages.
+    pub line: usize,
+    pub entry: Schedulable,
+    pub annotations: Vec<Annotation>,
+}
+
+/// A record that cannot be imported without breaking Rule #1 or replacing
+/// a different entry with the same UUID.
+#[derive(Debug, PartialEq)]
+pub struct Conflict {
+    pub line: usize,
+    pub uuid: String,
+    pub reason: String,
+}
+
+/// What [`import`] did, or would do on a dry run.
+#[derive(Debug, Default, PartialEq)]
+pub struct Summary {
+    pub added: usize,
+    pub duplicates: usize,
+    pub running: usize,
+    pub annotations_added: usize,
+    pub interrupts_added: usize,
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Parse `text` in the given format. Errors name the offending line.
+pub fn parse(text: &str, format: Format) -> Result<Vec<Record>, String> {
+    match format {
+        Format::Rustomato => parse_export(text),
+        Format::Timewarrior => timewarrior::parse(text),
+        Format::Timeclock => timeclock::parse(text),
+        Format::Csv => parse_generic_csv(text),
+    }
+}
+
+/// Parse CSV as written by `rustomato export`. The header must match the
+/// export format exactly. The derived `status` and `elapsed_min` columns are
+/// ignored; they are computed from the timestamps.
+fn parse_export(text: &str) -> Result<Vec<Record>, String> {
+    let mut rows = split_csv(text)?.into_iter();
+
+    let expected: Vec<&str> = CSV_HEADER.split(',').collect();
+    match rows.next() {
+        Some((_, header)) if header == expected => {}
+        Some((line, header)) => {
+            return Err(format!(
+                "line {}: unexpected header '{}'; expected '{}'",
+                line,
+                header.join(","),
+                CSV_HEADER
+            ));
+        }
+        None => return Err("the file is empty".to_string()),
+    }
+
+    rows.filter(|(_, fields)| fields.iter().any(|f| !f.is_empty()))
+        .map(|(line, fields)| {
+            parse_record(line, &fields).map_err(|e| format!("line {}: {}", line, e))
+        })
+        .collect()
+}
+
+/// Parse CSV with a header naming at least the `start` and `end` columns, and
+/// optionally `kind` (`pomodoro` unless given) and `note`. Timestamps take the
+/// same forms as `--started-at`. An empty `end` means the entry is running.
+fn parse_generic_csv(text: &str) -> Result<Vec<Record>, String> {
+    let mut rows = split_csv(text)?.into_iter();
+    let Some((_, header)) = rows.next() else {
+        return Err("the file is empty".to_string());
+    };
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|h| h.trim().eq_ignore_ascii_case(name))
+    };
+    let (Some(start), Some(end)) = (column("start"), column("end")) else {
+        return Err(format!(
+            "line 1: unexpected header '{}'; expected at least 'start,end'",
+            header.join(",")
+        ));
+    };
+    let (kind, note) = (column("kind"), column("note"));
+
+    rows.filter(|(_, fields)| fields.iter().any(|f| !f.is_empty()))
+        .map(|(line, fields)| {
+            let field = |i: Option<usize>| {
+                i.and_then(|i| fields.get(i))
+                    .map(|f| f.trim())
+                    .unwrap_or_default()
+            };
+            let parse = || {
+                let started_at = crate::parse_timestamp(field(Some(start)))?;
+                let ended_at = match field(Some(end)) {
+                    "" => None,
+                    end => Some(crate::parse_timestamp(end)?),
+                };
+                let kind = match field(kind) {
+                    "" => Kind::Pomodoro,
+                    kind => {
+                        Kind::from(kind).map_err(|e| format!("unknown kind '{}'", e.offender))?
+                    }
+                };
+                let note = Some(field(note).to_string()).filter(|n| !n.is_empty());
+                external(line, kind, started_at, ended_at, note)
+            };
+            parse().map_err(|e| format!("line {}: {}", line, e))
+        })
+        .collect()
+}
+
+/// Build a record for an entry tracked by another tool. Other tools do not
+/// plan ahead, so the planned duration is the actual one. Entries longer than
+/// the database allows are rejected rather than truncated; entries without an
+/// end are still running.
+pub(crate) fn external(
+    line: usize,
+    kind: Kind,
+    started_at: i64,
+    ended_at: Option<i64>,
+    note: Option<String>,
+) -> Result<Record, String> {
+    if let Some(ended_at) = ended_at
+        && ended_at < started_at
+    {
+        return Err("the entry ends before it starts".to_string());
+    }
+
+    let minutes = ended_at.map_or(25, |end| (end - started_at + 30) / 60);
+    if minutes > MAX_MINUTES {
+        return Err(format!(
+            "the entry lasts {} minutes, longer than the {} minutes an entry may last",
+            minutes, MAX_MINUTES
+        ));
+    }
+    let mut entry = Schedulable::new(0, kind, minutes.max(1));
+    entry.started_at = started_at;
+    entry.finished_at = ended_at.unwrap_or(0);
+    entry.utc_offset = Some(timezone::offset_to_record(started_at));
+
+    let annotations = note
+        .map(|body| Annotation {
+            uuid: SqlUuid::default(),
+            schedulable_uuid: entry.uuid,
+            body,
+            created_at: ended_at.unwrap_or(started_at),
+        })
+        .into_iter()
+        .collect();
+
+    Ok(Record {
+        line,
+        entry,
+        annotations,
+    })
+}
+
+/// The kind of an entry from another tool, judging by its tags or account:
+/// anything mentioning "break" is a break.
+pub(crate) fn kind_of(labels: &[String]) -> Kind {
+    if labels.iter().any(|l| l.to_lowercase().contains("break")) {
+        Kind::Break
+    } else {
+        Kind::Pomodoro
+    }
+}
+
+/// Combine the tags or account and the description of an entry from another
+/// tool into the body of an annotation, so that nothing is lost. Labels that
+/// only name the kind are left out.
+pub(crate) fn note_of(labels: &[String], text: &str) -> Option<String> {
+    let labels: Vec<&str> = labels
+        .iter()
+        .map(|l| l.as_str())
+        .filter(|l| Kind::from(l).is_err())
+        .collect();
+    let parts: Vec<String> = [labels.join(", "), text.trim().to_string()]
+        .into_iter()
+        .filter(|p| !p.is_empty())
+        .collect();
+    Some(parts.join(": ")).filter(|n| !n.is_empty())
+}
+
+/// Import `records` into `repo`, keeping their UUIDs.
+///
+/// Exact duplicates are skipped, and so are entries that had not ended when
+/// they were exported. Entries from other tools get new UUIDs on every
+/// parse, so for them an existing entry of the same kind and time range
+/// counts as a duplicate. Annotations are added unless their UUID exists. If
+/// any record conflicts with an existing entry or another record, nothing is
+/// imported and the conflicts are returned. With `dry_run`, nothing is
+/// imported either way.
+pub fn import(
+    repo: &Repository,
+    records: &[Record],
+    dry_run: bool,
+) -> Result<Summary, PersistenceError> {
+    let summary = repo.rehearse(|| apply(repo, records))?;
+    if dry_run || !summary.conflicts.is_empty() {
+        return Ok(summary);
+    }
+
+    repo.transaction(|| {
+        let summary = apply(repo, records)?;
+        if summary.conflicts.is_empty() {
+            Ok(summary)
+        } else {
+            // Someone else wrote in between; try again.
+            Err(PersistenceError::OverlappingTimeRange)
+        }
+    })
+}
+
+fn apply(repo: &Repository, records: &[Record]) -> Result<Summary, PersistenceError> {
+    let mut summary = Summary::default();
+
+    for record in records {
+        let entry = &record.entry;
+        if !matches!(entry.status(), Status::Finished | Status::Cancelled) {
+            summary.running += 1;
+            continue;
+        }
+
+        match repo.find_by_uuid(entry.uuid) {
+            Ok(existing) if crate::merge::same_entry(&existing, entry) => summary.duplicates += 1,
+            Ok(_) => {
+                summary.conflicts.push(Conflict {
+                    line: record.line,
+                    uuid: entry.uuid.to_string(),
+                    reason: "a different entry with this UUID exists".to_string(),
+                });
+                continue;
+            }
+            Err(_) => match save(repo, entry) {
+                Ok(_) => {
+                    summary.added += 1;
+                    summary.interrupts_added += log_interruptions(repo, entry)?;
+                }
+                Err(PersistenceError::OverlappingTimeRange) => {
+                    let end = entry.end();
+                    let overlapping = repo.overlapping(entry.started_at, end)?;
+                    if overlapping.iter().any(|o| {
+                        o.kind == entry.kind && o.started_at == entry.started_at && o.end() == end
+                    }) {
+                        summary.duplicates += 1;
+                        continue;
+                    }
+                    let others: Vec<String> =
+                        overlapping.iter().map(|o| o.uuid.to_string()).collect();
+                    summary.conflicts.push(Conflict {
+                        line: record.line,
+                        uuid: entry.uuid.to_string(),
+                        reason: format!("overlaps {} (Rule #1)", others.join(", ")),
+                    });
+                    continue;
+                }
+                Err(e) => return Err(e),
+            },
+        }
+
+        for annotation in &record.annotations {
+            if repo.find_annotation_by_uuid(annotation.uuid).is_err() {
+                repo.save_annotation(annotation)?;
+                summary.annotations_added += 1;
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Insert an ended entry; finished ones go the same way as `pomodoro log`.
+fn save(repo: &Repository, entry: &Schedulable) -> Result<Schedulable, PersistenceError> {
+    if matches!(entry.status(), Status::Finished) {
+        repo.save_external_finished(entry)
+    } else {
+        repo.save_external_ended(entry)
+    }
+}
+
+/// Exports only carry the interruption counter, so log one interrupt per
+/// counted interruption to keep the counter and the interrupt log in step (as
+/// `doctor` checks). They are logged as internal interruptions, the default of
+/// `pomodoro interrupt`, spread evenly over the entry.
+fn log_interruptions(repo: &Repository, entry: &Schedulable) -> Result<usize, PersistenceError> {
+    let count = entry.interruptions.max(0);
+    let span = entry.end() - entry.started_at;
+    for i in 1..=count {
+        repo.save_interrupt(&InterruptLog {
+            uuid: SqlUuid::default(),
+            schedulable_uuid: entry.uuid,
+            kind: InterruptionKind::Internal,
+            created_at: entry.started_at + span * i / (count + 1),
+        })?;
+    }
+    Ok(count as usize)
+}
+
+fn parse_record(line: usize, fields: &[String]) -> Result<Record, String> {
+    let [
+        uuid,
+        kind,
+        duration,
+        started_at,
+        finished_at,
+        cancelled_at,
+        _status,
+        interruptions,
+        _elapsed_min,
+        annotations,
+    ] = fields
+    else {
+        return Err(format!(
+            "expected {} columns, found {}",
+            CSV_HEADER.split(',').count(),
+            fields.len()
+        ));
+    };
+
+    let uuid = parse_uuid(uuid)?;
+    let (started_at, utc_offset) = match parse_ts(started_at, "started_at")? {
+        Some(started) => started,
+        None => return Err("started_at must not be empty".to_string()),
+    };
+
+    let mut entry = Schedulable::new(
+        0,
+        Kind::from(kind).map_err(|e| format!("unknown kind '{}'", e.offender))?,
+        duration
+            .parse()
+            .map_err(|_| format!("invalid planned_duration '{}'", duration))?,
+    );
+    entry.uuid = uuid;
+    entry.started_at = started_at;
+    entry.utc_offset = Some(utc_offset);
+    entry.finished_at = parse_ts(finished_at, "finished_at")?.map_or(0, |(ts, _)| ts);
+    entry.cancelled_at = parse_ts(cancelled_at, "cancelled_at")?.map_or(0, |(ts, _)| ts);
+    entry.interruptions = interruptions
+        .parse()
+        .map_err(|_| format!("invalid interruptions '{}'", interruptions))?;
+
+    let annotations = if annotations.is_empty() {
+        Vec::new()
+    } else {
+        serde_json::from_str::<Vec<AnnotationEntry>>(annotations)
+            .map_err(|e| format!("invalid annotations: {}", e))?
+            .into_iter()
+            .map(|a| {
+                Ok(Annotation {
+                    uuid: parse_uuid(&a.uuid)?,
+                    schedulable_uuid: uuid,
+                    body: a.body,
+                    created_at: match a.created_at {
+                        Some(ts) => parse_ts(&ts, "annotation created_at")?.map_or(0, |(ts, _)| ts),
+                        None => return Err("annotation created_at must not be null".to_string()),
+                    },
+                })
+            })
+            .collect::<Result<_, String>>()?
+    };
+
+    Ok(Record {
+        line,
+        entry,
+        annotations,
+    })
+}
+
+fn parse_uuid(s: &str) -> Result<SqlUuid, String> {
+    Uuid::parse_str(s)
+        .map(SqlUuid)
+        .map_err(|e| format!("invalid UUID '{}': {}", s, e))
+}
+
+/// Parse an RFC 3339 timestamp into Unix seconds and its UTC offset.
+/// Empty fields are `None`.
+fn parse_ts(s: &str, column: &str) -> Result<Option<(i64, i32)>, String> {
+    if s.is_empty() {
+        return Ok(None);
+    }
+    chrono::DateTime::parse_from_rfc3339(s)
+        .map(|dt| Some((dt.timestamp(), dt.offset().local_minus_utc())))
+        .map_err(|e| format!("invalid {} '{}': {}", column, s, e))
+}
+
+/// Split CSV text into records of fields (RFC 4180), each with the line
+/// it starts on. Quoted fields may contain commas, doubled quotes and
+/// newlines.
+fn split_csv(text: &str) -> Result<Vec<(usize, Vec<String>)>, String> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut line = 1;
+    let mut start = 1;
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if quoted => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            }
+            '"' if field.is_empty() => quoted = true,
+            '\n' if quoted => {
+                line += 1;
+                field.push(ch);
+            }
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            '\r' if !quoted && chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                records.push((start, std::mem::take(&mut fields)));
+                line += 1;
+                start = line;
+            }
+            c => field.push(c),
+        }
+    }
+
+    if quoted {
+        return Err(format!("line {}: unterminated quoted field", start));
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        records.push((start, fields));
+    }
+    Ok(records)
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} added", plural(self.added, "entry", "entries"))?;
+        writeln!(
+            f,
+            "{} skipped",
+            plural(self.duplicates, "duplicate", "duplicates")
+        )?;
+        if self.running > 0 {
+            writeln!(
+                f,
+                "{} skipped",
+                plural(self.running, "running entry", "running entries")
+            )?;
+        }
+        writeln!(
+            f,
+            "{} added",
+            plural(self.annotations_added, "annotation", "annotations")
+        )?;
+        writeln!(
+            f,
+            "{} added",
+            plural(self.interrupts_added, "interrupt", "interrupts")
+        )
+    }
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {} {}", self.line, self.uuid, self.reason)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_csv_quoted_fields() {
+        let rows = split_csv("a,b\n\"x,1\",\"say \"\"hi\"\"\nthere\"\nlast,\n").unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                (1, vec!["a".to_string(), "b".to_string()]),
+                (2, vec!["x,1".to_string(), "say \"hi\"\nthere".to_string()]),
+                (4, vec!["last".to_string(), String::new()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_csv_unterminated_quote() {
+        assert!(split_csv("a,\"b\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_export_rejects_other_header() {
+        let err = parse_export("uuid,kind\n").unwrap_err();
+        assert!(err.contains("unexpected header"));
+    }
+
+    #[test]
+    fn test_parse_export_record() {
+        let csv = format!(
+            "{}\n{}\n",
+            CSV_HEADER,
+            "1b4e28ba2fa1416d9a0a3e8f9c1d2e3f,pomodoro,25,2026-05-29T12:00:00+02:00,\
+             2026-05-29T12:25:00+02:00,,finished,1,25,\
+             \"[{\"\"uuid\"\":\"\"2b4e28ba2fa1416d9a0a3e8f9c1d2e3f\"\",\"\"body\"\":\"\"a, b\"\",\
+             \"\"created_at\"\":\"\"2026-05-29T12:25:00+02:00\"\"}]\""
+        );
+
+        let records = parse_export(&csv).unwrap();
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.line, 2);
+        assert_eq!(record.entry.started_at, 1_780_048_800);
+        assert_eq!(record.entry.finished_at, 1_780_050_300);
+        assert_eq!(record.entry.utc_offset, Some(7200));
+        assert_eq!(record.entry.interruptions, 1);
+        assert_eq!(record.annotations[0].body, "a, b");
+    }
+
+    #[test]
+    fn test_parse_export_reports_line_of_bad_record() {
+        let csv = format!("{}\nnot-a-uuid,pomodoro,25,,,,,0,,\n", CSV_HEADER);
+        let err = parse_export(&csv).unwrap_err();
+        assert!(err.starts_with("line 2: invalid UUID"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_generic_csv() {
+        let records = parse(
+            "note,start,end,kind\n\
+             \"tests, docs\",2026-05-29T10:00:00Z,2026-05-29T10:25:00Z,\n\
+             ,2026-05-29T10:25:00Z,2026-05-29T10:30:00Z,break\n",
+            Format::Csv,
+        )
+        .unwrap();
+
+        assert_eq!(records[0].entry.kind, Kind::Pomodoro);
+        assert_eq!(records[0].entry.duration, 25);
+        assert_eq!(records[0].annotations[0].body, "tests, docs");
+        assert_eq!(records[1].line, 3);
+        assert_eq!(records[1].entry.kind, Kind::Break);
+        assert!(records[1].annotations.is_empty());
+    }
+
+    #[test]
+    fn test_external_rejects_end_before_start() {
+        let err = parse(
+            "start,end\n2026-05-29T10:00:00Z,2026-05-29T09:00:00Z\n",
+            Format::Csv,
+        )
+        .unwrap_err();
+        assert_eq!(err, "line 2: the entry ends before it starts");
+    }
+
+    #[test]
+    fn test_note_of_leaves_out_kind() {
+        let labels = vec!["break".to_string(), "coffee".to_string()];
+        assert_eq!(note_of(&labels, " talk "), Some("coffee: talk".to_string()));
+        assert_eq!(note_of(&labels[..1], ""), None);
+    }
+}
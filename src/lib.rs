@@ -6,12 +6,27 @@ use std::fmt;
 use std::str::FromStr;
 use uuid::Uuid;
 
+pub mod archive;
+pub mod backup;
+pub mod chart;
+pub mod doctor;
+pub mod exit_code;
 pub mod export;
 pub mod hooks;
+pub mod ics;
+pub mod import;
+pub mod merge;
+pub mod metrics;
 pub mod migration;
+pub mod org;
 pub mod persistence;
+pub mod profile;
 pub mod report;
 pub mod scheduling;
+pub mod timeclock;
+pub mod timeline;
+pub mod timewarrior;
+pub mod timezone;
 
 #[derive(Debug)]
 pub struct Annotation {
@@ -111,8 +126,11 @@ pub struct Schedulable {
     pub finished_at: i64,
     pub cancelled_at: i64,
     pub interruptions: i64,
+    /// UTC offset in seconds east of UTC when the entry was started, if recorded.
+    pub utc_offset: Option<i32>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Status {
     New,
     Active,
@@ -184,6 +202,7 @@ impl Schedulable {
             finished_at: 0,
             cancelled_at: 0,
             interruptions: 0,
+            utc_offset: None,
         }
     }
 
@@ -202,6 +221,20 @@ impl Schedulable {
             Status::New
         }
     }
+
+    /// When the entry ended: `finished_at`, else `cancelled_at`, else
+    /// `i64::MAX` while it has not ended yet. This is the end Rule #1 uses,
+    /// so running entries extend to infinity as in
+    /// [`Repository::overlapping`](persistence::Repository::overlapping).
+    pub fn end(&self) -> i64 {
+        if self.finished_at != 0 {
+            self.finished_at
+        } else if self.cancelled_at != 0 {
+            self.cancelled_at
+        } else {
+            i64::MAX
+        }
+    }
 }
 
 impl fmt::Display for Kind {
@@ -294,13 +327,12 @@ impl fmt::Display for Schedulable {
 ///
 /// Accepts:
 /// - RFC 3339 / ISO 8601 with timezone offset (e.g. `2026-05-29T14:30:00Z` or `2026-05-29T14:30:00+02:00`)
-/// - ISO 8601 without timezone (interpreted as local time)
+/// - ISO 8601 without timezone (interpreted in the zone of the [`timezone::clock`])
 /// - `HH:MM` 24-hour clock — interpreted as today at that time, or yesterday
 ///   if that time is in the future (we never apply actions in the future)
 /// - A bare integer interpreted as a Unix timestamp
 pub fn parse_timestamp(s: &str) -> Result<i64, String> {
-    use chrono::Local;
-    use chrono::TimeZone;
+    let zone = timezone::clock().zone();
 
     // RFC 3339 / ISO 8601 with timezone
     if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(s) {
@@ -311,17 +343,17 @@ pub fn parse_timestamp(s: &str) -> Result<i64, String> {
         return Ok(dt.timestamp());
     }
 
-    // ISO 8601 without timezone – interpret as local time
+    // ISO 8601 without timezone – interpret in the viewer's zone
     if let Ok(naive) = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S")
-        && let Some(local) = Local.from_local_datetime(&naive).earliest()
+        && let Some(ts) = zone.timestamp(&naive)
     {
-        return Ok(local.timestamp());
+        return Ok(ts);
     }
     // Also accept space-separated ISO 8601 (no T)
     if let Ok(naive) = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
-        && let Some(local) = Local.from_local_datetime(&naive).earliest()
+        && let Some(ts) = zone.timestamp(&naive)
     {
-        return Ok(local.timestamp());
+        return Ok(ts);
     }
 
     // HH:MM — today at that time, or yesterday if that time is in the future
@@ -340,13 +372,13 @@ pub fn parse_timestamp(s: &str) -> Result<i64, String> {
     ))
 }
 
-/// Parse an `HH:MM` string into a Unix timestamp in local time.
+/// Parse an `HH:MM` string into a Unix timestamp in the viewer's zone.
 ///
 /// Returns the timestamp for that time **today** if it's not in the future,
 /// or **yesterday** if the wall-clock time has already passed today.
 /// This enforces the rule that we never apply actions about the future.
 fn parse_hhmm_local(s: &str) -> Option<i64> {
-    use chrono::{Local, TimeZone};
+    let zone = timezone::clock().zone();
 
     let (hours, minutes) = s.split_once(':').and_then(|(h, m)| {
         let h: u32 = h.parse().ok()?;
@@ -358,27 +390,23 @@ fn parse_hhmm_local(s: &str) -> Option<i64> {
         }
     })?;
 
-    let now = Local::now();
-    let today = now.date_naive();
+    let now = now();
+    let today = chrono::DateTime::from_timestamp(now, 0)?
+        .with_timezone(&zone.offset_at(now))
+        .date_naive();
 
     // Timestamp for today at HH:MM
     let today_naive = today.and_hms_opt(hours, minutes, 0)?;
-    let today_ts = Local
-        .from_local_datetime(&today_naive)
-        .earliest()?
-        .timestamp();
+    let today_ts = zone.timestamp(&today_naive)?;
 
-    if today_ts <= now.timestamp() {
+    if today_ts <= now {
         // Today at that time is in the past or right now — use today
         Some(today_ts)
     } else {
         // Future — use yesterday instead
         let yesterday = today - chrono::Duration::days(1);
         let yesterday_naive = yesterday.and_hms_opt(hours, minutes, 0)?;
-        Local
-            .from_local_datetime(&yesterday_naive)
-            .earliest()
-            .map(|dt| dt.timestamp())
+        zone.timestamp(&yesterday_naive)
     }
 }
 
@@ -408,15 +436,10 @@ pub fn abbreviate_uuids(uuids: &[SqlUuid]) -> Vec<String> {
 
 /// Format a timestamp as `HH:MM` (short form, for use in day reports).
 pub fn format_time(timestamp: i64) -> String {
-    use chrono::{Local, TimeZone};
     if timestamp == 0 {
         return "N/A".to_string();
     }
-    Local
-        .timestamp_opt(timestamp, 0)
-        .single()
-        .map(|dt| dt.format("%H:%M").to_string())
-        .unwrap_or_else(|| timestamp.to_string())
+    timezone::clock().format(timestamp, "%H:%M")
 }
 
 /// Return the current Unix timestamp (seconds since epoch).
@@ -436,15 +459,14 @@ pub fn interruption_noun(count: i64) -> &'static str {
     }
 }
 
-pub fn format_timestamp(timestamp: i64) -> String {
-    use chrono::{Local, TimeZone};
+/// The count followed by the singular or plural noun, e.g. "1 entry" or "2 entries".
+pub(crate) fn plural(n: usize, one: &str, many: &str) -> String {
+    format!("{} {}", n, if n == 1 { one } else { many })
+}
 
+pub fn format_timestamp(timestamp: i64) -> String {
     if timestamp == 0 {
         return "N/A".to_string();
     }
-    Local
-        .timestamp_opt(timestamp, 0)
-        .single()
-        .map(|dt| dt.format("%H:%M:%S").to_string())
-        .unwrap_or_else(|| timestamp.to_string())
+    timezone::clock().format(timestamp, "%H:%M:%S")
 }
@@ -1,10 +1,11 @@
 use clap::{CommandFactory, Parser, crate_version};
 use clap_complete::{Shell, generate};
-use rustomato::hooks;
-use rustomato::persistence::Repository;
+use rustomato::persistence::{PersistenceError, Repository};
 use rustomato::scheduling::{Scheduler, SchedulingError};
+use rustomato::timezone::{self, Clock, Perspective, Zone};
 use rustomato::{InterruptionKind, Kind, Schedulable, Status, abbreviate_uuids, format_timestamp};
-use std::io;
+use rustomato::{exit_code, hooks};
+use std::io::{self, IsTerminal};
 use std::path::*;
 use std::{env, process};
 use url::Url;
@@ -20,6 +21,14 @@ struct Opts {
     #[clap(long)]
     no_hooks: bool,
 
+    /// Use the named profile instead of the default one (env: RUSTOMATO_PROFILE)
+    #[clap(long, global = true, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Show and enter times in this zone: local, UTC or an offset like +09:00
+    #[clap(long, global = true, value_name = "ZONE", default_value = "local")]
+    tz: Zone,
+
     #[clap(subcommand)]
     subcmd: Option<SubCommands>,
 }
@@ -39,8 +48,24 @@ enum SubCommands {
     Report(ReportCommand),
     /// Display the man page
     Man(ManCommand),
-    /// Export entries as CSV for external analysis
+    /// Export entries as CSV, JSON, NDJSON, iCalendar, timeclock or org for external analysis
     Export(ExportCommand),
+    /// Back up the database while it is in use
+    Backup(BackupCommand),
+    /// Restore the database from a backup
+    Restore(RestoreCommand),
+    /// Check the database and hooks for consistency problems
+    Doctor(DoctorCommand),
+    /// Merge entries from another rustomato database
+    Merge(MergeCommand),
+    /// Manage profiles with separate databases and hooks
+    Profile(ProfileCommand),
+    /// Move old entries into a separate archive database
+    Archive(ArchiveCommand),
+    /// Import entries from an export or from other time-tracking tools
+    Import(ImportCommand),
+    /// Write Prometheus metrics about today, this week and the running timer
+    Metrics(MetricsCommand),
     #[clap(hide = true)]
     Completions(CompletionsCommand),
 }
@@ -53,7 +78,7 @@ struct InitCommand {}
 #[derive(Parser)]
 struct ManCommand {}
 
-/// Export entries as CSV for external analysis
+/// Export entries as CSV, JSON, NDJSON, iCalendar, timeclock or org for external analysis
 #[derive(Parser)]
 struct ExportCommand {
     /// Start date (YYYY-MM-DD). Defaults to the earliest entry.
@@ -62,6 +87,240 @@ struct ExportCommand {
     /// End date (YYYY-MM-DD). Defaults to now.
     #[clap(long, value_name = "DATE")]
     to: Option<String>,
+    /// Show times as recorded (in the UTC offset of each entry) or as viewed now
+    #[clap(
+        long = "as",
+        value_enum,
+        default_value = "viewed",
+        value_name = "PERSPECTIVE"
+    )]
+    times: TimesAs,
+    /// Output format
+    #[clap(long, value_enum, default_value = "csv")]
+    format: ExportFormat,
+    /// Account of the timeclock sessions. May contain {kind}, {tag} and {annotation}.
+    #[clap(long, value_name = "TEMPLATE", default_value = rustomato::timeclock::DEFAULT_ACCOUNT)]
+    account: String,
+    /// Also write cancelled pomodori as timeclock sessions
+    #[clap(long)]
+    include_cancelled: bool,
+    /// Write one CSV row per schedulable, or per interrupt or annotation of the exported schedulables
+    #[clap(long, value_enum, default_value = "schedulables")]
+    table: ExportTable,
+    /// Write all tables as CSV files into DIR instead of to stdout
+    #[clap(long, value_name = "DIR")]
+    bundle: Option<PathBuf>,
+    /// Only export entries of this kind
+    #[clap(long, value_enum)]
+    kind: Option<ExportKind>,
+    /// Only export entries with one of these statuses
+    #[clap(long, value_enum, value_delimiter = ',')]
+    status: Vec<ExportStatus>,
+    /// Only export entries with at least this many interruptions
+    #[clap(long, value_name = "N", default_value_t = 0)]
+    min_interruptions: i64,
+    /// Only export entries with an annotation containing TEXT, ignoring case
+    #[clap(long, value_name = "TEXT")]
+    grep: Option<String>,
+    /// CSV columns to write, in this order, separated by commas
+    #[clap(long, value_name = "COLUMNS")]
+    columns: Option<String>,
+    /// CSV field delimiter, a single character or "tab" [default: ,]
+    #[clap(long, value_name = "CHAR")]
+    delimiter: Option<String>,
+    /// Do not write the CSV header row
+    #[clap(long)]
+    no_header: bool,
+    /// Write CSV timestamps with the offset of --tz, or in UTC [default: local]
+    #[clap(long, value_enum)]
+    timestamps: Option<Timestamps>,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum ExportTable {
+    Schedulables,
+    Interrupts,
+    Annotations,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum ExportKind {
+    Pomodoro,
+    Break,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum ExportStatus {
+    Finished,
+    Cancelled,
+    Active,
+    Stale,
+}
+
+#[derive(Clone, Copy, PartialEq, clap::ValueEnum)]
+enum Timestamps {
+    Local,
+    Utc,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum ExportFormat {
+    /// One row per entry, annotations as a JSON column
+    Csv,
+    /// A single JSON document
+    Json,
+    /// One JSON object per line
+    Ndjson,
+    /// iCalendar with one event per entry
+    Ics,
+    /// hledger/ledger timeclock sessions, one per pomodoro
+    Timeclock,
+    /// An org-mode tree grouped by day
+    Org,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum TimesAs {
+    Recorded,
+    Viewed,
+}
+
+/// Back up the database while it is in use
+#[derive(Parser)]
+struct BackupCommand {
+    /// Where to write the backup. Defaults to a timestamped file in $RUSTOMATO_ROOT/backups/.
+    #[clap(value_name = "PATH")]
+    path: Option<PathBuf>,
+
+    /// Keep only the N most recent backups in $RUSTOMATO_ROOT/backups/
+    #[clap(long, value_name = "N", conflicts_with = "path")]
+    keep: Option<usize>,
+}
+
+/// Restore the database from a backup
+#[derive(Parser)]
+struct RestoreCommand {
+    /// The backup file to restore from
+    #[clap(value_name = "PATH")]
+    path: PathBuf,
+}
+
+/// Check the database and hooks for consistency problems
+#[derive(Parser)]
+struct DoctorCommand {
+    /// Repair the problems that can be fixed automatically
+    #[clap(long)]
+    fix: bool,
+
+    /// Output format
+    #[clap(long, value_enum, default_value = "text")]
+    format: DoctorFormat,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum DoctorFormat {
+    Text,
+    Json,
+}
+
+/// Merge entries from another rustomato database
+#[derive(Parser)]
+struct MergeCommand {
+    /// The database to merge from. It is only read, never modified.
+    #[clap(value_name = "PATH")]
+    path: PathBuf,
+
+    /// How to resolve entries that overlap local ones (Rule #1)
+    #[clap(long, value_enum, default_value = "prefer-local")]
+    policy: MergePolicy,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum MergePolicy {
+    /// Keep the local entry
+    PreferLocal,
+    /// Keep the entry that ended last
+    PreferNewer,
+    /// Ask for every conflict
+    Interactive,
+}
+
+/// Move old entries into a separate archive database
+#[derive(Parser)]
+struct ArchiveCommand {
+    /// Archive the entries started before this date (YYYY-MM-DD)
+    #[clap(long, value_name = "DATE")]
+    before: String,
+}
+
+/// Import entries from an export or from other time-tracking tools
+#[derive(Parser)]
+struct ImportCommand {
+    /// The file to import
+    #[clap(value_name = "FILE")]
+    path: PathBuf,
+
+    /// The format of the file
+    #[clap(long, value_enum, default_value = "rustomato")]
+    from: ImportFormat,
+
+    /// Only report what would be imported and which entries conflict
+    #[clap(long)]
+    dry_run: bool,
+}
+
+/// Write Prometheus metrics about today, this week and the running timer
+#[derive(Parser)]
+struct MetricsCommand {
+    /// Replace this file atomically instead of writing to stdout, e.g. for the node exporter's textfile collector
+    #[clap(long, value_name = "PATH")]
+    output: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum ImportFormat {
+    /// CSV written by `rustomato export`
+    Rustomato,
+    /// A Timewarrior data file
+    Timewarrior,
+    /// An hledger or ledger timeclock file
+    Timeclock,
+    /// CSV with the columns start,end,kind,note
+    Csv,
+}
+
+/// Manage profiles with separate databases and hooks
+#[derive(Parser)]
+struct ProfileCommand {
+    #[clap(subcommand)]
+    subcmd: ProfileCommands,
+}
+
+#[derive(Parser)]
+enum ProfileCommands {
+    /// List all profiles; the one in use is marked with '*'
+    List(ListProfiles),
+    /// Create a new profile with sample hooks
+    Create(CreateProfile),
+    /// Show or set the profile used when none is given
+    Default(DefaultProfile),
+}
+
+#[derive(Parser)]
+struct ListProfiles {}
+
+#[derive(Parser)]
+struct CreateProfile {
+    /// Name of the new profile
+    #[clap(value_name = "NAME")]
+    name: String,
+}
+
+#[derive(Parser)]
+struct DefaultProfile {
+    /// Name of the profile to use by default. Shows the current default if omitted.
+    #[clap(value_name = "NAME")]
+    name: Option<String>,
 }
 
 /// Work with a Pomodoro
@@ -284,10 +543,44 @@ struct CompletionsCommand {
 #[derive(Parser)]
 #[clap(infer_subcommands = true)]
 struct ReportCommand {
+    /// Aggregate the entries of all profiles (read-only)
+    #[clap(long, global = true)]
+    all_profiles: bool,
+
+    /// Include the entries moved to the archive with `rustomato archive`
+    #[clap(long, global = true)]
+    include_archive: bool,
+
+    /// Show times and days as recorded (in the UTC offset of each entry) or as viewed now
+    #[clap(
+        long = "as",
+        global = true,
+        value_enum,
+        default_value = "viewed",
+        value_name = "PERSPECTIVE"
+    )]
+    times: TimesAs,
+
+    /// Output format
+    #[clap(long, global = true, value_enum, default_value = "text")]
+    format: ReportFormat,
+
     #[clap(subcommand)]
     subcmd: ReportCommands,
 }
 
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum ReportFormat {
+    /// Human-readable text
+    Text,
+    /// A JSON document, see doc/report/README.md
+    Json,
+    /// Markdown with tables, e.g. for notes or issue comments
+    Markdown,
+    /// A self-contained HTML document with charts, e.g. for a wiki
+    Html,
+}
+
 #[derive(Parser)]
 enum ReportCommands {
     Day(DayReport),
@@ -298,6 +591,18 @@ enum ReportCommands {
     Last(LastReport),
     /// Interruption pattern analysis by hour of day and day of week
     Interruptions(InterruptionsReport),
+    /// Yearly report with a calendar heatmap of finished pomodori
+    Year(YearReport),
+    /// Productivity report for any range of days, e.g. a sprint
+    Range(RangeReport),
+    /// Compare the metrics of two periods side by side
+    Compare(CompareReport),
+    /// Finished and cancelled pomodori by hour of day and day of week
+    Hours(HoursReport),
+    /// How closely breaks followed the technique, day by day
+    Breaks(BreaksReport),
+    /// Draw a bar or line chart as SVG
+    Chart(ChartReport),
 }
 
 /// Daily productivity report
@@ -306,6 +611,9 @@ struct DayReport {
     /// Date in ISO 8601 format (YYYY-MM-DD). Defaults to today.
     #[clap(long, value_name = "DATE")]
     date: Option<String>,
+    /// Draw the day on an hour scale, sized to the terminal width
+    #[clap(long)]
+    timeline: bool,
 }
 
 /// Weekly productivity report
@@ -314,6 +622,9 @@ struct WeekReport {
     /// A date within the target week (YYYY-MM-DD). Defaults to today.
     #[clap(long, value_name = "DATE")]
     date: Option<String>,
+    /// Draw the days on a common hour scale, one row per day, sized to the terminal width
+    #[clap(long)]
+    timeline: bool,
 }
 
 /// Monthly productivity report
@@ -334,10 +645,51 @@ struct LastReport {
     #[clap(long, value_name = "DATE")]
     date: Option<String>,
     /// Size of the window in days. Defaults to 7.
-    #[clap(long, default_value = "7", value_name = "DAYS")]
+    #[clap(long, default_value = "7", value_name = "DAYS", value_parser = clap::value_parser!(u32).range(1..))]
     days: u32,
 }
 
+/// Yearly report with a calendar heatmap
+#[derive(Parser)]
+struct YearReport {
+    /// The year to report on (YYYY). Defaults to the current year.
+    #[clap(long, value_name = "YYYY", value_parser = clap::value_parser!(i32).range(1..=9999))]
+    year: Option<i32>,
+    /// Draw the heatmap in ANSI colours instead of shade blocks
+    #[clap(long, value_enum, default_value = "auto", value_name = "WHEN")]
+    color: ColorWhen,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum ColorWhen {
+    /// When writing to a terminal and NO_COLOR is not set
+    Auto,
+    Always,
+    Never,
+}
+
+/// Report for a range of days
+#[derive(Parser)]
+struct RangeReport {
+    /// First day of the range (YYYY-MM-DD)
+    #[clap(long, value_name = "DATE")]
+    from: String,
+    /// Last day of the range (YYYY-MM-DD). Defaults to today.
+    #[clap(long, value_name = "DATE")]
+    to: Option<String>,
+}
+
+/// Comparison of two periods
+#[derive(Parser)]
+struct CompareReport {
+    /// The first period: FROM..TO (YYYY-MM-DD..YYYY-MM-DD), a month (YYYY-MM) or a day (YYYY-MM-DD)
+    #[clap(value_name = "PERIOD_A")]
+    a: String,
+    /// The second period, compared to the first one
+    #[clap(value_name = "PERIOD_B")]
+    b: String,
+}
+
 /// Interruption pattern report
 #[derive(Parser)]
 struct InterruptionsReport {
@@ -345,8 +697,62 @@ struct InterruptionsReport {
     #[clap(long, value_name = "DATE")]
     date: Option<String>,
     /// Number of days to look back. Defaults to 7.
-    #[clap(long, default_value = "7", value_name = "DAYS")]
+    #[clap(long, default_value = "7", value_name = "DAYS", value_parser = clap::value_parser!(u32).range(1..))]
+    days: u32,
+}
+
+/// Time-of-day productivity report
+#[derive(Parser)]
+struct HoursReport {
+    /// End date for the analysis window (YYYY-MM-DD). Defaults to today.
+    #[clap(long, value_name = "DATE")]
+    date: Option<String>,
+    /// Number of days to look back. Defaults to 28.
+    #[clap(long, default_value = "28", value_name = "DAYS", value_parser = clap::value_parser!(u32).range(1..))]
+    days: u32,
+}
+
+/// Break adherence report
+#[derive(Parser)]
+struct BreaksReport {
+    /// End date for the analysis window (YYYY-MM-DD). Defaults to today.
+    #[clap(long, value_name = "DATE")]
+    date: Option<String>,
+    /// Number of days to look back. Defaults to 7.
+    #[clap(long, default_value = "7", value_name = "DAYS", value_parser = clap::value_parser!(u32).range(1..))]
     days: u32,
+    /// Minutes a break may start after a pomodoro, and the next pomodoro after the planned end of the break
+    #[clap(long, default_value = "5", value_name = "MINUTES")]
+    within: u32,
+}
+
+/// SVG chart
+#[derive(Parser)]
+struct ChartReport {
+    /// What to draw
+    #[clap(long, value_enum, default_value = "daily", value_name = "KIND")]
+    kind: ChartKind,
+    /// End date for the chart (YYYY-MM-DD). Defaults to today.
+    #[clap(long, value_name = "DATE")]
+    date: Option<String>,
+    /// Number of days to look back. Defaults to 14 for daily, 28 for hourly and interruptions, and 84 for weekly.
+    #[clap(long, value_name = "DAYS", value_parser = clap::value_parser!(u32).range(1..))]
+    days: Option<u32>,
+    /// Write the SVG to this file instead of stdout
+    #[clap(long, value_name = "PATH")]
+    out: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum ChartKind {
+    /// Completed and cancelled pomodori per day
+    Daily,
+    /// Completed and cancelled pomodori by hour of day
+    Hourly,
+    /// Internal and external interruptions by hour of day
+    Interruptions,
+    /// Completed and cancelled pomodori per week, as a trend
+    Weekly,
 }
 
 fn main() {
@@ -380,7 +786,7 @@ fn main() {
         return;
     }
 
-    let root = match env::var("RUSTOMATO_ROOT") {
+    let base = match env::var("RUSTOMATO_ROOT") {
         Ok(val) => {
             let root = PathBuf::from(val);
             if !root.exists() {
@@ -400,10 +806,32 @@ fn main() {
         }
     };
 
+    let requested_profile = opts
+        .profile
+        .clone()
+        .or_else(|| env::var("RUSTOMATO_PROFILE").ok());
+
+    // Profile management works on the base root, so handle it before resolving a profile.
+    if let SubCommands::Profile(profile_options) = &subcmd {
+        cmd_profile(&base, requested_profile.as_deref(), profile_options);
+        return;
+    }
+
+    let (profile, root) = match rustomato::profile::resolve(&base, requested_profile.as_deref()) {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            eprintln!("Error: {}.", e);
+            process::exit(1);
+        }
+    };
+
     let verbose = opts.verbose;
+    let tz = opts.tz;
+    timezone::set_clock(Clock::new(tz, Perspective::Viewed));
 
     if verbose {
         println!("Using root {}", root.to_str().expect("converting"));
+        println!("Using profile {}", profile);
     }
 
     // Handle init early — no database needed.
@@ -427,9 +855,10 @@ fn main() {
         return;
     }
 
+    // An explicit --profile wins over the database of the environment.
     let db_url = match env::var("RUSTOMATO_DATABASE_URL") {
-        Ok(val) => Url::parse(&val).expect("parsing the database URL"),
-        Err(_) => {
+        Ok(val) if opts.profile.is_none() => Url::parse(&val).expect("parsing the database URL"),
+        _ => {
             let db_path = root.join("data.db");
             Url::from_file_path(&db_path).expect("converting database path to URL")
         }
@@ -440,7 +869,7 @@ fn main() {
     }
 
     let repo = Repository::from_url(&db_url);
-    let scheduler = Scheduler::new(repo, root, verbose, opts.no_hooks);
+    let scheduler = Scheduler::new(repo, root.clone(), verbose, opts.no_hooks);
     let pid = process::id();
 
     match subcmd {
@@ -497,43 +926,145 @@ fn main() {
                 verbose,
             ),
         },
-        SubCommands::Report(report_options) => match report_options.subcmd {
-            ReportCommands::Day(day_options) => {
+        SubCommands::Report(report_options) => {
+            let repo = if report_options.all_profiles {
+                all_profiles_repository(&base, report_options.include_archive)
+            } else {
                 let repo = Repository::from_url(&db_url);
-                rustomato::report::print_day_report(&repo, day_options.date);
+                if report_options.include_archive
+                    && let Err(e) =
+                        rustomato::archive::include(&repo, &rustomato::archive::path(&root))
+                {
+                    eprintln!("Error: {}.", e);
+                    process::exit(e.exit_code());
+                }
+                repo
+            };
+            use_perspective(&repo, tz, report_options.times);
+            let format = match report_options.format {
+                ReportFormat::Text => rustomato::report::Format::Text,
+                ReportFormat::Json => rustomato::report::Format::Json,
+                ReportFormat::Markdown => rustomato::report::Format::Markdown,
+                ReportFormat::Html => rustomato::report::Format::Html,
+            };
+            match report_options.subcmd {
+                ReportCommands::Day(day_options) => {
+                    rustomato::report::print_day_report(
+                        &repo,
+                        day_options.date,
+                        day_options.timeline,
+                        format,
+                    );
+                }
+                ReportCommands::Week(week_options) => {
+                    rustomato::report::print_week_report(
+                        &repo,
+                        week_options.date,
+                        week_options.timeline,
+                        format,
+                    );
+                }
+                ReportCommands::Interruptions(int_options) => {
+                    rustomato::report::print_interruptions_report(
+                        &repo,
+                        int_options.date,
+                        int_options.days,
+                        format,
+                    );
+                }
+                ReportCommands::Month(month_options) => {
+                    rustomato::report::print_month_report(
+                        &repo,
+                        month_options.date,
+                        month_options.months,
+                        format,
+                    );
+                }
+                ReportCommands::Last(last_options) => {
+                    rustomato::report::print_last_report(
+                        &repo,
+                        last_options.date,
+                        last_options.days,
+                        format,
+                    );
+                }
+                ReportCommands::Year(year_options) => {
+                    let colour = match year_options.color {
+                        ColorWhen::Auto => {
+                            io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none()
+                        }
+                        ColorWhen::Always => true,
+                        ColorWhen::Never => false,
+                    };
+                    rustomato::report::print_year_report(&repo, year_options.year, colour, format);
+                }
+                ReportCommands::Range(range_options) => {
+                    rustomato::report::print_range_report(
+                        &repo,
+                        range_options.from,
+                        range_options.to,
+                        format,
+                    );
+                }
+                ReportCommands::Compare(compare_options) => {
+                    rustomato::report::print_compare_report(
+                        &repo,
+                        &compare_options.a,
+                        &compare_options.b,
+                        format,
+                    );
+                }
+                ReportCommands::Hours(hours_options) => {
+                    rustomato::report::print_hours_report(
+                        &repo,
+                        hours_options.date,
+                        hours_options.days,
+                        format,
+                    );
+                }
+                ReportCommands::Breaks(breaks_options) => {
+                    rustomato::report::print_breaks_report(
+                        &repo,
+                        breaks_options.date,
+                        breaks_options.days,
+                        breaks_options.within,
+                        format,
+                    );
+                }
+                ReportCommands::Chart(chart_options) => {
+                    if format != rustomato::report::Format::Text {
+                        Opts::command()
+                            .error(
+                                clap::error::ErrorKind::ArgumentConflict,
+                                "report chart always writes SVG; --format cannot be used with it",
+                            )
+                            .exit();
+                    }
+                    let kind = match chart_options.kind {
+                        ChartKind::Daily => rustomato::report::ChartKind::Daily,
+                        ChartKind::Hourly => rustomato::report::ChartKind::Hourly,
+                        ChartKind::Interruptions => rustomato::report::ChartKind::Interruptions,
+                        ChartKind::Weekly => rustomato::report::ChartKind::Weekly,
+                    };
+                    rustomato::report::print_chart(
+                        &repo,
+                        kind,
+                        chart_options.date,
+                        chart_options.days,
+                        chart_options.out.as_deref(),
+                    );
+                }
             }
-            ReportCommands::Week(week_options) => {
-                rustomato::report::print_week_report(
-                    &Repository::from_url(&db_url),
-                    week_options.date,
-                );
-            }
-            ReportCommands::Interruptions(int_options) => {
-                rustomato::report::print_interruptions_report(
-                    &Repository::from_url(&db_url),
-                    int_options.date,
-                    int_options.days,
-                );
-            }
-            ReportCommands::Month(month_options) => {
-                rustomato::report::print_month_report(
-                    &Repository::from_url(&db_url),
-                    month_options.date,
-                    month_options.months,
-                );
-            }
-            ReportCommands::Last(last_options) => {
-                rustomato::report::print_last_report(
-                    &Repository::from_url(&db_url),
-                    last_options.date,
-                    last_options.days,
-                );
-            }
-        },
-        SubCommands::Export(ref opts) => {
-            let repo = Repository::from_url(&db_url);
-            rustomato::export::cmd_export(&repo, opts.from.as_deref(), opts.to.as_deref());
         }
+        SubCommands::Export(ref opts) => cmd_export(&db_url, tz, opts),
+        SubCommands::Backup(ref opts) => cmd_backup(scheduler.repo(), &root, opts, verbose),
+        SubCommands::Restore(ref opts) => cmd_restore(&db_url, opts, verbose),
+        SubCommands::Doctor(ref opts) => cmd_doctor(scheduler.repo(), &root, opts),
+        SubCommands::Merge(ref opts) => cmd_merge(scheduler.repo(), opts),
+        SubCommands::Archive(ref opts) => cmd_archive(scheduler.repo(), &root, opts),
+        SubCommands::Import(ref opts) => cmd_import(scheduler.repo(), opts),
+        SubCommands::Metrics(ref opts) => cmd_metrics(scheduler.repo(), &root, opts),
+        SubCommands::Profile(_) => unreachable!(), // handled above
         SubCommands::Man(_) => unreachable!(),
         SubCommands::Completions(_) => unreachable!(),
     };
@@ -558,12 +1089,11 @@ fn cmd_pomodoro_start(scheduler: &Scheduler, opts: &StartPomodoro, pid: u32, ver
             }
         }
         Err(err) => {
-            match err {
-                SchedulingError::AlreadyRunning(_) => eprintln!("Error: {}.", err),
-                SchedulingError::HookRejected => process::exit(1),
-                _ => eprintln!("Error: {}.", err),
+            // A rejecting hook has already reported why
+            if err != SchedulingError::HookRejected {
+                eprintln!("Error: {}.", err);
             }
-            process::exit(1);
+            process::exit(err.exit_code());
         }
     }
 }
@@ -594,7 +1124,7 @@ fn cmd_pomodoro_interrupt(scheduler: &Scheduler, opts: &InterruptPomodoro, verbo
         }
         Err(err) => {
             eprintln!("Error: {}.", err);
-            process::exit(1);
+            process::exit(err.exit_code());
         }
     }
 }
@@ -658,9 +1188,13 @@ fn cmd_pomodoro_log(scheduler: &Scheduler, opts: &LogPomodoro, verbose: bool) {
     let mut pom = Schedulable::new(0, Kind::Pomodoro, actual_duration);
     pom.started_at = started_at;
     pom.finished_at = finished_at;
+    pom.utc_offset = Some(timezone::offset_to_record(started_at));
 
-    if scheduler.log(&pom).is_err() {
-        process::exit(1);
+    if let Err(err) = scheduler.log(&pom) {
+        if err != SchedulingError::HookRejected {
+            eprintln!("Error: {}.", err);
+        }
+        process::exit(err.exit_code());
     }
 }
 
@@ -723,9 +1257,13 @@ fn cmd_break_log(scheduler: &Scheduler, opts: &LogBreak, verbose: bool) {
     let mut brk = Schedulable::new(0, Kind::Break, actual_duration);
     brk.started_at = started_at;
     brk.finished_at = finished_at;
+    brk.utc_offset = Some(timezone::offset_to_record(started_at));
 
-    if scheduler.log(&brk).is_err() {
-        process::exit(1);
+    if let Err(err) = scheduler.log(&brk) {
+        if err != SchedulingError::HookRejected {
+            eprintln!("Error: {}.", err);
+        }
+        process::exit(err.exit_code());
     }
 }
 
@@ -780,7 +1318,7 @@ fn cmd_annotate(
         }
         Err(err) => {
             eprintln!("Error: {}.", err);
-            process::exit(1);
+            process::exit(err.exit_code());
         }
     }
 }
@@ -807,7 +1345,7 @@ fn cmd_cancel(scheduler: &Scheduler, target: Option<&str>, index: Option<&str>,
         }
         Err(err) => {
             eprintln!("Error: {}.", err);
-            process::exit(1);
+            process::exit(err.exit_code());
         }
     }
 }
@@ -834,7 +1372,7 @@ fn cmd_delete(scheduler: &Scheduler, target: Option<&str>, index: Option<&str>,
         }
         Err(err) => {
             eprintln!("Error: {}.", err);
-            process::exit(1);
+            process::exit(err.exit_code());
         }
     }
 }
@@ -864,12 +1402,11 @@ fn cmd_break_start(scheduler: &Scheduler, opts: &StartBreak, pid: u32, verbose:
             process::exit(0);
         }
         Err(err) => {
-            match err {
-                SchedulingError::AlreadyRunning(_) => eprintln!("Error: {}.", err),
-                SchedulingError::HookRejected => process::exit(1),
-                _ => eprintln!("Error: {}.", err),
+            // A rejecting hook has already reported why
+            if err != SchedulingError::HookRejected {
+                eprintln!("Error: {}.", err);
             }
-            process::exit(1);
+            process::exit(err.exit_code());
         }
     }
 }
@@ -880,7 +1417,35 @@ fn cmd_status(db_url: &Url) {
             Some(existing) => println!("{}", existing),
             None => println!("Nothing active"),
         },
-        Err(e) => eprintln!("{}", e),
+        Err(e) => {
+            eprintln!("Error: {}.", e);
+            process::exit(e.exit_code());
+        }
+    }
+}
+
+fn cmd_metrics(repo: &Repository, root: &Path, opts: &MetricsCommand) {
+    // The totals are counters, which must not drop when entries are archived.
+    if let Err(e) = rustomato::archive::include(repo, &rustomato::archive::path(root)) {
+        eprintln!("Error: {}.", e);
+        process::exit(e.exit_code());
+    }
+    let metrics = match rustomato::metrics::collect(repo, rustomato::now()) {
+        Ok(m) => m.to_string(),
+        Err(e) => {
+            eprintln!("Error: {}.", e);
+            process::exit(e.exit_code());
+        }
+    };
+
+    match &opts.output {
+        Some(path) => {
+            if let Err(e) = rustomato::metrics::write_atomically(path, &metrics) {
+                eprintln!("Error: cannot write {}: {}.", path.display(), e);
+                process::exit(exit_code::IO);
+            }
+        }
+        None => print!("{}", metrics),
     }
 }
 
@@ -895,7 +1460,7 @@ fn cmd_list(db_url: &Url, opts: &ListCommand) {
         Ok(e) => e,
         Err(e) => {
             eprintln!("Error: {}.", e);
-            process::exit(1);
+            process::exit(e.exit_code());
         }
     };
 
@@ -955,6 +1520,405 @@ fn cmd_list(db_url: &Url, opts: &ListCommand) {
     }
 }
 
+fn cmd_backup(repo: &Repository, root: &Path, opts: &BackupCommand, verbose: bool) {
+    if opts.keep == Some(0) {
+        eprintln!("Error: --keep must be > 0.");
+        process::exit(1);
+    }
+
+    let path = match &opts.path {
+        Some(path) => match repo.backup_to(path) {
+            Ok(()) => path.clone(),
+            Err(e) => {
+                eprintln!("Error: {}.", e);
+                process::exit(e.exit_code());
+            }
+        },
+        None => {
+            let dir = rustomato::backup::backups_dir(root);
+            match rustomato::backup::backup_rotated(repo, &dir, opts.keep) {
+                Ok((path, removed)) => {
+                    if verbose {
+                        for old in &removed {
+                            println!("Removed old backup {}", old.display());
+                        }
+                    }
+                    path
+                }
+                Err(e) => {
+                    eprintln!("Error: {}.", e);
+                    process::exit(1);
+                }
+            }
+        }
+    };
+
+    println!("Backed up to {}", path.display());
+}
+
+fn cmd_restore(db_url: &Url, opts: &RestoreCommand, verbose: bool) {
+    let mut repo = Repository::from_url(db_url);
+    match repo.restore_from(&opts.path) {
+        Ok(()) => {
+            if verbose {
+                println!("Restored from {}", opts.path.display());
+            }
+        }
+        Err(PersistenceError::AlreadyRunning(pid)) => {
+            eprintln!(
+                "Error: cannot restore while a pomodoro or break is running (pid {}).",
+                pid
+            );
+            process::exit(exit_code::ALREADY_RUNNING);
+        }
+        Err(e) => {
+            eprintln!("Error: {}.", e);
+            process::exit(e.exit_code());
+        }
+    }
+}
+
+fn cmd_doctor(repo: &Repository, root: &Path, opts: &DoctorCommand) {
+    let findings = match rustomato::doctor::run(repo, root, opts.fix) {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("Error: {}.", e);
+            process::exit(1);
+        }
+    };
+
+    match opts.format {
+        DoctorFormat::Text => print!("{}", rustomato::doctor::format_text(&findings, opts.fix)),
+        DoctorFormat::Json => println!("{}", rustomato::doctor::format_json(&findings)),
+    }
+
+    if findings.iter().any(|f| !f.fixed) {
+        process::exit(1);
+    }
+}
+
+fn cmd_merge(repo: &Repository, opts: &MergeCommand) {
+    use rustomato::merge::{self, Conflict, Resolution};
+
+    let other = match Repository::snapshot(&opts.path) {
+        Ok(other) => other,
+        Err(e) => {
+            eprintln!("Error: {}.", e);
+            process::exit(e.exit_code());
+        }
+    };
+
+    let mut resolve: Box<dyn FnMut(&Conflict) -> Resolution> = match opts.policy {
+        MergePolicy::PreferLocal => Box::new(merge::prefer_local),
+        MergePolicy::PreferNewer => Box::new(merge::prefer_newer),
+        MergePolicy::Interactive => Box::new(ask_resolution),
+    };
+
+    match merge::merge(repo, &other, &mut *resolve) {
+        Ok(summary) => {
+            println!("Merged {}:", opts.path.display());
+            for line in summary.to_string().lines() {
+                println!("  {}", line);
+            }
+        }
+        Err(PersistenceError::AlreadyRunning(pid)) => {
+            eprintln!(
+                "Error: cannot merge while a pomodoro or break is running (pid {}).",
+                pid
+            );
+            process::exit(exit_code::ALREADY_RUNNING);
+        }
+        Err(e) => {
+            eprintln!("Error: {}.", e);
+            process::exit(e.exit_code());
+        }
+    }
+}
+
+fn cmd_archive(repo: &Repository, root: &Path, opts: &ArchiveCommand) {
+    let date = chrono::NaiveDate::parse_from_str(&opts.before, "%Y-%m-%d").unwrap_or_else(|e| {
+        eprintln!(
+            "Error: invalid --before date '{}': {}. Expected format: YYYY-MM-DD",
+            opts.before, e
+        );
+        process::exit(1);
+    });
+    let (before, _) = timezone::clock().day_bounds(date);
+    let path = rustomato::archive::path(root);
+
+    match rustomato::archive::archive(repo, &path, before) {
+        Ok(summary) => println!("Archived {} to {}", summary, path.display()),
+        Err(e) => {
+            eprintln!("Error: {}.", e);
+            process::exit(e.exit_code());
+        }
+    }
+}
+
+fn cmd_export(db_url: &Url, tz: Zone, opts: &ExportCommand) {
+    use rustomato::export::{Filter, Format, Table};
+
+    let repo = Repository::from_url(db_url);
+    use_perspective(&repo, tz, opts.times);
+    let table = match opts.table {
+        ExportTable::Schedulables => Table::Schedulables,
+        ExportTable::Interrupts => Table::Interrupts,
+        ExportTable::Annotations => Table::Annotations,
+    };
+    let is_csv = matches!(opts.format, ExportFormat::Csv);
+    let dialect_options = [
+        ("--columns", opts.columns.is_some()),
+        ("--delimiter", opts.delimiter.is_some()),
+        ("--no-header", opts.no_header),
+        ("--timestamps", opts.timestamps.is_some()),
+    ];
+    if !is_csv && let Some((option, _)) = dialect_options.iter().find(|(_, given)| *given) {
+        Opts::command()
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                format!("{} only applies to --format csv", option),
+            )
+            .exit();
+    }
+    let unsupported = if opts.bundle.is_some() && !is_csv {
+        Some("--bundle only writes CSV")
+    } else if opts.bundle.is_some() && (table != Table::Schedulables || opts.columns.is_some()) {
+        Some(
+            "--bundle writes all tables with all columns; --table and --columns cannot be used with it",
+        )
+    } else if table != Table::Schedulables && !is_csv {
+        Some("--table interrupts and --table annotations are only available as CSV")
+    } else {
+        None
+    };
+    if let Some(message) = unsupported {
+        eprintln!("Error: {}.", message);
+        process::exit(1);
+    }
+
+    let filter = Filter {
+        kind: opts.kind.map(|kind| match kind {
+            ExportKind::Pomodoro => Kind::Pomodoro,
+            ExportKind::Break => Kind::Break,
+        }),
+        statuses: opts
+            .status
+            .iter()
+            .map(|status| match status {
+                ExportStatus::Finished => Status::Finished,
+                ExportStatus::Cancelled => Status::Cancelled,
+                ExportStatus::Active => Status::Active,
+                ExportStatus::Stale => Status::Stale,
+            })
+            .collect(),
+        min_interruptions: opts.min_interruptions,
+        grep: opts.grep.clone(),
+    };
+
+    if let Some(dir) = &opts.bundle {
+        let dialect = csv_dialect(opts, table);
+        let paths = rustomato::export::cmd_export_bundle(
+            &repo,
+            opts.from.as_deref(),
+            opts.to.as_deref(),
+            &filter,
+            &dialect,
+            dir,
+        );
+        for path in paths {
+            println!("Exported to {}", path.display());
+        }
+        return;
+    }
+
+    let format = match opts.format {
+        ExportFormat::Csv => Format::Csv(table, csv_dialect(opts, table)),
+        ExportFormat::Json => Format::Json,
+        ExportFormat::Ndjson => Format::Ndjson,
+        ExportFormat::Ics => Format::Ics,
+        ExportFormat::Org => Format::Org,
+        ExportFormat::Timeclock => Format::Timeclock(rustomato::timeclock::Options {
+            account: opts.account.clone(),
+            include_cancelled: opts.include_cancelled,
+        }),
+    };
+    rustomato::export::cmd_export(
+        &repo,
+        opts.from.as_deref(),
+        opts.to.as_deref(),
+        &filter,
+        format,
+    );
+}
+
+/// The CSV dialect asked for on the command line. Exits on invalid options.
+fn csv_dialect(
+    opts: &ExportCommand,
+    table: rustomato::export::Table,
+) -> rustomato::export::Dialect {
+    let dialect = rustomato::export::Dialect {
+        header: !opts.no_header,
+        utc: opts.timestamps == Some(Timestamps::Utc),
+        ..Default::default()
+    }
+    .with_delimiter(opts.delimiter.as_deref().unwrap_or(","));
+    let dialect = match &opts.columns {
+        Some(columns) => dialect.and_then(|d| d.with_columns(table, columns)),
+        None => dialect,
+    };
+    dialect.unwrap_or_else(|e| {
+        eprintln!("Error: {}.", e);
+        process::exit(1);
+    })
+}
+
+fn cmd_import(repo: &Repository, opts: &ImportCommand) {
+    use rustomato::import::Format;
+
+    let format = match opts.from {
+        ImportFormat::Rustomato => Format::Rustomato,
+        ImportFormat::Timewarrior => Format::Timewarrior,
+        ImportFormat::Timeclock => Format::Timeclock,
+        ImportFormat::Csv => Format::Csv,
+    };
+    let records = std::fs::read_to_string(&opts.path)
+        .map_err(|e| e.to_string())
+        .and_then(|text| rustomato::import::parse(&text, format))
+        .unwrap_or_else(|e| {
+            eprintln!("Error: cannot import {}: {}.", opts.path.display(), e);
+            process::exit(1);
+        });
+
+    match rustomato::import::import(repo, &records, opts.dry_run) {
+        Ok(summary) => {
+            if !summary.conflicts.is_empty() {
+                eprintln!("Conflicts in {}:", opts.path.display());
+                for conflict in &summary.conflicts {
+                    eprintln!("  {}", conflict);
+                }
+                if !opts.dry_run {
+                    eprintln!("Error: nothing was imported.");
+                    process::exit(exit_code::OVERLAP);
+                }
+            }
+            println!("Imported {}:", opts.path.display());
+            for line in summary.to_string().lines() {
+                println!("  {}", line);
+            }
+            if opts.dry_run {
+                println!("Dry run; nothing was imported.");
+                if !summary.conflicts.is_empty() {
+                    process::exit(exit_code::OVERLAP);
+                }
+            }
+        }
+        Err(PersistenceError::AlreadyRunning(pid)) => {
+            eprintln!(
+                "Error: cannot import while a pomodoro or break is running (pid {}).",
+                pid
+            );
+            process::exit(exit_code::ALREADY_RUNNING);
+        }
+        Err(e) => {
+            eprintln!("Error: {}.", e);
+            process::exit(e.exit_code());
+        }
+    }
+}
+
+/// Ask on the terminal which side of a merge conflict to keep.
+/// Anything but an explicit choice for the other side keeps the local entries.
+fn ask_resolution(conflict: &rustomato::merge::Conflict) -> rustomato::merge::Resolution {
+    use rustomato::merge::Resolution;
+    use std::io::Write;
+
+    println!("Incoming {}", conflict.incoming);
+    for local in conflict.local {
+        println!("  conflicts with local {}", local);
+    }
+
+    loop {
+        print!("Keep [l]ocal or take [o]ther? ");
+        let _ = std::io::stdout().flush();
+
+        let mut answer = String::new();
+        match std::io::stdin().read_line(&mut answer) {
+            Ok(0) | Err(_) => return Resolution::KeepLocal,
+            Ok(_) => {}
+        }
+        match answer.trim() {
+            "l" | "local" => return Resolution::KeepLocal,
+            "o" | "other" => return Resolution::TakeOther,
+            _ => continue,
+        }
+    }
+}
+
+fn cmd_profile(base: &Path, requested: Option<&str>, opts: &ProfileCommand) {
+    use rustomato::profile;
+
+    let result = match &opts.subcmd {
+        ProfileCommands::List(_) => profile::list(base).map_err(|e| e.to_string()).map(|names| {
+            let current = requested
+                .map(str::to_string)
+                .unwrap_or_else(|| profile::default_profile(base));
+            for name in names {
+                let marker = if name == current { "*" } else { " " };
+                println!("{} {}", marker, name);
+            }
+        }),
+        ProfileCommands::Create(create) => profile::create(base, &create.name).map(|root| {
+            println!("Created profile {} in {}", create.name, root.display());
+        }),
+        ProfileCommands::Default(default) => match &default.name {
+            Some(name) => profile::set_default(base, name),
+            None => {
+                println!("{}", profile::default_profile(base));
+                Ok(())
+            }
+        },
+    };
+
+    if let Err(e) = result {
+        eprintln!("Error: {}.", e);
+        process::exit(1);
+    }
+}
+
+/// Switch the clock to showing times as recorded, if requested, using the
+/// offsets recorded in `repo`.
+fn use_perspective(repo: &Repository, zone: Zone, times: TimesAs) {
+    if let TimesAs::Recorded = times {
+        let recorded = repo.recorded_offsets().unwrap_or_else(|e| {
+            eprintln!("Error: {}.", e);
+            process::exit(e.exit_code());
+        });
+        timezone::set_clock(Clock::new(zone, Perspective::Recorded).with_recorded(recorded));
+    }
+}
+
+/// Combine the databases of all profiles into one read-only repository.
+fn all_profiles_repository(base: &Path, include_archive: bool) -> Repository {
+    let names = rustomato::profile::list(base).unwrap_or_else(|e| {
+        eprintln!("Error: cannot list profiles: {}.", e);
+        process::exit(1);
+    });
+    let paths: Vec<PathBuf> = names
+        .iter()
+        .map(|name| rustomato::profile::root_for(base, name))
+        .flat_map(|root| {
+            let archive = include_archive.then(|| rustomato::archive::path(&root));
+            std::iter::once(root.join("data.db")).chain(archive)
+        })
+        .filter(|path| path.is_file())
+        .collect();
+
+    Repository::combined(&paths).unwrap_or_else(|e| {
+        eprintln!("Error: {}.", e);
+        process::exit(1);
+    })
+}
+
 /// Show detailed information about a single schedulable.
 fn cmd_show(db_url: &Url, opts: &ShowCommand) {
     let repo = Repository::from_url(db_url);
@@ -972,7 +1936,7 @@ fn cmd_show(db_url: &Url, opts: &ShowCommand) {
         Ok(s) => s,
         Err(e) => {
             eprintln!("Error: {}.", e);
-            process::exit(1);
+            process::exit(e.exit_code());
         }
     };
 
@@ -1049,18 +2013,17 @@ fn cmd_show(db_url: &Url, opts: &ShowCommand) {
 /// - 1-6 days ago:   "Day HH:MM"   (e.g. "Sat 11:42")
 /// - 7+ days ago:    "YYYY-MM-DD"  (e.g. "2026-05-23")
 fn format_started(timestamp: i64) -> String {
-    use chrono::{Local, TimeZone};
-
     if timestamp == 0 {
         return "N/A".to_string();
     }
 
-    let dt = match Local.timestamp_opt(timestamp, 0).single() {
+    let clock = timezone::clock();
+    let dt = match clock.datetime(timestamp) {
         Some(dt) => dt,
         None => return timestamp.to_string(),
     };
 
-    let today = Local::now().date_naive();
+    let today = clock.today();
     let entry_date = dt.date_naive();
     let days_diff = (today - entry_date).num_days();
 
@@ -1075,14 +2038,10 @@ fn format_started(timestamp: i64) -> String {
 
 /// Build a human-readable timeline string for a schedulable.
 fn format_timeline(s: &Schedulable) -> String {
-    use chrono::Local;
-
     let elapsed_secs = match s.status() {
         rustomato::Status::Finished => s.finished_at - s.started_at,
         rustomato::Status::Cancelled => s.cancelled_at - s.started_at,
-        rustomato::Status::Active | rustomato::Status::Stale => {
-            Local::now().timestamp() - s.started_at
-        }
+        rustomato::Status::Active | rustomato::Status::Stale => rustomato::now() - s.started_at,
         rustomato::Status::New => 0,
     };
 
@@ -0,0 +1,310 @@
+use crate::persistence::{PersistenceError, Repository};
+use crate::{Schedulable, SqlUuid, Status, plural};
+use std::collections::HashSet;
+use std::fmt;
+use uuid::Uuid;
+
+/// How to settle an incoming entry that collides with local ones.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resolution {
+    KeepLocal,
+    TakeOther,
+}
+
+/// An incoming entry that cannot be added without breaking Rule #1, either
+/// because its time range overlaps local entries or because a different
+/// version of it (same UUID) already exists locally.
+pub struct Conflict<'a> {
+    pub incoming: &'a Schedulable,
+    pub local: &'a [Schedulable],
+}
+
+/// What [`merge`] did.
+#[derive(Debug, Default, PartialEq)]
+pub struct Summary {
+    pub added: usize,
+    pub duplicates: usize,
+    pub kept_local: usize,
+    pub took_other: usize,
+    pub replaced: usize,
+    pub running: usize,
+    pub annotations_added: usize,
+    pub annotations_dropped: usize,
+    pub interrupts_added: usize,
+    pub interrupts_dropped: usize,
+}
+
+/// Resolve every conflict in favour of the local database.
+pub fn prefer_local(_: &Conflict) -> Resolution {
+    Resolution::KeepLocal
+}
+
+/// Resolve a conflict in favour of the entry that ended last. Ties go to
+/// the local database.
+pub fn prefer_newer(conflict: &Conflict) -> Resolution {
+    let incoming = conflict.incoming.end();
+    if conflict.local.iter().all(|l| incoming > l.end()) {
+        Resolution::TakeOther
+    } else {
+        Resolution::KeepLocal
+    }
+}
+
+/// What to do with an incoming entry, decided before anything is written.
+enum Step {
+    /// The entry exists locally already; its annotations and interrupts may
+    /// still be new.
+    Duplicate(Schedulable),
+    /// Delete the conflicting local entries, if any, then add the entry.
+    Add(Schedulable, Vec<SqlUuid>),
+}
+
+/// Import everything from `other` into `local`, matching records by UUID.
+///
+/// Exact duplicates are skipped. Entries that are still running in `other`
+/// are skipped, too. Conflicting entries are passed to `resolve`; taking the
+/// other side deletes the conflicting local entries together with their
+/// annotations and interrupts. Annotations and interrupts come along with
+/// entries that are added or already exist locally; those of all other
+/// entries are dropped.
+///
+/// All conflicts are resolved before anything is written, so `resolve` may
+/// take its time (e.g. ask the user) without locking the database. The
+/// changes are then made in one transaction, so a failed merge leaves `local`
+/// unchanged. Merging is idempotent; merging the same database twice adds
+/// nothing.
+pub fn merge(
+    local: &Repository,
+    other: &Repository,
+    resolve: &mut dyn FnMut(&Conflict) -> Resolution,
+) -> Result<Summary, PersistenceError> {
+    if let Some(active) = local.active()?
+        && matches!(active.status(), Status::Active)
+    {
+        return Err(PersistenceError::AlreadyRunning(active.pid));
+    }
+
+    let mut summary = Summary::default();
+    let mut steps = Vec::new();
+    let mut deleted = HashSet::new();
+
+    for incoming in other.entries_between(i64::MIN, i64::MAX)? {
+        if !matches!(incoming.status(), Status::Finished | Status::Cancelled) {
+            summary.running += 1;
+            continue;
+        }
+
+        let same_uuid = local
+            .find_by_uuid(incoming.uuid)
+            .ok()
+            .filter(|existing| !deleted.contains(&existing.uuid.0));
+        if let Some(existing) = &same_uuid
+            && same_entry(existing, &incoming)
+        {
+            summary.duplicates += 1;
+            steps.push(Step::Duplicate(incoming));
+            continue;
+        }
+
+        // Local entries replaced for an earlier incoming entry no longer conflict
+        let mut conflicting: Vec<Schedulable> = local
+            .overlapping(incoming.started_at, incoming.end())?
+            .into_iter()
+            .filter(|c| !deleted.contains(&c.uuid.0))
+            .collect();
+        if let Some(existing) = same_uuid
+            && !conflicting.iter().any(|c| c.uuid.0 == existing.uuid.0)
+        {
+            conflicting.push(existing);
+        }
+
+        if !conflicting.is_empty() {
+            let conflict = Conflict {
+                incoming: &incoming,
+                local: &conflicting,
+            };
+            match resolve(&conflict) {
+                Resolution::KeepLocal => {
+                    summary.kept_local += 1;
+                    continue;
+                }
+                Resolution::TakeOther => {
+                    summary.took_other += 1;
+                    summary.replaced += conflicting.len();
+                    deleted.extend(conflicting.iter().map(|c| c.uuid.0));
+                }
+            }
+        }
+
+        summary.added += 1;
+        steps.push(Step::Add(
+            incoming,
+            conflicting.iter().map(|c| c.uuid).collect(),
+        ));
+    }
+
+    local.transaction(|| {
+        for step in &steps {
+            match step {
+                Step::Duplicate(entry) => {
+                    add_annotations(local, other, entry, &mut summary)?;
+                    add_missing_interrupts(local, other, entry, &mut summary)?;
+                }
+                Step::Add(entry, replaced) => {
+                    for uuid in replaced {
+                        local.delete(*uuid)?;
+                    }
+                    add(local, other, entry, &mut summary)?;
+                }
+            }
+        }
+
+        for annotation in other.annotations_between(i64::MIN, i64::MAX)? {
+            if local.find_annotation_by_uuid(annotation.uuid).is_err() {
+                summary.annotations_dropped += 1;
+            }
+        }
+
+        let merged = local_interrupts(local)?;
+        for interrupt in other.interrupts_between(i64::MIN, i64::MAX)? {
+            if !merged.contains(&interrupt.uuid.0) {
+                summary.interrupts_dropped += 1;
+            }
+        }
+
+        Ok(())
+    })?;
+
+    Ok(summary)
+}
+
+/// Save an incoming entry together with its annotations and interrupts.
+///
+/// Attaching an interrupt bumps the counter, so the entry is saved without
+/// the interruptions its log accounts for; the counter ends up where it was
+/// in `other` unless the log holds more interrupts than that.
+fn add(
+    local: &Repository,
+    other: &Repository,
+    incoming: &Schedulable,
+    summary: &mut Summary,
+) -> Result<(), PersistenceError> {
+    let interrupts = other.interrupts_for(incoming.uuid)?;
+    let mut entry = incoming.clone();
+    entry.interruptions = (entry.interruptions - interrupts.len() as i64).max(0);
+    local.save_external_ended(&entry)?;
+
+    add_annotations(local, other, incoming, summary)?;
+    for interrupt in &interrupts {
+        local.interrupt(interrupt)?;
+        summary.interrupts_added += 1;
+    }
+
+    Ok(())
+}
+
+/// Copy the annotations of `entry` that `local` does not have yet.
+fn add_annotations(
+    local: &Repository,
+    other: &Repository,
+    entry: &Schedulable,
+    summary: &mut Summary,
+) -> Result<(), PersistenceError> {
+    for annotation in other.annotations_for(entry.uuid)? {
+        if local.find_annotation_by_uuid(annotation.uuid).is_ok() {
+            continue;
+        }
+        local.save_annotation(&annotation)?;
+        summary.annotations_added += 1;
+    }
+    Ok(())
+}
+
+/// Copy the interrupts of an entry that exists on both sides and that `local`
+/// does not have yet. Both counters agree already, so they are left alone.
+fn add_missing_interrupts(
+    local: &Repository,
+    other: &Repository,
+    entry: &Schedulable,
+    summary: &mut Summary,
+) -> Result<(), PersistenceError> {
+    let existing = local.interrupts_for(entry.uuid)?;
+    for interrupt in other.interrupts_for(entry.uuid)? {
+        if existing.iter().any(|i| i.uuid.0 == interrupt.uuid.0) {
+            continue;
+        }
+        local.save_interrupt(&interrupt)?;
+        summary.interrupts_added += 1;
+    }
+    Ok(())
+}
+
+fn local_interrupts(local: &Repository) -> Result<HashSet<Uuid>, PersistenceError> {
+    Ok(local
+        .interrupts_between(i64::MIN, i64::MAX)?
+        .into_iter()
+        .map(|i| i.uuid.0)
+        .collect())
+}
+
+/// Same UUID and same recorded data; the PID is ignored.
+pub(crate) fn same_entry(a: &Schedulable, b: &Schedulable) -> bool {
+    a.uuid.0 == b.uuid.0
+        && a.kind == b.kind
+        && a.duration == b.duration
+        && a.started_at == b.started_at
+        && a.finished_at == b.finished_at
+        && a.cancelled_at == b.cancelled_at
+        && a.interruptions == b.interruptions
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} added", plural(self.added, "entry", "entries"))?;
+        writeln!(
+            f,
+            "{} skipped",
+            plural(self.duplicates, "duplicate", "duplicates")
+        )?;
+        if self.kept_local + self.took_other > 0 {
+            writeln!(
+                f,
+                "{}: {} kept local, {} taken from the other database ({} replaced)",
+                plural(self.kept_local + self.took_other, "conflict", "conflicts"),
+                self.kept_local,
+                self.took_other,
+                plural(self.replaced, "local entry", "local entries"),
+            )?;
+        }
+        if self.running > 0 {
+            writeln!(
+                f,
+                "{} skipped",
+                plural(self.running, "running entry", "running entries")
+            )?;
+        }
+        writeln!(
+            f,
+            "{} added",
+            plural(self.annotations_added, "annotation", "annotations")
+        )?;
+        writeln!(
+            f,
+            "{} added",
+            plural(self.interrupts_added, "interrupt", "interrupts")
+        )?;
+        let dropped = self.annotations_dropped + self.interrupts_dropped;
+        if dropped > 0 {
+            writeln!(
+                f,
+                "{} dropped because their entry was not merged",
+                plural(
+                    dropped,
+                    "annotation or interrupt",
+                    "annotations and interrupts"
+                )
+            )?;
+        }
+        Ok(())
+    }
+}
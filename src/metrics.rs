@@ -0,0 +1,315 @@
+//! Prometheus metrics for `rustomato metrics`.
+//!
+//! The metrics are written in the text exposition format, e.g. for the
+//! textfile collector of the node exporter:
+//!
+//! ```text
+//! # HELP rustomato_pomodori Pomodori started in the period, by how they ended.
+//! # TYPE rustomato_pomodori gauge
+//! rustomato_pomodori{period="today",status="completed"} 4
+//! ```
+
+use crate::persistence::{PersistenceError, Repository};
+use crate::report::{self, AggregateStats};
+use crate::{Kind, Status, timezone};
+use chrono::{Datelike, Duration};
+use std::fmt;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// What is running right now.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum State {
+    /// A pomodoro is running
+    Active,
+    /// A break is running
+    Break,
+    /// Nothing is running, or the process that ran it is gone
+    Idle,
+}
+
+impl State {
+    const ALL: [State; 3] = [State::Active, State::Break, State::Idle];
+
+    fn name(self) -> &'static str {
+        match self {
+            State::Active => "active",
+            State::Break => "break",
+            State::Idle => "idle",
+        }
+    }
+}
+
+/// A snapshot of the metrics at one point in time.
+#[derive(Debug)]
+pub struct Metrics {
+    today: AggregateStats,
+    week: AggregateStats,
+    total: AggregateStats,
+    state: State,
+    remaining_seconds: i64,
+    consecutive_pomodori: i64,
+}
+
+/// Collect the metrics as of `now`. Today and this week (starting on Monday)
+/// are those of the [`timezone::clock`].
+///
+/// The all-time totals are Prometheus counters, so `repo` should include the
+/// archive (see [`crate::archive::include`]); otherwise they drop whenever
+/// entries are archived.
+pub fn collect(repo: &Repository, now: i64) -> Result<Metrics, PersistenceError> {
+    let clock = timezone::clock();
+    let today = clock
+        .datetime(now)
+        .map(|dt| dt.date_naive())
+        .unwrap_or_else(|| clock.today());
+    let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+
+    let aggregate = |start: i64| -> Result<AggregateStats, PersistenceError> {
+        Ok(report::compute_aggregate(
+            &repo.entries_between(start, now)?,
+            &repo.interrupts_between(start, now)?,
+        ))
+    };
+
+    let (state, remaining_seconds) = match repo.active()? {
+        Some(s) if s.status() == Status::Active => {
+            let state = match s.kind {
+                Kind::Pomodoro => State::Active,
+                Kind::Break => State::Break,
+            };
+            (state, (s.started_at + s.duration * 60 - now).max(0))
+        }
+        _ => (State::Idle, 0),
+    };
+
+    Ok(Metrics {
+        today: aggregate(clock.day_bounds(today).0)?,
+        week: aggregate(clock.day_bounds(monday).0)?,
+        total: aggregate(0)?,
+        state,
+        remaining_seconds,
+        consecutive_pomodori: repo.consecutive_pomodoro_count_at(now)?,
+    })
+}
+
+impl fmt::Display for Metrics {
+    /// The metrics in the Prometheus text exposition format.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let periods = [("today", &self.today), ("week", &self.week)];
+
+        header(
+            f,
+            "rustomato_pomodori",
+            "gauge",
+            "Pomodori started in the period, by how they ended.",
+        )?;
+        for (period, stats) in periods {
+            writeln!(
+                f,
+                "rustomato_pomodori{{period=\"{}\",status=\"completed\"}} {}",
+                period, stats.completed
+            )?;
+            writeln!(
+                f,
+                "rustomato_pomodori{{period=\"{}\",status=\"cancelled\"}} {}",
+                period, stats.cancelled
+            )?;
+        }
+
+        header(
+            f,
+            "rustomato_interruptions",
+            "gauge",
+            "Interruptions recorded in the period, by kind.",
+        )?;
+        for (period, stats) in periods {
+            writeln!(
+                f,
+                "rustomato_interruptions{{period=\"{}\",kind=\"internal\"}} {}",
+                period, stats.internal_count
+            )?;
+            writeln!(
+                f,
+                "rustomato_interruptions{{period=\"{}\",kind=\"external\"}} {}",
+                period, stats.external_count
+            )?;
+        }
+
+        header(
+            f,
+            "rustomato_pomodori_completed_total",
+            "counter",
+            "Pomodori completed.",
+        )?;
+        writeln!(
+            f,
+            "rustomato_pomodori_completed_total {}",
+            self.total.completed
+        )?;
+        header(
+            f,
+            "rustomato_pomodori_cancelled_total",
+            "counter",
+            "Pomodori cancelled.",
+        )?;
+        writeln!(
+            f,
+            "rustomato_pomodori_cancelled_total {}",
+            self.total.cancelled
+        )?;
+        header(
+            f,
+            "rustomato_interruptions_total",
+            "counter",
+            "Interruptions recorded, by kind.",
+        )?;
+        writeln!(
+            f,
+            "rustomato_interruptions_total{{kind=\"internal\"}} {}",
+            self.total.internal_count
+        )?;
+        writeln!(
+            f,
+            "rustomato_interruptions_total{{kind=\"external\"}} {}",
+            self.total.external_count
+        )?;
+
+        header(
+            f,
+            "rustomato_state",
+            "gauge",
+            "Whether a pomodoro (active), a break or nothing (idle) is running.",
+        )?;
+        for state in State::ALL {
+            writeln!(
+                f,
+                "rustomato_state{{state=\"{}\"}} {}",
+                state.name(),
+                u8::from(state == self.state)
+            )?;
+        }
+
+        header(
+            f,
+            "rustomato_remaining_seconds",
+            "gauge",
+            "Seconds left of the running pomodoro or break.",
+        )?;
+        writeln!(f, "rustomato_remaining_seconds {}", self.remaining_seconds)?;
+        header(
+            f,
+            "rustomato_consecutive_pomodori",
+            "gauge",
+            "Pomodori finished since the last long break or midnight.",
+        )?;
+        writeln!(
+            f,
+            "rustomato_consecutive_pomodori {}",
+            self.consecutive_pomodori
+        )
+    }
+}
+
+fn header(f: &mut fmt::Formatter<'_>, name: &str, kind: &str, help: &str) -> fmt::Result {
+    writeln!(f, "# HELP {} {}", name, help)?;
+    writeln!(f, "# TYPE {} {}", name, kind)
+}
+
+/// Replace the file at `path` with `contents` atomically: the contents are
+/// written to a temporary file in the same directory, which is then renamed.
+/// Readers such as a metrics collector never see a partially written file.
+pub fn write_atomically(path: &Path, contents: &str) -> io::Result<()> {
+    let dir = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "not a file name"))?;
+    let tmp = dir.join(format!(
+        ".{}.{}.tmp",
+        name.to_string_lossy(),
+        std::process::id()
+    ));
+
+    let result = std::fs::File::create(&tmp)
+        .and_then(|mut file| {
+            file.write_all(contents.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|()| std::fs::rename(&tmp, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let metrics = Metrics {
+            today: AggregateStats {
+                completed: 3,
+                cancelled: 1,
+                internal_count: 2,
+                ..Default::default()
+            },
+            week: AggregateStats {
+                completed: 10,
+                external_count: 4,
+                ..Default::default()
+            },
+            total: AggregateStats {
+                completed: 42,
+                cancelled: 5,
+                internal_count: 7,
+                external_count: 9,
+                ..Default::default()
+            },
+            state: State::Break,
+            remaining_seconds: 120,
+            consecutive_pomodori: 3,
+        };
+
+        let text = metrics.to_string();
+        for line in [
+            "# TYPE rustomato_pomodori gauge\n",
+            "rustomato_pomodori{period=\"today\",status=\"completed\"} 3\n",
+            "rustomato_pomodori{period=\"today\",status=\"cancelled\"} 1\n",
+            "rustomato_pomodori{period=\"week\",status=\"completed\"} 10\n",
+            "rustomato_interruptions{period=\"today\",kind=\"internal\"} 2\n",
+            "rustomato_interruptions{period=\"week\",kind=\"external\"} 4\n",
+            "# TYPE rustomato_pomodori_completed_total counter\n",
+            "rustomato_pomodori_completed_total 42\n",
+            "rustomato_pomodori_cancelled_total 5\n",
+            "rustomato_interruptions_total{kind=\"external\"} 9\n",
+            "rustomato_state{state=\"active\"} 0\n",
+            "rustomato_state{state=\"break\"} 1\n",
+            "rustomato_state{state=\"idle\"} 0\n",
+            "rustomato_remaining_seconds 120\n",
+            "rustomato_consecutive_pomodori 3\n",
+        ] {
+            assert!(text.contains(line), "missing {:?} in\n{}", line, text);
+        }
+        assert!(text.ends_with('\n'));
+        // Every metric is announced before its samples
+        assert_eq!(text.matches("# HELP ").count(), 8);
+        assert_eq!(text.matches("# TYPE ").count(), 8);
+    }
+
+    #[test]
+    fn test_write_atomically() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rustomato.prom");
+
+        write_atomically(&path, "a 1\n").unwrap();
+        write_atomically(&path, "a 2\n").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "a 2\n");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
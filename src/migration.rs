@@ -27,6 +27,10 @@ const MIGRATIONS: &[(&str, &str)] = &[
         "V8__add_cascade_delete",
         include_str!("../migrations/V8__add_cascade_delete.sql"),
     ),
+    (
+        "V9__add_utc_offset",
+        include_str!("../migrations/V9__add_utc_offset.sql"),
+    ),
 ];
 
 pub fn run(conn: &Connection) {
@@ -34,20 +38,67 @@ pub fn run(conn: &Connection) {
         .expect("creating _migrations table");
 
     for (name, sql) in MIGRATIONS {
-        let already_run: bool = conn
-            .query_row(
-                "SELECT COUNT(*) > 0 FROM _migrations WHERE name = ?1",
-                params![name],
-                |row| row.get(0),
-            )
-            .unwrap_or(false);
-
-        if !already_run {
-            let wrapped = format!("BEGIN;\n{sql}\nCOMMIT;");
-            conn.execute_batch(&wrapped)
-                .unwrap_or_else(|e| panic!("running migration {name}: {e}"));
+        if already_run(conn, name) {
+            continue;
+        }
+
+        // Take the write lock before checking again, so that processes
+        // starting at the same time do not apply a migration twice.
+        conn.execute_batch("BEGIN IMMEDIATE;")
+            .unwrap_or_else(|e| panic!("locking the database for migration {name}: {e}"));
+        if already_run(conn, name) {
+            conn.execute_batch("COMMIT;")
+                .unwrap_or_else(|e| panic!("releasing the database after migration {name}: {e}"));
+            continue;
+        }
+
+        let result = conn.execute_batch(sql).and_then(|_| {
             conn.execute("INSERT INTO _migrations (name) VALUES (?1)", params![name])
-                .unwrap_or_else(|e| panic!("recording migration {name}: {e}"));
+        });
+        if let Err(e) = result {
+            let _ = conn.execute_batch("ROLLBACK;");
+            panic!("running migration {name}: {e}");
         }
+        conn.execute_batch("COMMIT;")
+            .unwrap_or_else(|e| panic!("committing migration {name}: {e}"));
+    }
+}
+
+fn already_run(conn: &Connection, name: &str) -> bool {
+    conn.query_row(
+        "SELECT COUNT(*) > 0 FROM _migrations WHERE name = ?1",
+        params![name],
+        |row| row.get(0),
+    )
+    .unwrap_or(false)
+}
+
+/// Check whether the database behind `conn` can be used with this build.
+///
+/// Returns an error if the database was not created by rustomato (no
+/// `_migrations` table) or if it has migrations applied that this build
+/// does not know about, i.e. it was written by a newer version.
+pub fn check_compatible(conn: &Connection) -> Result<(), String> {
+    let mut stmt = conn
+        .prepare("SELECT name FROM _migrations ORDER BY name")
+        .map_err(|e| format!("not a rustomato database: {}", e))?;
+    let applied: Vec<String> = stmt
+        .query_map([], |row| row.get(0))
+        .and_then(|rows| rows.collect())
+        .map_err(|e| format!("cannot read migrations: {}", e))?;
+
+    let unknown: Vec<&str> = applied
+        .iter()
+        .map(String::as_str)
+        .filter(|name| !MIGRATIONS.iter().any(|(known, _)| known == name))
+        .collect();
+
+    if unknown.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "database has migrations unknown to this version ({}); upgrade rustomato first",
+            unknown.join(", ")
+        ))
     }
 }
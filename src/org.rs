@@ -0,0 +1,140 @@
+//! Org-mode output for `export --format org`.
+//!
+//! Entries are grouped under a heading per day, each with its clock line,
+//! its interruptions as a property and its annotations as a list:
+//!
+//! ```text
+//! * [2026-05-29 Fri]
+//! ** DONE Pomodoro
+//! :PROPERTIES:
+//! :ID:       1b4e28ba2fa1416d9a0a3e8f9c1d2e3f
+//! :INTERRUPTIONS: 1
+//! :END:
+//! CLOCK: [2026-05-29 Fri 10:00]--[2026-05-29 Fri 10:25] =>  0:25
+//! - [2026-05-29 Fri 10:25] wrote the tests
+//! ```
+
+use crate::{Annotation, Schedulable, Status, timezone};
+
+/// Render entries as an org tree, in the order given. Days are those of the
+/// [`timezone::clock`].
+pub fn tree(entries: &[(Schedulable, Vec<Annotation>)]) -> String {
+    let mut out = String::from("#+TODO: ACTIVE STALE | DONE CANCELLED\n");
+    let mut day = String::new();
+    for (s, annotations) in entries {
+        let this_day = timezone::clock().format(s.started_at, "[%Y-%m-%d %a]");
+        if this_day != day {
+            out.push_str(&format!("* {}\n", this_day));
+            day = this_day;
+        }
+        entry(&mut out, s, annotations);
+    }
+    out
+}
+
+fn entry(out: &mut String, s: &Schedulable, annotations: &[Annotation]) {
+    let kind = match s.kind {
+        crate::Kind::Pomodoro => "Pomodoro",
+        crate::Kind::Break => "Break",
+    };
+    let (keyword, end) = match s.status() {
+        Status::Finished => ("DONE", Some(s.finished_at)),
+        Status::Cancelled => ("CANCELLED", Some(s.cancelled_at)),
+        Status::Stale => ("STALE", None),
+        _ => ("ACTIVE", None),
+    };
+
+    out.push_str(&format!("** {} {}\n", keyword, kind));
+    out.push_str(":PROPERTIES:\n");
+    out.push_str(&format!(":ID:       {}\n", s.uuid));
+    out.push_str(&format!(":INTERRUPTIONS: {}\n", s.interruptions));
+    out.push_str(":END:\n");
+
+    match end {
+        Some(end) => out.push_str(&format!(
+            "CLOCK: {}--{} => {}\n",
+            stamp(s.started_at),
+            stamp(end),
+            duration(s.started_at, end)
+        )),
+        // A clock that is still running
+        None => out.push_str(&format!("CLOCK: {}\n", stamp(s.started_at))),
+    }
+
+    for annotation in annotations {
+        let mut lines = annotation.body.lines();
+        out.push_str(&format!(
+            "- {} {}\n",
+            stamp(annotation.created_at),
+            lines.next().unwrap_or_default()
+        ));
+        // Continuation lines are indented so they stay in the list item
+        for line in lines {
+            out.push_str(&format!("  {}\n", line));
+        }
+    }
+}
+
+/// An inactive org timestamp, e.g. `[2026-05-29 Fri 10:00]`.
+fn stamp(ts: i64) -> String {
+    timezone::clock().format(ts, "[%Y-%m-%d %a %H:%M]")
+}
+
+/// The clocked time as org writes it, e.g. ` 0:25`. Like org, this counts
+/// the minutes between the timestamps as shown, ignoring the seconds.
+fn duration(start: i64, end: i64) -> String {
+    let minutes = end.div_euclid(60) - start.div_euclid(60);
+    format!("{:2}:{:02}", minutes / 60, minutes % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Kind;
+
+    #[test]
+    fn test_duration() {
+        assert_eq!(duration(0, 1500), " 0:25");
+        assert_eq!(duration(59, 1501), " 0:25");
+        assert_eq!(duration(0, 36_000), "10:00");
+    }
+
+    #[test]
+    fn test_tree() {
+        let clock = timezone::clock();
+        let mut pom = Schedulable::new(0, Kind::Pomodoro, 25);
+        pom.started_at = 1_780_048_800;
+        pom.finished_at = pom.started_at + 1500;
+        pom.interruptions = 1;
+        let note = Annotation {
+            uuid: crate::SqlUuid::default(),
+            schedulable_uuid: pom.uuid,
+            body: "wrote tests\n* not a heading".to_string(),
+            created_at: pom.finished_at,
+        };
+        let mut brk = Schedulable::new(0, Kind::Break, 5);
+        brk.started_at = pom.finished_at;
+        brk.cancelled_at = brk.started_at + 120;
+
+        let org = tree(&[(pom.clone(), vec![note]), (brk, vec![])]);
+
+        let day = clock.format(pom.started_at, "[%Y-%m-%d %a]");
+        assert!(org.starts_with(&format!(
+            "#+TODO: ACTIVE STALE | DONE CANCELLED\n* {}\n",
+            day
+        )));
+        assert_eq!(org.matches("\n* ").count(), 1);
+        assert!(org.contains(&format!(
+            "** DONE Pomodoro\n:PROPERTIES:\n:ID:       {}\n:INTERRUPTIONS: 1\n:END:\n",
+            pom.uuid
+        )));
+        assert!(org.contains(&format!(
+            "CLOCK: {}--{} =>  0:25\n- {} wrote tests\n  * not a heading\n",
+            stamp(pom.started_at),
+            stamp(pom.finished_at),
+            stamp(pom.finished_at)
+        )));
+        assert!(org.contains("** CANCELLED Break\n"));
+        assert!(org.contains("=>  0:02\n"));
+    }
+}
@@ -1,9 +1,14 @@
 use super::{Annotation, InterruptLog, InterruptionKind, Kind, Schedulable, SqlUuid, Status};
+use crate::exit_code;
 use rusqlite::Connection;
 use rusqlite::Error::QueryReturnedNoRows;
+use rusqlite::ErrorCode;
 use rusqlite::OpenFlags;
+use rusqlite::backup::Progress;
 use rusqlite::params;
 use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use url::Url;
 use uuid::Uuid;
 
@@ -11,7 +16,7 @@ pub struct Repository {
     db: Connection,
 }
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug)]
 pub enum PersistenceError {
     CannotSave(String),
     CannotUpdate(String),
@@ -19,6 +24,11 @@ pub enum PersistenceError {
     AlreadyRunning(u32),
     OverlappingTimeRange,
     CannotDelete(String),
+    CannotBackup(String),
+    CannotRestore(String),
+    ConstraintViolation(String),
+    Locked(String),
+    Io(String),
 }
 
 impl fmt::Display for PersistenceError {
@@ -32,30 +42,73 @@ impl fmt::Display for PersistenceError {
                 write!(f, "Time range overlaps with an existing entry (Rule #1)")
             }
             PersistenceError::CannotDelete(e) => write!(f, "Cannot delete: {}", e),
+            PersistenceError::CannotBackup(e) => write!(f, "Cannot back up: {}", e),
+            PersistenceError::CannotRestore(e) => write!(f, "Cannot restore: {}", e),
+            PersistenceError::ConstraintViolation(e) => write!(f, "Invalid data: {}", e),
+            PersistenceError::Locked(e) => write!(f, "Database is locked: {}", e),
+            PersistenceError::Io(e) => write!(f, "Cannot access the database: {}", e),
+        }
+    }
+}
+
+impl PersistenceError {
+    /// The process exit code that reports this error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            PersistenceError::AlreadyRunning(_) => exit_code::ALREADY_RUNNING,
+            PersistenceError::CannotFind(_) => exit_code::NOT_FOUND,
+            PersistenceError::OverlappingTimeRange => exit_code::OVERLAP,
+            PersistenceError::ConstraintViolation(_) => exit_code::CONSTRAINT_VIOLATION,
+            PersistenceError::Locked(_) => exit_code::DATABASE_LOCKED,
+            PersistenceError::Io(_) | PersistenceError::CannotBackup(_) => exit_code::IO,
+            PersistenceError::CannotSave(_)
+            | PersistenceError::CannotUpdate(_)
+            | PersistenceError::CannotDelete(_)
+            | PersistenceError::CannotRestore(_) => exit_code::ERROR,
         }
     }
 }
 
 // ── Error helpers ────────────────────────────────────────────────
 
-/// Shorthand for `PersistenceError::CannotFind(e.to_string())`.
+/// Recognize the causes callers may want to react to specifically: Rule #1
+/// violations, other constraint violations, a locked database and I/O
+/// failures. Returns `None` for everything else.
+fn classify(e: &rusqlite::Error) -> Option<PersistenceError> {
+    let msg = e.to_string();
+    match e.sqlite_error_code()? {
+        ErrorCode::ConstraintViolation if msg.contains("Rule #1") => {
+            Some(PersistenceError::OverlappingTimeRange)
+        }
+        ErrorCode::ConstraintViolation => Some(PersistenceError::ConstraintViolation(msg)),
+        ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked => Some(PersistenceError::Locked(msg)),
+        ErrorCode::CannotOpen
+        | ErrorCode::SystemIoFailure
+        | ErrorCode::DiskFull
+        | ErrorCode::ReadOnly
+        | ErrorCode::PermissionDenied => Some(PersistenceError::Io(msg)),
+        _ => None,
+    }
+}
+
+/// Shorthand for `PersistenceError::CannotFind(e.to_string())`, unless [`classify`] knows better.
 fn find_err(e: rusqlite::Error) -> PersistenceError {
-    PersistenceError::CannotFind(e.to_string())
+    classify(&e).unwrap_or_else(|| PersistenceError::CannotFind(e.to_string()))
 }
 
-/// Shorthand for `PersistenceError::CannotSave(e.to_string())`.
+/// Shorthand for `PersistenceError::CannotSave(e.to_string())`, unless [`classify`] knows better.
 fn save_err(e: rusqlite::Error) -> PersistenceError {
-    PersistenceError::CannotSave(e.to_string())
+    classify(&e).unwrap_or_else(|| PersistenceError::CannotSave(e.to_string()))
 }
 
-/// Shorthand for `PersistenceError::CannotUpdate(e.to_string())`.
+/// Shorthand for `PersistenceError::CannotUpdate(e.to_string())`, unless [`classify`] knows better.
 fn update_err(e: rusqlite::Error) -> PersistenceError {
-    PersistenceError::CannotUpdate(e.to_string())
+    classify(&e).unwrap_or_else(|| PersistenceError::CannotUpdate(e.to_string()))
 }
 
-/// Shorthand for `PersistenceError::CannotDelete(e.to_string())`.
+/// Shorthand for `PersistenceError::CannotDelete(e.to_string())`, unless [`classify`] knows better.
 fn delete_err(e: rusqlite::Error) -> PersistenceError {
-    PersistenceError::CannotDelete(e.to_string())
+    classify(&e).unwrap_or_else(|| PersistenceError::CannotDelete(e.to_string()))
 }
 
 // ── Row mappers ──────────────────────────────────────────────────
@@ -78,6 +131,7 @@ fn row_to_schedulable(row: &rusqlite::Row<'_>) -> rusqlite::Result<Schedulable>
         finished_at: row.get(5).unwrap_or(0),
         cancelled_at: row.get(6).unwrap_or(0),
         interruptions: row.get(7).unwrap_or(0),
+        utc_offset: row.get(8)?,
     })
 }
 
@@ -119,6 +173,32 @@ fn row_to_interrupt_log(row: &rusqlite::Row<'_>) -> rusqlite::Result<InterruptLo
     })
 }
 
+/// Read `RUSTOMATO_BUSY_TIMEOUT` (milliseconds) or default to 5 seconds.
+///
+/// While another process holds the write lock, SQLite retries until this
+/// timeout has passed before giving up with [`PersistenceError::Locked`].
+fn busy_timeout() -> Duration {
+    std::env::var("RUSTOMATO_BUSY_TIMEOUT")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .map(Duration::from_millis)
+        .unwrap_or(Duration::from_secs(5))
+}
+
+/// Bring the schema up to date.
+fn migrate(db: &Connection) {
+    // Foreign key enforcement must be OFF during migrations because
+    // V6 drops and recreates the schedulables table, and V4 has already
+    // created the annotations table with a FK reference to schedulables.
+    // With FKs ON, SQLite would reject the DROP TABLE when annotation
+    // rows exist. Enforcement is re-enabled after migrations complete.
+    db.execute_batch("PRAGMA foreign_keys = OFF;")
+        .expect("disabling foreign key enforcement for migration");
+    crate::migration::run(db);
+    db.execute_batch("PRAGMA foreign_keys = ON;")
+        .expect("enabling foreign key enforcement");
+}
+
 impl Repository {
     pub fn new(location: &str) -> Self {
         let db = Connection::open_with_flags(
@@ -128,16 +208,17 @@ impl Repository {
                 | OpenFlags::SQLITE_OPEN_URI,
         )
         .expect("opening database connection");
-        // Foreign key enforcement must be OFF during migrations because
-        // V6 drops and recreates the schedulables table, and V4 has already
-        // created the annotations table with a FK reference to schedulables.
-        // With FKs ON, SQLite would reject the DROP TABLE when annotation
-        // rows exist. Enforcement is re-enabled after migrations complete.
-        db.execute_batch("PRAGMA foreign_keys = OFF;")
-            .expect("disabling foreign key enforcement for migration");
-        crate::migration::run(&db);
-        db.execute_batch("PRAGMA foreign_keys = ON;")
-            .expect("enabling foreign key enforcement");
+        db.busy_timeout(busy_timeout())
+            .expect("setting the busy timeout");
+
+        // Hooks often call rustomato while the timer process holds a
+        // connection. With write-ahead logging, readers and the writer do not
+        // block each other. In-memory databases stay in "memory" mode.
+        let _mode: String = db
+            .query_row("PRAGMA journal_mode = WAL", [], |row| row.get(0))
+            .expect("enabling write-ahead logging");
+
+        migrate(&db);
         Self { db }
     }
 
@@ -145,6 +226,55 @@ impl Repository {
         Self::new(location.as_str())
     }
 
+    /// Change how long to wait for another process to release the database.
+    pub fn set_busy_timeout(&self, timeout: Duration) -> Result<(), PersistenceError> {
+        self.db.busy_timeout(timeout).map_err(update_err)
+    }
+
+    /// Run `f` in a transaction that holds the write lock from the start, so
+    /// that it cannot fail halfway because another process started writing.
+    /// The transaction is rolled back if `f` fails. Nested calls join the
+    /// outermost transaction.
+    pub fn transaction<T>(
+        &self,
+        f: impl FnOnce() -> Result<T, PersistenceError>,
+    ) -> Result<T, PersistenceError> {
+        if !self.db.is_autocommit() {
+            return f();
+        }
+
+        self.db
+            .execute_batch("BEGIN IMMEDIATE;")
+            .map_err(update_err)?;
+        match f() {
+            Ok(value) => {
+                self.db.execute_batch("COMMIT;").map_err(update_err)?;
+                Ok(value)
+            }
+            Err(e) => {
+                let _ = self.db.execute_batch("ROLLBACK;");
+                Err(e)
+            }
+        }
+    }
+
+    /// Run `f` and undo all of its changes afterwards, whatever the outcome.
+    /// Constraints and triggers are checked as usual, so this tells what `f`
+    /// would have done.
+    pub fn rehearse<T>(
+        &self,
+        f: impl FnOnce() -> Result<T, PersistenceError>,
+    ) -> Result<T, PersistenceError> {
+        self.db
+            .execute_batch("SAVEPOINT rehearsal;")
+            .map_err(update_err)?;
+        let result = f();
+        self.db
+            .execute_batch("ROLLBACK TO rehearsal; RELEASE rehearsal;")
+            .map_err(update_err)?;
+        result
+    }
+
     pub fn active(&self) -> Result<Option<Schedulable>, PersistenceError> {
         match self.db.query_row(
             "SELECT uuid from schedulables where pid IS NOT NULL",
@@ -164,7 +294,7 @@ impl Repository {
         let uuid_s = uuid.to_string();
 
         match self.db.query_row(
-            "SELECT uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions from schedulables where uuid=?1",
+            "SELECT uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions, utc_offset from schedulables where uuid=?1",
             params![uuid_s],
             row_to_schedulable,
         ) {
@@ -176,6 +306,17 @@ impl Repository {
         }
     }
 
+    /// Increment the interruption counter of the interrupted schedulable and
+    /// add `log` to the interrupt log, both or neither. Returns the updated
+    /// schedulable.
+    pub fn interrupt(&self, log: &InterruptLog) -> Result<Schedulable, PersistenceError> {
+        self.transaction(|| {
+            let updated = self.record_interrupt(log.schedulable_uuid)?;
+            self.save_interrupt(log)?;
+            Ok(updated)
+        })
+    }
+
     /// Increment the interruption counter for the schedulable with the given UUID.
     /// Returns the updated schedulable.
     pub fn record_interrupt(&self, uuid: SqlUuid) -> Result<Schedulable, PersistenceError> {
@@ -197,7 +338,7 @@ impl Repository {
     /// Find the most recently ended schedulable (finished or cancelled) of any kind.
     pub fn most_recently_ended(&self) -> Result<Option<Schedulable>, PersistenceError> {
         match self.db.query_row(
-            "SELECT uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions \
+            "SELECT uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions, utc_offset \
              FROM schedulables \
              WHERE finished_at IS NOT NULL OR cancelled_at IS NOT NULL \
              ORDER BY COALESCE(finished_at, cancelled_at) DESC \
@@ -207,7 +348,7 @@ impl Repository {
         ) {
             Ok(val) => Ok(Some(val)),
             Err(QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(PersistenceError::CannotFind(format!("{}", e))),
+            Err(e) => Err(find_err(e)),
         }
     }
 
@@ -238,7 +379,7 @@ impl Repository {
             row_to_annotation,
         ) {
             Ok(val) => Ok(val),
-            Err(e) => Err(PersistenceError::CannotFind(format!("{}", e))),
+            Err(e) => Err(find_err(e)),
         }
     }
 
@@ -253,15 +394,15 @@ impl Repository {
             .prepare(
                 "SELECT uuid, schedulable_uuid, body, created_at FROM annotations WHERE schedulable_uuid=?1 ORDER BY created_at ASC",
             )
-            .map_err(|e| PersistenceError::CannotFind(format!("{}", e)))?;
+            .map_err(find_err)?;
 
         let rows = stmt
             .query_map(params![uuid_s], row_to_annotation)
-            .map_err(|e| PersistenceError::CannotFind(format!("{}", e)))?;
+            .map_err(find_err)?;
 
         let mut result = Vec::new();
         for row in rows {
-            result.push(row.map_err(|e| PersistenceError::CannotFind(format!("{}", e)))?);
+            result.push(row.map_err(find_err)?);
         }
         Ok(result)
     }
@@ -269,7 +410,7 @@ impl Repository {
     /// Find the most recently finished pomodoro across all time.
     pub fn most_recently_finished_pomodoro(&self) -> Result<Option<Schedulable>, PersistenceError> {
         match self.db.query_row(
-            "SELECT uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions \
+            "SELECT uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions, utc_offset \
              FROM schedulables \
              WHERE kind = 'pomodoro' AND finished_at != 0 \
              ORDER BY finished_at DESC \
@@ -279,14 +420,14 @@ impl Repository {
         ) {
             Ok(val) => Ok(Some(val)),
             Err(QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(PersistenceError::CannotFind(format!("{}", e))),
+            Err(e) => Err(find_err(e)),
         }
     }
 
     /// Find the most recently finished break across all time.
     pub fn most_recently_finished_break(&self) -> Result<Option<Schedulable>, PersistenceError> {
         match self.db.query_row(
-            "SELECT uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions \
+            "SELECT uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions, utc_offset \
              FROM schedulables \
              WHERE kind = 'break' AND finished_at != 0 \
              ORDER BY finished_at DESC \
@@ -296,7 +437,7 @@ impl Repository {
         ) {
             Ok(val) => Ok(Some(val)),
             Err(QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(PersistenceError::CannotFind(format!("{}", e))),
+            Err(e) => Err(find_err(e)),
         }
     }
 
@@ -306,18 +447,18 @@ impl Repository {
         let mut stmt = self
             .db
             .prepare(
-                "SELECT uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions \
+                "SELECT uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions, utc_offset \
                  FROM schedulables \
                  WHERE uuid LIKE ?1",
             )
-            .map_err(|e| PersistenceError::CannotFind(format!("{}", e)))?;
+            .map_err(find_err)?;
 
         // The oldest UUID prefixes in the DB may be shorter than 6 chars for very old entries,
         // so we match the prefix followed by '%'
         let pattern = format!("{}%%", prefix);
         let rows: Vec<Schedulable> = stmt
             .query_map(params![pattern], row_to_schedulable)
-            .map_err(|e| PersistenceError::CannotFind(format!("{}", e)))?
+            .map_err(find_err)?
             .filter_map(|r| r.ok())
             .collect();
 
@@ -354,14 +495,14 @@ impl Repository {
         let mut stmt = self
             .db
             .prepare(
-                "SELECT uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions \
+                "SELECT uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions, utc_offset \
                  FROM schedulables \
                  WHERE (?1 IS NULL OR kind = ?1) \
                    AND (?2 IS NULL OR uuid != ?2) \
                  ORDER BY started_at DESC \
                  LIMIT 1 OFFSET ?3",
             )
-            .map_err(|e| PersistenceError::CannotFind(format!("{}", e)))?;
+            .map_err(find_err)?;
 
         match stmt.query_row(
             params![kind_param, exclude_param, offset],
@@ -369,7 +510,7 @@ impl Repository {
         ) {
             Ok(val) => Ok(Some(val)),
             Err(QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(PersistenceError::CannotFind(format!("{}", e))),
+            Err(e) => Err(find_err(e)),
         }
     }
 
@@ -383,18 +524,18 @@ impl Repository {
         let mut stmt = self
             .db
             .prepare(
-                "SELECT uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions \
+                "SELECT uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions, utc_offset \
                  FROM schedulables \
                  WHERE kind = 'pomodoro' AND finished_at != 0 \
                  ORDER BY finished_at DESC \
                  LIMIT 1 OFFSET ?1",
             )
-            .map_err(|e| PersistenceError::CannotFind(format!("{}", e)))?;
+            .map_err(find_err)?;
 
         match stmt.query_row(params![offset], row_to_schedulable) {
             Ok(val) => Ok(Some(val)),
             Err(QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(PersistenceError::CannotFind(format!("{}", e))),
+            Err(e) => Err(find_err(e)),
         }
     }
 
@@ -407,7 +548,7 @@ impl Repository {
         let mut stmt = self
             .db
             .prepare(
-                "SELECT uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions \
+                "SELECT uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions, utc_offset \
                  FROM schedulables \
                  WHERE started_at <= ?1 \
                    AND (finished_at IS NULL OR finished_at >= ?1) \
@@ -415,12 +556,12 @@ impl Repository {
                  ORDER BY started_at DESC \
                  LIMIT 1",
             )
-            .map_err(|e| PersistenceError::CannotFind(format!("{}", e)))?;
+            .map_err(find_err)?;
 
         match stmt.query_row(params![ts], row_to_schedulable) {
             Ok(val) => Ok(Some(val)),
             Err(QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(PersistenceError::CannotFind(format!("{}", e))),
+            Err(e) => Err(find_err(e)),
         }
     }
 
@@ -458,15 +599,15 @@ impl Repository {
              WHERE schedulable_uuid=?1 \
              ORDER BY created_at ASC",
             )
-            .map_err(|e| PersistenceError::CannotFind(format!("{}", e)))?;
+            .map_err(find_err)?;
 
         let rows = stmt
             .query_map(params![uuid_s], row_to_interrupt_log)
-            .map_err(|e| PersistenceError::CannotFind(format!("{}", e)))?;
+            .map_err(find_err)?;
 
         let mut result = Vec::new();
         for row in rows {
-            result.push(row.map_err(|e| PersistenceError::CannotFind(format!("{}", e)))?);
+            result.push(row.map_err(find_err)?);
         }
         Ok(result)
     }
@@ -485,15 +626,15 @@ impl Repository {
              WHERE created_at >= ?1 AND created_at <= ?2 \
              ORDER BY created_at ASC",
             )
-            .map_err(|e| PersistenceError::CannotFind(format!("{}", e)))?;
+            .map_err(find_err)?;
 
         let rows = stmt
             .query_map(params![start, end], row_to_interrupt_log)
-            .map_err(|e| PersistenceError::CannotFind(format!("{}", e)))?;
+            .map_err(find_err)?;
 
         let mut result = Vec::new();
         for row in rows {
-            result.push(row.map_err(|e| PersistenceError::CannotFind(format!("{}", e)))?);
+            result.push(row.map_err(find_err)?);
         }
         Ok(result)
     }
@@ -512,15 +653,15 @@ impl Repository {
              WHERE created_at >= ?1 AND created_at <= ?2 \
              ORDER BY created_at ASC",
             )
-            .map_err(|e| PersistenceError::CannotFind(format!("{}", e)))?;
+            .map_err(find_err)?;
 
         let rows = stmt
             .query_map(params![start, end], row_to_annotation)
-            .map_err(|e| PersistenceError::CannotFind(format!("{}", e)))?;
+            .map_err(find_err)?;
 
         let mut result = Vec::new();
         for row in rows {
-            result.push(row.map_err(|e| PersistenceError::CannotFind(format!("{}", e)))?);
+            result.push(row.map_err(find_err)?);
         }
         Ok(result)
     }
@@ -530,20 +671,39 @@ impl Repository {
         let mut stmt = self
             .db
             .prepare(
-                "SELECT uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions \
+                "SELECT uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions, utc_offset \
              FROM schedulables \
              ORDER BY started_at DESC \
              LIMIT ?1",
             )
-            .map_err(|e| PersistenceError::CannotFind(format!("{}", e)))?;
+            .map_err(find_err)?;
 
         let rows = stmt
             .query_map(params![limit], row_to_schedulable)
-            .map_err(|e| PersistenceError::CannotFind(format!("{}", e)))?;
+            .map_err(find_err)?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row.map_err(find_err)?);
+        }
+        Ok(result)
+    }
+
+    /// The start times of all entries with the UTC offsets recorded for them
+    /// (`None` for entries that predate recording offsets), ordered by start time.
+    pub fn recorded_offsets(&self) -> Result<Vec<(i64, Option<i32>)>, PersistenceError> {
+        let mut stmt = self
+            .db
+            .prepare("SELECT started_at, utc_offset FROM schedulables ORDER BY started_at ASC")
+            .map_err(find_err)?;
+
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(find_err)?;
 
         let mut result = Vec::new();
         for row in rows {
-            result.push(row.map_err(|e| PersistenceError::CannotFind(format!("{}", e)))?);
+            result.push(row.map_err(find_err)?);
         }
         Ok(result)
     }
@@ -556,20 +716,20 @@ impl Repository {
         let mut stmt = self
             .db
             .prepare(
-                "SELECT uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions \
+                "SELECT uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions, utc_offset \
              FROM schedulables \
              WHERE started_at >= ?1 AND started_at <= ?2 \
              ORDER BY started_at ASC",
             )
-            .map_err(|e| PersistenceError::CannotFind(format!("{}", e)))?;
+            .map_err(find_err)?;
 
         let rows = stmt
             .query_map(params![start, end], row_to_schedulable)
-            .map_err(|e| PersistenceError::CannotFind(format!("{}", e)))?;
+            .map_err(find_err)?;
 
         let mut result = Vec::new();
         for row in rows {
-            result.push(row.map_err(|e| PersistenceError::CannotFind(format!("{}", e)))?);
+            result.push(row.map_err(find_err)?);
         }
         Ok(result)
     }
@@ -588,26 +748,25 @@ impl Repository {
     }
 
     /// Like [`consecutive_pomodoro_count`], but uses the given Unix timestamp as the reference
-    /// for determining the current day. This allows deterministic testing.
+    /// for determining the current day, and ignores what finished after it. This allows
+    /// deterministic testing and looking back at past breaks.
     pub fn consecutive_pomodoro_count_at(&self, now_ts: i64) -> Result<i64, PersistenceError> {
-        use chrono::{Local, TimeZone};
-
-        // Get midnight of the day containing now_ts
-        let now_dt = Local.timestamp_opt(now_ts, 0).single().unwrap();
-        let midnight = now_dt.date_naive().and_hms_opt(0, 0, 0).unwrap();
-        let midnight_ts = Local
-            .from_local_datetime(&midnight)
-            .single()
+        // Get midnight of the day containing now_ts, in the viewer's zone
+        let zone = crate::timezone::clock().zone();
+        let now_dt = chrono::DateTime::from_timestamp(now_ts, 0)
             .unwrap()
-            .timestamp();
+            .with_timezone(&zone.offset_at(now_ts));
+        let midnight = now_dt.date_naive().and_hms_opt(0, 0, 0).unwrap();
+        let midnight_ts = zone.timestamp(&midnight).unwrap();
 
         // Find the most recent long break's finished_at (since midnight)
         let last_long_break_ts: i64 = self
             .db
             .query_row(
                 "SELECT COALESCE(MAX(finished_at), 0) FROM schedulables \
-                 WHERE kind = 'break' AND finished_at != 0 AND duration >= 10 AND finished_at >= ?1",
-                params![midnight_ts],
+                 WHERE kind = 'break' AND finished_at != 0 AND duration >= 10 \
+                 AND finished_at >= ?1 AND finished_at <= ?2",
+                params![midnight_ts, now_ts],
                 |row| row.get(0),
             )
             .unwrap_or(0);
@@ -618,11 +777,11 @@ impl Repository {
         self.db
             .query_row(
                 "SELECT COUNT(*) FROM schedulables \
-                 WHERE kind = 'pomodoro' AND finished_at != 0 AND finished_at >= ?1",
-                params![since],
+                 WHERE kind = 'pomodoro' AND finished_at != 0 AND finished_at >= ?1 AND finished_at <= ?2",
+                params![since, now_ts],
                 |row| row.get(0),
             )
-            .map_err(|e| PersistenceError::CannotFind(format!("{}", e)))
+            .map_err(find_err)
     }
 
     /// Directly insert a finished pomodoro (for external log).
@@ -632,21 +791,64 @@ impl Repository {
         let uuid = s.uuid.to_string();
 
         match self.db.execute(
-            "INSERT INTO schedulables (uuid, kind, pid, duration, started_at, finished_at, interruptions) \
-             VALUES (?1, ?2, NULL, ?3, ?4, ?5, ?6)",
-            params![uuid, s.kind, s.duration, s.started_at, s.finished_at, s.interruptions],
+            "INSERT INTO schedulables (uuid, kind, pid, duration, started_at, finished_at, interruptions, utc_offset) \
+             VALUES (?1, ?2, NULL, ?3, ?4, ?5, ?6, ?7)",
+            params![uuid, s.kind, s.duration, s.started_at, s.finished_at, s.interruptions, s.utc_offset],
         ) {
             Ok(_) => self.find_by_uuid(s.uuid),
-            Err(e) => {
-                let msg = format!("{}", e);
-                if msg.contains("Rule #1") {
-                    return Err(PersistenceError::OverlappingTimeRange);
-                }
-                Err(PersistenceError::CannotSave(msg))
-            }
+            Err(e) => Err(save_err(e)),
+        }
+    }
+
+    /// Insert a finished or cancelled entry as is, keeping its UUID and
+    /// interruption counter (for merging). The no-overlap trigger (Rule #1)
+    /// is checked.
+    pub fn save_external_ended(&self, s: &Schedulable) -> Result<Schedulable, PersistenceError> {
+        if !matches!(s.status(), Status::Finished | Status::Cancelled) {
+            return Err(PersistenceError::CannotSave(format!(
+                "{} has not ended; cannot save",
+                s
+            )));
+        }
+
+        let uuid = s.uuid.to_string();
+
+        match self.db.execute(
+            "INSERT INTO schedulables (uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions, utc_offset) \
+             VALUES (?1, ?2, NULL, ?3, ?4, NULLIF(?5, 0), NULLIF(?6, 0), ?7, ?8)",
+            params![uuid, s.kind, s.duration, s.started_at, s.finished_at, s.cancelled_at, s.interruptions, s.utc_offset],
+        ) {
+            Ok(_) => self.find_by_uuid(s.uuid),
+            Err(e) => Err(save_err(e)),
         }
     }
 
+    /// Find the entries whose time range overlaps `[start, end)`, using the
+    /// same definition as the no-overlap trigger (Rule #1). Running entries
+    /// extend to infinity.
+    pub fn overlapping(&self, start: i64, end: i64) -> Result<Vec<Schedulable>, PersistenceError> {
+        let mut stmt = self
+            .db
+            .prepare(
+                "SELECT uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions, utc_offset \
+             FROM schedulables \
+             WHERE started_at < ?2 \
+               AND ?1 < COALESCE(finished_at, cancelled_at, 9223372036854775807) \
+             ORDER BY started_at ASC",
+            )
+            .map_err(find_err)?;
+
+        let rows = stmt
+            .query_map(params![start, end], row_to_schedulable)
+            .map_err(find_err)?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row.map_err(find_err)?);
+        }
+        Ok(result)
+    }
+
     pub fn save(&self, s: &Schedulable) -> Result<Schedulable, PersistenceError> {
         let uuid = s.uuid.to_string();
 
@@ -654,8 +856,8 @@ impl Repository {
             Status::New => {Err(PersistenceError::CannotSave(format!("{} has not been started; cannot save", s)))},
             Status::Active | Status::Stale => {
                 match self.db.execute(
-                    "INSERT INTO schedulables (pid, kind, uuid, duration, started_at) VALUES (?1, ?2, ?3, ?4, ?5)",
-                    params![s.pid, s.kind, uuid, s.duration, s.started_at],
+                    "INSERT INTO schedulables (pid, kind, uuid, duration, started_at, utc_offset) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![s.pid, s.kind, uuid, s.duration, s.started_at, s.utc_offset],
                 ) {
                     Ok(_) => {
                         Ok(self.find_by_uuid(s.uuid).expect("Could not find the inserted"))
@@ -664,7 +866,7 @@ impl Repository {
                          if let Ok(option) = self.active() {
                              match option {
                                  Some(existing) => return Err(PersistenceError::AlreadyRunning(existing.pid)),
-                                 None => return Err(PersistenceError::CannotSave(format!("{} could not be inserted as active, but there was no active Pomodoro or Break found, either.", s))),
+                                 None => return Err(classify(&e).unwrap_or_else(|| PersistenceError::CannotSave(format!("{} could not be inserted as active, but there was no active Pomodoro or Break found, either.", s)))),
                              }
                          };
                         Err(save_err(e))
@@ -716,4 +918,374 @@ impl Repository {
             Err(e) => Err(delete_err(e)),
         }
     }
+
+    /// Find schedulables whose interruption counter disagrees with the number of
+    /// `interrupt_log` rows pointing at them. Returns each with its logged count.
+    ///
+    /// Entries without any log rows that started before the first logged
+    /// interruption are skipped: they predate the interrupt log, so only the
+    /// counter was ever recorded for them.
+    pub fn interrupt_count_mismatches(&self) -> Result<Vec<(Schedulable, i64)>, PersistenceError> {
+        let mut stmt = self
+            .db
+            .prepare(
+                "SELECT s.uuid, s.kind, s.pid, s.duration, s.started_at, s.finished_at, s.cancelled_at, s.interruptions, s.utc_offset, \
+                        (SELECT COUNT(*) FROM interrupt_log l WHERE l.schedulable_uuid = s.uuid) AS logged \
+             FROM schedulables s \
+             WHERE s.interruptions != logged \
+               AND NOT (logged = 0 AND s.started_at < COALESCE((SELECT MIN(created_at) FROM interrupt_log), 9223372036854775807)) \
+             ORDER BY s.started_at ASC",
+            )
+            .map_err(find_err)?;
+
+        let rows = stmt
+            .query_map([], |row| Ok((row_to_schedulable(row)?, row.get(9)?)))
+            .map_err(find_err)?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row.map_err(find_err)?);
+        }
+        Ok(result)
+    }
+
+    /// Overwrite the interruption counter of a schedulable.
+    pub fn set_interruptions(&self, uuid: SqlUuid, count: i64) -> Result<(), PersistenceError> {
+        let uuid_s = uuid.to_string();
+
+        match self.db.execute(
+            "UPDATE schedulables SET interruptions = ?2 WHERE uuid == ?1",
+            params![uuid_s, count],
+        ) {
+            Ok(rows) if rows > 0 => Ok(()),
+            Ok(_) => Err(PersistenceError::CannotUpdate(format!(
+                "schedulable {} not found",
+                uuid_s
+            ))),
+            Err(e) => Err(update_err(e)),
+        }
+    }
+
+    /// Annotations whose schedulable no longer exists.
+    pub fn orphaned_annotations(&self) -> Result<Vec<Annotation>, PersistenceError> {
+        let mut stmt = self
+            .db
+            .prepare(
+                "SELECT uuid, schedulable_uuid, body, created_at FROM annotations \
+             WHERE schedulable_uuid NOT IN (SELECT uuid FROM schedulables) \
+             ORDER BY created_at ASC",
+            )
+            .map_err(find_err)?;
+
+        let rows = stmt.query_map([], row_to_annotation).map_err(find_err)?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row.map_err(find_err)?);
+        }
+        Ok(result)
+    }
+
+    /// Interrupt log entries whose schedulable no longer exists.
+    pub fn orphaned_interrupts(&self) -> Result<Vec<InterruptLog>, PersistenceError> {
+        let mut stmt = self
+            .db
+            .prepare(
+                "SELECT uuid, schedulable_uuid, kind, created_at FROM interrupt_log \
+             WHERE schedulable_uuid NOT IN (SELECT uuid FROM schedulables) \
+             ORDER BY created_at ASC",
+            )
+            .map_err(find_err)?;
+
+        let rows = stmt.query_map([], row_to_interrupt_log).map_err(find_err)?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row.map_err(find_err)?);
+        }
+        Ok(result)
+    }
+
+    /// Delete a single annotation.
+    pub fn delete_annotation(&self, uuid: SqlUuid) -> Result<(), PersistenceError> {
+        let uuid_s = uuid.to_string();
+
+        match self
+            .db
+            .execute("DELETE FROM annotations WHERE uuid = ?1", params![uuid_s])
+        {
+            Ok(rows) if rows > 0 => Ok(()),
+            Ok(_) => Err(PersistenceError::CannotDelete(format!(
+                "annotation {} not found",
+                uuid_s
+            ))),
+            Err(e) => Err(delete_err(e)),
+        }
+    }
+
+    /// Delete a single interrupt log entry. The schedulable's counter is not touched.
+    pub fn delete_interrupt(&self, uuid: SqlUuid) -> Result<(), PersistenceError> {
+        let uuid_s = uuid.to_string();
+
+        match self
+            .db
+            .execute("DELETE FROM interrupt_log WHERE uuid = ?1", params![uuid_s])
+        {
+            Ok(rows) if rows > 0 => Ok(()),
+            Ok(_) => Err(PersistenceError::CannotDelete(format!(
+                "interrupt {} not found",
+                uuid_s
+            ))),
+            Err(e) => Err(delete_err(e)),
+        }
+    }
+
+    /// Ended schedulables whose end is not after their start.
+    pub fn empty_entries(&self) -> Result<Vec<Schedulable>, PersistenceError> {
+        let mut stmt = self
+            .db
+            .prepare(
+                "SELECT uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions, utc_offset \
+             FROM schedulables \
+             WHERE COALESCE(finished_at, cancelled_at) <= started_at \
+             ORDER BY started_at ASC",
+            )
+            .map_err(find_err)?;
+
+        let rows = stmt.query_map([], row_to_schedulable).map_err(find_err)?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row.map_err(find_err)?);
+        }
+        Ok(result)
+    }
+
+    /// Write a consistent copy of the database to `path` using SQLite's online
+    /// backup API. Safe to run while another process is writing to the database.
+    pub fn backup_to(&self, path: &Path) -> Result<(), PersistenceError> {
+        self.db
+            .backup(rusqlite::MAIN_DB, path, None)
+            .map_err(|e| PersistenceError::CannotBackup(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Replace the contents of the database with the backup at `path`.
+    ///
+    /// Refuses to run while an entry is active, or if the backup has
+    /// migrations applied that this version does not know about. Backups
+    /// taken by older versions are migrated after the restore.
+    pub fn restore_from(&mut self, path: &Path) -> Result<(), PersistenceError> {
+        check_source(path).map_err(PersistenceError::CannotRestore)?;
+
+        if let Some(active) = self.active()?
+            && matches!(active.status(), Status::Active)
+        {
+            return Err(PersistenceError::AlreadyRunning(active.pid));
+        }
+
+        self.load(path).map_err(PersistenceError::CannotRestore)
+    }
+
+    /// Open an in-memory copy of the database at `path`, migrated to the
+    /// current schema. The file itself is only read, never modified.
+    pub fn snapshot(path: &Path) -> Result<Self, PersistenceError> {
+        check_source(path).map_err(PersistenceError::CannotFind)?;
+        let mut repo = Self::new("file::memory:");
+        repo.load(path).map_err(PersistenceError::CannotFind)?;
+        Ok(repo)
+    }
+
+    /// Open an in-memory database that combines the databases at `paths`, for
+    /// reporting across profiles. The files are only read. Rule #1 is not
+    /// enforced across databases, since separate profiles may well track
+    /// overlapping time. Records found in more than one database appear once.
+    pub fn combined(paths: &[PathBuf]) -> Result<Self, PersistenceError> {
+        let repo = Self::new("file::memory:");
+        repo.db
+            .execute_batch(
+                "DROP TRIGGER IF EXISTS check_no_overlap; \
+                 DROP TRIGGER IF EXISTS singularity_pid; \
+                 DROP INDEX IF EXISTS singularity_state;",
+            )
+            .map_err(update_err)?;
+
+        for path in paths {
+            repo.copy_from(&Self::snapshot(path)?)?;
+        }
+        Ok(repo)
+    }
+
+    /// Create the database at `path` if it does not exist, and bring it up to
+    /// the current schema otherwise. Databases written by a newer version are
+    /// left untouched.
+    pub fn create_or_migrate(path: &Path) -> Result<(), PersistenceError> {
+        if path.exists() {
+            check_source(path).map_err(PersistenceError::CannotFind)?;
+        }
+        Self::new(&path.to_string_lossy());
+        Ok(())
+    }
+
+    /// Move the entries that started before `before` and have ended into the
+    /// existing database at `path`, together with their annotations and
+    /// interrupts, in one transaction. Rule #1 is checked in the archive.
+    pub fn move_ended_before(
+        &self,
+        path: &Path,
+        before: i64,
+    ) -> Result<crate::archive::Summary, PersistenceError> {
+        self.db
+            .execute(
+                "ATTACH DATABASE ?1 AS archive",
+                params![path.to_string_lossy()],
+            )
+            .map_err(save_err)?;
+
+        let result = self.transaction(|| {
+            const ENDED: &str = "SELECT uuid FROM main.schedulables \
+                 WHERE started_at < ?1 AND COALESCE(finished_at, cancelled_at) IS NOT NULL";
+
+            let entries = self
+                .db
+                .execute(
+                    "INSERT INTO archive.schedulables (uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions, utc_offset) \
+                     SELECT uuid, kind, NULL, duration, started_at, finished_at, cancelled_at, interruptions, utc_offset \
+                     FROM main.schedulables \
+                     WHERE started_at < ?1 AND COALESCE(finished_at, cancelled_at) IS NOT NULL \
+                     ORDER BY started_at ASC",
+                    params![before],
+                )
+                .map_err(save_err)?;
+            let annotations = self
+                .db
+                .execute(
+                    &format!(
+                        "INSERT INTO archive.annotations (uuid, schedulable_uuid, body, created_at) \
+                         SELECT uuid, schedulable_uuid, body, created_at FROM main.annotations \
+                         WHERE schedulable_uuid IN ({})",
+                        ENDED
+                    ),
+                    params![before],
+                )
+                .map_err(save_err)?;
+            let interrupts = self
+                .db
+                .execute(
+                    &format!(
+                        "INSERT INTO archive.interrupt_log (uuid, schedulable_uuid, kind, created_at) \
+                         SELECT uuid, schedulable_uuid, kind, created_at FROM main.interrupt_log \
+                         WHERE schedulable_uuid IN ({})",
+                        ENDED
+                    ),
+                    params![before],
+                )
+                .map_err(save_err)?;
+
+            // Annotations and interrupts follow by ON DELETE CASCADE.
+            self.db
+                .execute(
+                    &format!("DELETE FROM main.schedulables WHERE uuid IN ({})", ENDED),
+                    params![before],
+                )
+                .map_err(delete_err)?;
+
+            Ok(crate::archive::Summary {
+                entries,
+                annotations,
+                interrupts,
+            })
+        });
+
+        self.db
+            .execute_batch("DETACH DATABASE archive")
+            .map_err(update_err)?;
+        result
+    }
+
+    /// Attach the database at `path` read-only and make its records visible
+    /// to all queries, as if they were part of this database. Temporary views
+    /// shadow the tables of this database, so writes are no longer possible.
+    pub fn attach_read_only(&self, path: &Path) -> Result<(), PersistenceError> {
+        check_source(path).map_err(PersistenceError::CannotFind)?;
+        let mut uri = Url::from_file_path(
+            path.canonicalize()
+                .map_err(|e| PersistenceError::Io(format!("{}: {}", path.display(), e)))?,
+        )
+        .map_err(|_| PersistenceError::CannotFind(path.display().to_string()))?;
+        uri.set_query(Some("mode=ro"));
+
+        self.db
+            .execute("ATTACH DATABASE ?1 AS archive", params![uri.as_str()])
+            .map_err(find_err)?;
+        self.db
+            .execute_batch(
+                &[
+                    ("schedulables", "uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions, utc_offset"),
+                    ("annotations", "uuid, schedulable_uuid, body, created_at"),
+                    ("interrupt_log", "uuid, schedulable_uuid, kind, created_at"),
+                ]
+                .iter()
+                .map(|(table, columns)| {
+                    format!(
+                        "CREATE TEMP VIEW {table} AS \
+                         SELECT {columns} FROM main.{table} UNION ALL SELECT {columns} FROM archive.{table};"
+                    )
+                })
+                .collect::<String>(),
+            )
+            .map_err(find_err)
+    }
+
+    /// Copy all records of `other` into this database, skipping UUIDs that
+    /// are already present.
+    fn copy_from(&self, other: &Repository) -> Result<(), PersistenceError> {
+        for s in other.entries_between(i64::MIN, i64::MAX)? {
+            self.db
+                .execute(
+                    "INSERT OR IGNORE INTO schedulables (uuid, kind, pid, duration, started_at, finished_at, cancelled_at, interruptions, utc_offset) \
+                     VALUES (?1, ?2, NULLIF(?3, 0), ?4, ?5, NULLIF(?6, 0), NULLIF(?7, 0), ?8, ?9)",
+                    params![s.uuid.to_string(), s.kind, s.pid, s.duration, s.started_at, s.finished_at, s.cancelled_at, s.interruptions, s.utc_offset],
+                )
+                .map_err(save_err)?;
+        }
+
+        for a in other.annotations_between(i64::MIN, i64::MAX)? {
+            self.db
+                .execute(
+                    "INSERT OR IGNORE INTO annotations (uuid, schedulable_uuid, body, created_at) VALUES (?1, ?2, ?3, ?4)",
+                    params![a.uuid.to_string(), a.schedulable_uuid.to_string(), a.body, a.created_at],
+                )
+                .map_err(save_err)?;
+        }
+
+        for i in other.interrupts_between(i64::MIN, i64::MAX)? {
+            self.db
+                .execute(
+                    "INSERT OR IGNORE INTO interrupt_log (uuid, schedulable_uuid, kind, created_at) VALUES (?1, ?2, ?3, ?4)",
+                    params![i.uuid.to_string(), i.schedulable_uuid.to_string(), i.kind.as_str(), i.created_at],
+                )
+                .map_err(save_err)?;
+        }
+        Ok(())
+    }
+
+    /// Overwrite this database with the one at `path` and migrate it.
+    fn load(&mut self, path: &Path) -> Result<(), String> {
+        self.db
+            .restore(rusqlite::MAIN_DB, path, None::<fn(Progress)>)
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        migrate(&self.db);
+        Ok(())
+    }
+}
+
+/// Make sure `path` is a rustomato database this version can read.
+fn check_source(path: &Path) -> Result<(), String> {
+    if !path.is_file() {
+        return Err(format!("{} does not exist", path.display()));
+    }
+
+    let source = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    crate::migration::check_compatible(&source)
 }
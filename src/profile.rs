@@ -0,0 +1,192 @@
+use std::path::{Path, PathBuf};
+
+/// The profile that lives directly in the base root, as before profiles existed.
+pub const DEFAULT: &str = "default";
+
+/// File in the base root that names the profile to use when none is given.
+const DEFAULT_FILE: &str = "default-profile";
+
+/// The directory that holds all profiles other than [`DEFAULT`].
+pub fn profiles_dir(base: &Path) -> PathBuf {
+    base.join("profiles")
+}
+
+/// The root directory of the named profile. It has the same layout as the
+/// base root: its own `data.db`, `hooks/` and `backups/`.
+pub fn root_for(base: &Path, name: &str) -> PathBuf {
+    if name == DEFAULT {
+        base.to_path_buf()
+    } else {
+        profiles_dir(base).join(name)
+    }
+}
+
+/// Profile names become directory names, so only a safe subset is allowed.
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("profile name must not be empty".to_string());
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "invalid profile name '{}'; use letters, digits, '-' and '_' only",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Whether the named profile exists. The default profile always does.
+pub fn exists(base: &Path, name: &str) -> bool {
+    name == DEFAULT || root_for(base, name).is_dir()
+}
+
+/// All profiles, the default one first and the others sorted by name.
+pub fn list(base: &Path) -> std::io::Result<Vec<String>> {
+    let mut names = Vec::new();
+    match std::fs::read_dir(profiles_dir(base)) {
+        Ok(dir) => {
+            for entry in dir {
+                let entry = entry?;
+                if entry.path().is_dir()
+                    && let Some(name) = entry.file_name().to_str()
+                    && validate_name(name).is_ok()
+                    && name != DEFAULT
+                {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+    names.sort();
+    names.insert(0, DEFAULT.to_string());
+    Ok(names)
+}
+
+/// Create a new profile with sample hooks and return its root.
+pub fn create(base: &Path, name: &str) -> Result<PathBuf, String> {
+    validate_name(name)?;
+    if exists(base, name) {
+        return Err(format!("profile '{}' already exists", name));
+    }
+
+    let root = root_for(base, name);
+    crate::hooks::init(&root).map_err(|e| format!("cannot create {}: {}", root.display(), e))?;
+    Ok(root)
+}
+
+/// The profile used when neither `--profile` nor `RUSTOMATO_PROFILE` is given.
+pub fn default_profile(base: &Path) -> String {
+    std::fs::read_to_string(base.join(DEFAULT_FILE))
+        .map(|s| s.trim().to_string())
+        .ok()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| DEFAULT.to_string())
+}
+
+/// Make `name` the profile used when none is given.
+pub fn set_default(base: &Path, name: &str) -> Result<(), String> {
+    validate_name(name)?;
+    if !exists(base, name) {
+        return Err(format!("profile '{}' does not exist", name));
+    }
+
+    let path = base.join(DEFAULT_FILE);
+    let result = if name == DEFAULT {
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            other => other,
+        }
+    } else {
+        std::fs::write(&path, format!("{}\n", name))
+    };
+    result.map_err(|e| format!("cannot write {}: {}", path.display(), e))
+}
+
+/// Pick the profile to use, preferring an explicitly requested one over the
+/// configured default, and return its name and root.
+pub fn resolve(base: &Path, requested: Option<&str>) -> Result<(String, PathBuf), String> {
+    let name = match requested {
+        Some(name) => name.to_string(),
+        None => default_profile(base),
+    };
+    validate_name(&name)?;
+    if !exists(base, &name) {
+        return Err(format!(
+            "profile '{}' does not exist; create it with `rustomato profile create {}`",
+            name, name
+        ));
+    }
+    let root = root_for(base, &name);
+    Ok((name, root))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_default_profile_is_base_root() {
+        let base = Path::new("/tmp/rustomato");
+        assert_eq!(root_for(base, DEFAULT), base);
+        assert_eq!(root_for(base, "work"), base.join("profiles/work"));
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("work").is_ok());
+        assert!(validate_name("side_project-2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("../etc").is_err());
+        assert!(validate_name("a b").is_err());
+    }
+
+    #[test]
+    fn test_list_default_first() {
+        let base = tempdir().unwrap();
+        create(base.path(), "work").unwrap();
+        create(base.path(), "personal").unwrap();
+
+        assert_eq!(
+            list(base.path()).unwrap(),
+            vec!["default", "personal", "work"]
+        );
+    }
+
+    #[test]
+    fn test_create_twice_fails() {
+        let base = tempdir().unwrap();
+        create(base.path(), "work").unwrap();
+        assert!(create(base.path(), "work").is_err());
+        assert!(create(base.path(), DEFAULT).is_err());
+    }
+
+    #[test]
+    fn test_set_and_reset_default() {
+        let base = tempdir().unwrap();
+        assert!(set_default(base.path(), "work").is_err());
+
+        create(base.path(), "work").unwrap();
+        set_default(base.path(), "work").unwrap();
+        assert_eq!(default_profile(base.path()), "work");
+        assert_eq!(
+            resolve(base.path(), None).unwrap(),
+            ("work".to_string(), base.path().join("profiles/work"))
+        );
+
+        set_default(base.path(), DEFAULT).unwrap();
+        assert_eq!(default_profile(base.path()), DEFAULT);
+    }
+
+    #[test]
+    fn test_resolve_missing_profile() {
+        let base = tempdir().unwrap();
+        let err = resolve(base.path(), Some("nope")).unwrap_err();
+        assert!(err.contains("profile create nope"));
+    }
+}
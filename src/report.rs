@@ -1,36 +1,238 @@
-use crate::persistence::Repository;
-use crate::{InterruptLog, InterruptionKind, Kind, Schedulable};
-use chrono::{Datelike, Duration, Local, NaiveDate, Timelike};
+use crate::chart::{self, Chart, Series, Style};
+use crate::persistence::{PersistenceError, Repository};
+use crate::timeline::{self, Timeline};
+use crate::timezone;
+use crate::{Annotation, InterruptLog, InterruptionKind, Kind, Schedulable, Status};
+use chrono::{Datelike, Duration, NaiveDate, Timelike, Weekday};
+use serde::{Serialize, Serializer};
 use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+/// Version of the JSON documents written by `report --format json`. See
+/// `doc/report/schema/` for what it covers.
+pub const SCHEMA_VERSION: u32 = 1;
 
 // ── Data structures ───────────────────────────────────────────
 
-#[derive(Debug, Default)]
-#[allow(dead_code)]
-struct DayStats {
-    date: NaiveDate,
-    pomodori_completed: usize,
-    pomodori_cancelled: usize,
-    breaks_taken: usize,
-    breaks_cancelled: usize,
-    interruptions: i64,
-    internal_interruptions: usize,
-    external_interruptions: usize,
-}
-
-#[derive(Debug, Default)]
-struct AggregateStats {
-    completed: usize,
-    cancelled: usize,
-    completion_rate: u32,
-    breaks_taken: usize,
-    breaks_cancelled: usize,
-    total_interruptions: i64,
-    internal_count: usize,
-    external_count: usize,
-    avg_interruptions: f64,
-    max_focus_block: usize,
-    break_ratio: f64,
+/// Totals for a single day.
+#[derive(Debug, Default, Serialize)]
+pub struct DayStats {
+    pub date: NaiveDate,
+    pub pomodori_completed: usize,
+    pub pomodori_cancelled: usize,
+    pub breaks_taken: usize,
+    pub breaks_cancelled: usize,
+    pub interruptions: i64,
+    pub internal_interruptions: usize,
+    pub external_interruptions: usize,
+}
+
+impl DayStats {
+    fn has_data(&self) -> bool {
+        self.pomodori_completed > 0 || self.pomodori_cancelled > 0 || self.breaks_taken > 0
+    }
+}
+
+/// Totals and ratios for any period.
+#[derive(Debug, Default, Serialize)]
+pub struct AggregateStats {
+    pub completed: usize,
+    pub cancelled: usize,
+    /// Percentage of pomodori that were completed rather than cancelled
+    pub completion_rate: u32,
+    pub breaks_taken: usize,
+    pub breaks_cancelled: usize,
+    /// Sum of the interruption counters of all pomodori
+    pub total_interruptions: i64,
+    /// Interruptions in the interrupt log, which has no entries before the upgrade that introduced it
+    pub internal_count: usize,
+    pub external_count: usize,
+    pub avg_interruptions: f64,
+    /// Longest run of completed pomodori without an interruption
+    pub max_focus_block: usize,
+    pub break_ratio: f64,
+}
+
+impl AggregateStats {
+    fn has_data(&self) -> bool {
+        self.completed > 0 || self.cancelled > 0 || self.breaks_taken > 0
+    }
+}
+
+/// The totals of one week within a month. The week starts on Monday, which
+/// may be in the previous month; only days within the month are counted.
+#[derive(Debug, Serialize)]
+pub struct WeekChunk {
+    pub week_start: NaiveDate,
+    #[serde(flatten)]
+    pub stats: AggregateStats,
+}
+
+/// The totals of a calendar month.
+#[derive(Debug, Serialize)]
+pub struct MonthStats {
+    pub year: i32,
+    pub month: u32,
+    #[serde(flatten)]
+    pub stats: AggregateStats,
+}
+
+/// On how many days of a period at least one pomodoro was completed.
+#[derive(Debug, Default, Serialize)]
+pub struct ActiveDays {
+    pub active: u32,
+    pub total: u32,
+    /// Longest run of consecutive active days
+    pub best_streak: u32,
+}
+
+/// What a [`Hint`] is about.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HintKind {
+    Warning,
+    Tip,
+    Decline,
+    Improvement,
+    Progress,
+}
+
+/// An insight derived from the numbers of a report.
+#[derive(Debug, Serialize)]
+pub struct Hint {
+    pub kind: HintKind,
+    pub message: String,
+}
+
+impl Hint {
+    fn new(kind: HintKind, message: impl Into<String>) -> Self {
+        Hint {
+            kind,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let icon = match self.kind {
+            HintKind::Warning => "⚠",
+            HintKind::Tip => "💡",
+            HintKind::Decline => "📉",
+            HintKind::Improvement => "📈",
+            HintKind::Progress => "✅",
+        };
+        write!(f, "{} {}", icon, self.message)
+    }
+}
+
+/// Interruptions in the interrupt log that fall into one hour of the day or
+/// one day of the week.
+#[derive(Debug, Serialize)]
+pub struct InterruptionBucket<K> {
+    #[serde(flatten)]
+    pub key: K,
+    pub total: usize,
+    pub internal: usize,
+    pub external: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Hour {
+    pub hour: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Day {
+    pub weekday: Weekday,
+}
+
+// ── Output formats ────────────────────────────────────────────
+
+/// The output formats of `report`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+    Markdown,
+    Html,
+}
+
+/// A report that can be rendered in every [`Format`].
+pub trait Render: Serialize {
+    /// The name of the report in JSON documents, e.g. `week`
+    const NAME: &'static str;
+
+    fn text(&self) -> String;
+
+    fn markdown(&self) -> String;
+
+    /// A self-contained HTML document; the text output unless the report
+    /// has a layout of its own.
+    fn html(&self) -> String {
+        let mut html = Html::new(&format!("rustomato {} report", Self::NAME));
+        html.pre(&self.text());
+        html.into_string()
+    }
+}
+
+/// Render a report in the given format.
+pub fn render<R: Render>(report: &R, format: Format) -> String {
+    #[derive(Serialize)]
+    struct Document<'a, R> {
+        schema_version: u32,
+        report: &'static str,
+        #[serde(flatten)]
+        data: &'a R,
+    }
+
+    match format {
+        Format::Text => report.text(),
+        Format::Markdown => report.markdown(),
+        Format::Html => report.html(),
+        Format::Json => {
+            let mut json = serde_json::to_string_pretty(&Document {
+                schema_version: SCHEMA_VERSION,
+                report: R::NAME,
+                data: report,
+            })
+            .expect("serializing report");
+            json.push('\n');
+            json
+        }
+    }
+}
+
+/// Print a report, or exit if it could not be computed.
+fn print<R: Render>(report: Result<R, PersistenceError>, format: Format) {
+    match report {
+        Ok(report) => print!("{}", render(&report, format)),
+        Err(e) => {
+            eprintln!("Error: {}.", e);
+            std::process::exit(e.exit_code());
+        }
+    }
+}
+
+/// Serialize a timestamp like the JSON export does, with `null` for 0.
+pub(crate) fn timestamp<S: Serializer>(ts: &i64, serializer: S) -> Result<S::Ok, S::Error> {
+    if *ts == 0 {
+        serializer.serialize_none()
+    } else {
+        serializer.serialize_some(&timezone::clock().format(*ts, "%+"))
+    }
+}
+
+pub(crate) fn display<T: fmt::Display, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+pub(crate) fn status<S: Serializer>(status: &Status, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(status.as_str())
 }
 
 // ── Report builder ─────────────────────────────────────────────
@@ -65,11 +267,134 @@ impl Report {
         let _ = writeln!(self.buf, "  {}", text);
     }
 
+    /// A Markdown table. Pipes and line breaks in cells are escaped.
+    fn table(&mut self, headers: &[&str], rows: &[Vec<String>]) {
+        let cell = |s: &String| s.replace('|', "\\|").replace('\n', "<br>");
+        self.line(format_args!("| {} |", headers.join(" | ")));
+        self.line(format_args!(
+            "|{}",
+            headers.iter().map(|_| "---|").collect::<String>()
+        ));
+        for row in rows {
+            let cells: Vec<String> = row.iter().map(cell).collect();
+            self.line(format_args!("| {} |", cells.join(" | ")));
+        }
+    }
+
     fn into_string(self) -> String {
         self.buf
     }
 }
 
+/// A self-contained HTML document: inline styles and charts, no external
+/// assets, so that it can be attached or uploaded as is.
+struct Html {
+    buf: String,
+}
+
+const HTML_STYLE: &str = "\
+body { font-family: sans-serif; color: #212121; max-width: 52em; margin: 2em auto; padding: 0 1em; }
+h1 { border-bottom: 1px solid #e0e0e0; padding-bottom: 0.3em; }
+table { border-collapse: collapse; margin: 0.5em 0 1em; }
+th, td { border: 1px solid #e0e0e0; padding: 0.3em 0.6em; text-align: left; vertical-align: top; white-space: pre-line; }
+th { background: #f5f5f5; }
+pre { background: #fafafa; padding: 0.5em; overflow-x: auto; }
+svg { max-width: 100%; height: auto; }
+.muted { color: #757575; }
+";
+
+impl Html {
+    fn new(title: &str) -> Self {
+        let mut html = Self { buf: String::new() };
+        html.raw("<!DOCTYPE html>");
+        html.raw("<html lang=\"en\">");
+        html.raw("<head>");
+        html.raw("<meta charset=\"utf-8\">");
+        html.raw("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">");
+        html.element("title", title);
+        html.raw(format_args!("<style>\n{}</style>", HTML_STYLE));
+        html.raw("</head>");
+        html.raw("<body>");
+        html
+    }
+
+    /// A line of markup, which is not escaped.
+    fn raw(&mut self, markup: impl std::fmt::Display) {
+        use std::fmt::Write;
+        let _ = writeln!(self.buf, "{}", markup);
+    }
+
+    /// An element with escaped text, e.g. a heading or a paragraph.
+    fn element(&mut self, tag: &str, text: impl std::fmt::Display) {
+        self.raw(format_args!(
+            "<{tag}>{}</{tag}>",
+            chart::escape(&text.to_string())
+        ));
+    }
+
+    fn muted(&mut self, text: &str) {
+        self.raw(format_args!(
+            "<p class=\"muted\">{}</p>",
+            chart::escape(text)
+        ));
+    }
+
+    /// A bullet list; each item starts with an optional bold label.
+    fn list(&mut self, items: &[(&str, String)]) {
+        self.raw("<ul>");
+        for (label, text) in items {
+            if label.is_empty() {
+                self.element("li", text);
+            } else {
+                self.raw(format_args!(
+                    "<li><strong>{}:</strong> {}</li>",
+                    chart::escape(label),
+                    chart::escape(text)
+                ));
+            }
+        }
+        self.raw("</ul>");
+    }
+
+    /// A table. Line breaks in cells are kept.
+    fn table(&mut self, headers: &[&str], rows: &[Vec<String>]) {
+        let cells = |tag: &str, cells: &mut dyn Iterator<Item = &str>| {
+            cells
+                .map(|c| format!("<{tag}>{}</{tag}>", chart::escape(c)))
+                .collect::<String>()
+        };
+        self.raw("<table>");
+        self.raw(format_args!(
+            "<tr>{}</tr>",
+            cells("th", &mut headers.iter().copied())
+        ));
+        for row in rows {
+            self.raw(format_args!(
+                "<tr>{}</tr>",
+                cells("td", &mut row.iter().map(String::as_str))
+            ));
+        }
+        self.raw("</table>");
+    }
+
+    fn pre(&mut self, text: &str) {
+        self.raw(format_args!(
+            "<pre>{}</pre>",
+            chart::escape(text.trim_start_matches('\n').trim_end())
+        ));
+    }
+
+    fn chart(&mut self, chart: &Chart) {
+        self.raw(chart.svg().trim_end());
+    }
+
+    fn into_string(mut self) -> String {
+        self.raw("</body>");
+        self.raw("</html>");
+        self.buf
+    }
+}
+
 /// Ratio indicator — returns a checkmark or warning emoji.
 fn ratio_indicator(ratio: f64) -> &'static str {
     if (0.5..=2.0).contains(&ratio) {
@@ -85,7 +410,7 @@ fn write_metrics(
     report: &mut Report,
     agg: &AggregateStats,
     prev_rate: Option<u32>,
-    active_days: Option<(u32, u32, u32)>,
+    active_days: Option<&ActiveDays>,
 ) {
     let prev_str = prev_rate
         .filter(|_| agg.completed > 0 || agg.cancelled > 0)
@@ -116,19 +441,259 @@ fn write_metrics(
         ));
     }
 
-    if let Some((active_count, total_days, streak)) = active_days {
-        let day = if streak == 1 { "day" } else { "days" };
+    if let Some(days) = active_days {
+        let day = if days.best_streak == 1 { "day" } else { "days" };
         report.indent(format_args!(
             "Active days:  {} of {} ({:.0}%)  \u{00b7}  Best streak: {} {}",
-            active_count,
-            total_days,
-            active_count as f64 / total_days as f64 * 100.0,
-            streak,
+            days.active,
+            days.total,
+            days.active as f64 / days.total as f64 * 100.0,
+            days.best_streak,
             day
         ));
     }
 }
 
+/// The Markdown counterpart of [`write_metrics`] and
+/// [`print_interruption_summary`], as a bullet list.
+fn markdown_metrics(report: &mut Report, agg: &AggregateStats, active_days: Option<&ActiveDays>) {
+    report.line(format_args!(
+        "- **Pomodori:** {} completed · {} cancelled · {}% completion rate",
+        agg.completed, agg.cancelled, agg.completion_rate
+    ));
+    report.line(format_args!(
+        "- **Breaks:** {} taken · {} cancelled",
+        agg.breaks_taken, agg.breaks_cancelled
+    ));
+    if agg.completed > 0 && agg.breaks_taken > 0 {
+        report.line(format_args!(
+            "- **Ratio:** {:.1} break per pomodoro",
+            agg.break_ratio
+        ));
+    }
+    if agg.max_focus_block > 0 {
+        report.line(format_args!(
+            "- **Focus block:** {} consecutive pomodori without interruption",
+            agg.max_focus_block
+        ));
+    }
+    if let Some(days) = active_days {
+        report.line(format_args!(
+            "- **Active days:** {} of {} · best streak {}",
+            days.active, days.total, days.best_streak
+        ));
+    }
+    report.line(format_args!(
+        "- **Interruptions:** {} ({:.1} avg per pomodoro) · {} internal · {} external",
+        agg.total_interruptions, agg.avg_interruptions, agg.internal_count, agg.external_count
+    ));
+}
+
+/// The hints as a Markdown section, if there are any.
+fn markdown_hints(report: &mut Report, hints: &[Hint]) {
+    if hints.is_empty() {
+        return;
+    }
+    report.blank();
+    report.line("## Insights");
+    report.blank();
+    for hint in hints {
+        report.line(format_args!("- {}", hint));
+    }
+}
+
+fn day_rows<'a>(days: impl IntoIterator<Item = &'a DayStats>, label: &str) -> Vec<Vec<String>> {
+    days.into_iter()
+        .map(|d| {
+            vec![
+                d.date.format(label).to_string(),
+                d.pomodori_completed.to_string(),
+                d.pomodori_cancelled.to_string(),
+                d.breaks_taken.to_string(),
+                d.breaks_cancelled.to_string(),
+                d.interruptions.to_string(),
+            ]
+        })
+        .collect()
+}
+
+const MARKDOWN_COLUMNS: [&str; 5] = [
+    "Done",
+    "Cancelled",
+    "Breaks",
+    "Breaks cancelled",
+    "Interruptions",
+];
+
+fn markdown_headers(first: &'static str) -> Vec<&'static str> {
+    std::iter::once(first).chain(MARKDOWN_COLUMNS).collect()
+}
+
+/// The timeline of the days from `first` to `last` at the width of the
+/// terminal, if `wanted`.
+fn collect_timeline(
+    repo: &Repository,
+    first: NaiveDate,
+    last: NaiveDate,
+    wanted: bool,
+) -> Result<Option<Timeline>, PersistenceError> {
+    wanted
+        .then(|| timeline::collect(repo, first, last, timeline::terminal_width()))
+        .transpose()
+}
+
+fn write_timeline(report: &mut Report, timeline: Option<&Timeline>) {
+    if let Some(timeline) = timeline {
+        for line in timeline.lines() {
+            report.line(line);
+        }
+        report.blank();
+    }
+}
+
+fn markdown_timeline(report: &mut Report, timeline: Option<&Timeline>) {
+    if let Some(timeline) = timeline {
+        report.line("```text");
+        write_timeline(report, Some(timeline));
+        report.line("```");
+        report.blank();
+    }
+}
+
+/// The lines of [`write_metrics`] as label and text, for an HTML list.
+fn metric_items(
+    agg: &AggregateStats,
+    active_days: Option<&ActiveDays>,
+) -> Vec<(&'static str, String)> {
+    let mut items = vec![
+        (
+            "Pomodori",
+            format!(
+                "{} completed · {} cancelled · {}% completion rate",
+                agg.completed, agg.cancelled, agg.completion_rate
+            ),
+        ),
+        (
+            "Breaks",
+            format!(
+                "{} taken · {} cancelled",
+                agg.breaks_taken, agg.breaks_cancelled
+            ),
+        ),
+    ];
+    if agg.completed > 0 && agg.breaks_taken > 0 {
+        items.push((
+            "Ratio",
+            format!(
+                "{:.1} break per pomodoro {}",
+                agg.break_ratio,
+                ratio_indicator(agg.break_ratio)
+            ),
+        ));
+    }
+    if agg.max_focus_block > 0 {
+        items.push((
+            "Focus block",
+            format!(
+                "{} consecutive pomodori without interruption",
+                agg.max_focus_block
+            ),
+        ));
+    }
+    if let Some(days) = active_days {
+        items.push((
+            "Active days",
+            format!(
+                "{} of {} · best streak {}",
+                days.active, days.total, days.best_streak
+            ),
+        ));
+    }
+    items
+}
+
+/// The HTML counterpart of [`print_interruption_summary`].
+fn html_interruptions(html: &mut Html, agg: &AggregateStats) {
+    html.element("h2", "Interruptions");
+    let mut items = vec![(
+        "Total",
+        format!(
+            "{} ({:.1} avg per pomodoro)",
+            agg.total_interruptions, agg.avg_interruptions
+        ),
+    )];
+    let total_logged = agg.internal_count + agg.external_count;
+    if total_logged > 0 {
+        for (label, count) in [
+            ("Internal", agg.internal_count),
+            ("External", agg.external_count),
+        ] {
+            let pct = (count as f64 / total_logged as f64 * 100.0) as u32;
+            items.push((label, format!("{} ({}%)", count, pct)));
+        }
+    }
+    html.list(&items);
+    if total_logged == 0 && agg.total_interruptions > 0 {
+        html.muted("Kind breakdown not available for interruptions recorded before the upgrade.");
+    }
+}
+
+fn html_hints(html: &mut Html, hints: &[Hint]) {
+    if hints.is_empty() {
+        return;
+    }
+    html.element("h2", "Insights");
+    let items: Vec<(&str, String)> = hints.iter().map(|h| ("", h.to_string())).collect();
+    html.list(&items);
+}
+
+/// The entries with their annotations as a table, with the day of each one
+/// if `dated`.
+fn html_entries(html: &mut Html, entries: &[DayEntry], dated: bool) {
+    html.element("h2", "Entries");
+    if entries.is_empty() {
+        html.muted("Nothing recorded.");
+        return;
+    }
+    let mut headers = vec![
+        "Start",
+        "End",
+        "Kind",
+        "Planned",
+        "Status",
+        "Interruptions",
+        "Annotations",
+    ];
+    if dated {
+        headers.insert(0, "Day");
+    }
+    let rows: Vec<Vec<String>> = entries
+        .iter()
+        .map(|e| {
+            let mut row = vec![
+                crate::format_time(e.started_at),
+                e.end(),
+                e.kind.to_string(),
+                format!("{} min", e.planned_duration),
+                e.status.as_str().to_string(),
+                e.interruptions.to_string(),
+                e.annotations.join("\n"),
+            ];
+            if dated {
+                row.insert(0, timezone::clock().format(e.started_at, "%a %b %d"));
+            }
+            row
+        })
+        .collect();
+    html.table(&headers, &rows);
+}
+
+fn html_timeline(html: &mut Html, timeline: Option<&Timeline>) {
+    if let Some(timeline) = timeline {
+        html.pre(&timeline.lines().join("\n"));
+    }
+}
+
 // ── Helpers ───────────────────────────────────────────────────
 
 fn parse_date_or_today(date: Option<String>) -> NaiveDate {
@@ -140,41 +705,88 @@ fn parse_date_or_today(date: Option<String>) -> NaiveDate {
             );
             std::process::exit(1);
         }),
-        None => Local::now().date_naive(),
+        None => timezone::clock().today(),
     }
 }
 
-/// Returns (start_of_day_ts, end_of_day_ts) for a given date in local timezone.
+/// Returns (start_of_day_ts, end_of_day_ts) for a given date, as recorded or
+/// as viewed depending on the [`timezone::clock`].
 fn day_bounds(date: NaiveDate) -> (i64, i64) {
-    let start = date
-        .and_hms_opt(0, 0, 0)
-        .and_then(|dt| dt.and_local_timezone(Local).earliest())
-        .map(|dt| dt.timestamp())
-        .unwrap_or(0);
-    let end = date
-        .and_hms_opt(23, 59, 59)
-        .and_then(|dt| dt.and_local_timezone(Local).earliest())
-        .map(|dt| dt.timestamp())
-        .unwrap_or(i64::MAX);
-    (start, end)
-}
-
-/// Fetch entries and interrupt logs for a time range, exiting on error.
-fn fetch_data(repo: &Repository, start: i64, end: i64) -> (Vec<Schedulable>, Vec<InterruptLog>) {
-    let entries = repo.entries_between(start, end).unwrap_or_else(|e| {
-        eprintln!("Error: {}", e);
-        std::process::exit(1);
-    });
-    let interrupts = repo.interrupts_between(start, end).unwrap_or_else(|e| {
-        eprintln!("Error: {}", e);
-        std::process::exit(1);
-    });
-    (entries, interrupts)
+    timezone::clock().day_bounds(date)
+}
+
+/// Fetch entries and interrupt logs for a time range.
+fn fetch_data(
+    repo: &Repository,
+    start: i64,
+    end: i64,
+) -> Result<(Vec<Schedulable>, Vec<InterruptLog>), PersistenceError> {
+    Ok((
+        repo.entries_between(start, end)?,
+        repo.interrupts_between(start, end)?,
+    ))
+}
+
+/// The day with the most completed pomodori, and the one with the fewest
+/// completed relative to the cancelled ones.
+fn best_and_worst_day(days: &[DayStats]) -> (Option<NaiveDate>, Option<NaiveDate>) {
+    let best = days
+        .iter()
+        .filter(|d| d.pomodori_completed > 0)
+        .max_by_key(|d| d.pomodori_completed)
+        .map(|d| d.date);
+    let worst = days
+        .iter()
+        .filter(|d| d.pomodori_completed + d.pomodori_cancelled > 0)
+        .min_by_key(|d| (d.pomodori_completed as i64) - (d.pomodori_cancelled as i64))
+        .map(|d| d.date);
+    (best, worst)
+}
+
+fn day_marker(date: NaiveDate, best: Option<NaiveDate>, worst: Option<NaiveDate>) -> &'static str {
+    if Some(date) == best {
+        " \u{2605}"
+    } else if Some(date) == worst {
+        " \u{2297}"
+    } else {
+        "  "
+    }
+}
+
+/// Write the days with any data as a "Day-by-day" table, marking the best and
+/// the worst day.
+fn write_day_table(
+    report: &mut Report,
+    days: &[DayStats],
+    best: Option<NaiveDate>,
+    worst: Option<NaiveDate>,
+) {
+    report.line("Day-by-day:");
+    report.line("  Day       Done   Canc  Brk ▼  Brk ✗   Interr.");
+    report.separator(50);
+    if !days.iter().any(DayStats::has_data) {
+        report.indent("(nothing recorded)");
+    } else {
+        for ds in days.iter().filter(|d| d.has_data()) {
+            report.line(format_args!(
+                "  {:6}{} {:>4}  {:>4}  {:>4}  {:>4}  {:>7}",
+                ds.date.format("%b %-d"),
+                day_marker(ds.date, best, worst),
+                ds.pomodori_completed,
+                ds.pomodori_cancelled,
+                ds.breaks_taken,
+                ds.breaks_cancelled,
+                ds.interruptions,
+            ));
+        }
+    }
+    report.blank();
 }
 
 // ── Computation ──────────────────────────────────────────────
 
-fn compute_aggregate(entries: &[Schedulable], interrupts: &[InterruptLog]) -> AggregateStats {
+/// Totals and ratios of the given entries and interrupt logs.
+pub fn compute_aggregate(entries: &[Schedulable], interrupts: &[InterruptLog]) -> AggregateStats {
     let completed = entries
         .iter()
         .filter(|e| e.kind == Kind::Pomodoro && e.finished_at != 0)
@@ -254,15 +866,16 @@ fn compute_aggregate(entries: &[Schedulable], interrupts: &[InterruptLog]) -> Ag
     }
 }
 
-fn compute_day_stats(
+/// Totals for every day from `first` to `last`, inclusive.
+pub fn compute_day_stats(
     entries: &[Schedulable],
     interrupts: &[InterruptLog],
-    monday: NaiveDate,
-    sunday: NaiveDate,
+    first: NaiveDate,
+    last: NaiveDate,
 ) -> Vec<DayStats> {
     let mut days = Vec::new();
-    let mut current = monday;
-    while current <= sunday {
+    let mut current = first;
+    while current <= last {
         let (start, end) = day_bounds(current);
 
         let day_entries: Vec<&Schedulable> = entries
@@ -363,13 +976,25 @@ fn print_interruption_summary(
     report.blank();
 }
 
+/// Write the hints as an "Insights" block, if there are any.
+fn print_hints(report: &mut Report, hints: &[Hint]) {
+    if !hints.is_empty() {
+        report.line("Insights:");
+        for hint in hints {
+            report.indent(hint);
+        }
+        report.blank();
+    }
+}
+
 // ── Hints ────────────────────────────────────────────────────
 
-fn check_pattern_hints(
+/// Hints for a week or a rolling window, compared to the one before.
+pub fn check_pattern_hints(
     day_stats: &[DayStats],
     week: &AggregateStats,
     prev_week: &AggregateStats,
-) -> Vec<String> {
+) -> Vec<Hint> {
     let mut hints = Vec::new();
 
     // Weekdays (Mon-Fri) with zero completed pomodori
@@ -378,31 +1003,40 @@ fn check_pattern_hints(
         .filter(|d| d.date.weekday().num_days_from_monday() < 5 && d.pomodori_completed == 0)
         .count();
     if zero_days >= 3 {
-        hints.push(format!(
-            "⚠ You had {} weekdays with no completed pomodori. Consider reviewing your weekly schedule.",
-            zero_days
+        hints.push(Hint::new(
+            HintKind::Warning,
+            format!(
+                "You had {} weekdays with no completed pomodori. Consider reviewing your weekly schedule.",
+                zero_days
+            ),
         ));
     }
 
     // Low completion rate
     if week.completion_rate < 70 && week.completed + week.cancelled >= 3 {
-        hints.push(format!(
-            "⚠ Low completion rate ({}%). Try shorter pomodori or reviewing what's causing cancellations.",
-            week.completion_rate
+        hints.push(Hint::new(
+            HintKind::Warning,
+            format!(
+                "Low completion rate ({}%). Try shorter pomodori or reviewing what's causing cancellations.",
+                week.completion_rate
+            ),
         ));
     }
 
     // No focus blocks
     if week.max_focus_block <= 1 && week.total_interruptions > 0 {
-        hints.push("⚠ No consecutive uninterrupted pomodori. Consider silencing notifications and using a 'do not disturb' signal.".to_string());
+        hints.push(Hint::new(
+            HintKind::Warning,
+            "No consecutive uninterrupted pomodori. Consider silencing notifications and using a 'do not disturb' signal.",
+        ));
     }
 
     // Break ratio too low (only if we have enough data to judge)
     if week.break_ratio > 0.0 && week.break_ratio < 0.3 && week.completed >= 3 {
-        hints.push(
-            "⚠ Few breaks relative to pomodori. Skipping breaks reduces cognitive performance over the day."
-                .to_string(),
-        );
+        hints.push(Hint::new(
+            HintKind::Warning,
+            "Few breaks relative to pomodori. Skipping breaks reduces cognitive performance over the day.",
+        ));
     }
 
     // Interruption kind dominance
@@ -410,15 +1044,15 @@ fn check_pattern_hints(
     if total_ilog > 0 {
         let internal_pct = week.internal_count as f64 / total_ilog as f64 * 100.0;
         if internal_pct > 70.0 {
-            hints.push(
-                "💡 Most interruptions are internal. Consider a 'parking lot' notepad to capture distracting thoughts during pomodori."
-                    .to_string(),
-            );
+            hints.push(Hint::new(
+                HintKind::Tip,
+                "Most interruptions are internal. Consider a 'parking lot' notepad to capture distracting thoughts during pomodori.",
+            ));
         } else if internal_pct < 30.0 {
-            hints.push(
-                "💡 Most interruptions are external. Can you negotiate focused blocks or use a status signal?"
-                    .to_string(),
-            );
+            hints.push(Hint::new(
+                HintKind::Tip,
+                "Most interruptions are external. Can you negotiate focused blocks or use a status signal?",
+            ));
         }
     }
 
@@ -426,23 +1060,26 @@ fn check_pattern_hints(
     if prev_week.completed > 0 {
         if week.completed < prev_week.completed && week.completion_rate <= prev_week.completion_rate
         {
-            hints.push(
-                "📉 Both completed count and completion rate declined this week. Consider whether workload has changed."
-                    .to_string(),
-            );
+            hints.push(Hint::new(
+                HintKind::Decline,
+                "Both completed count and completion rate declined this week. Consider whether workload has changed.",
+            ));
         } else if week.completed > prev_week.completed
             && week.completion_rate >= prev_week.completion_rate
         {
-            hints.push(
-                "📈 Completed more pomodori with maintained or improved completion rate. Great progress!"
-                    .to_string(),
-            );
+            hints.push(Hint::new(
+                HintKind::Improvement,
+                "Completed more pomodori with maintained or improved completion rate. Great progress!",
+            ));
         }
 
         if week.internal_count < prev_week.internal_count && prev_week.internal_count > 0 {
-            hints.push(format!(
-                "✅ Internal interruptions are decreasing ({} this week vs {} last week). Your focus discipline is improving.",
-                week.internal_count, prev_week.internal_count
+            hints.push(Hint::new(
+                HintKind::Progress,
+                format!(
+                    "Internal interruptions are decreasing ({} this week vs {} last week). Your focus discipline is improving.",
+                    week.internal_count, prev_week.internal_count
+                ),
             ));
         }
     }
@@ -452,13 +1089,27 @@ fn check_pattern_hints(
 
 // ── Weekly report ────────────────────────────────────────────
 
-/// Print a weekly productivity report covering the ISO week containing the given
-/// date (defaults to today), with day-by-day breakdown, week-over-week comparison,
-/// best/worst day, and actionable hints.
-pub fn print_week_report(repo: &Repository, date: Option<String>) {
-    let mut rpt = Report::new();
+/// A week from Monday to Sunday, compared to the week before.
+#[derive(Debug, Serialize)]
+pub struct WeekReport {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub days: Vec<DayStats>,
+    pub summary: AggregateStats,
+    pub previous: AggregateStats,
+    pub best_day: Option<NaiveDate>,
+    pub worst_day: Option<NaiveDate>,
+    pub hints: Vec<Hint>,
+    /// The days drawn on an hour scale, if asked for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeline: Option<Timeline>,
+    /// The entries with their annotations, for the HTML report
+    #[serde(skip)]
+    pub entries: Vec<DayEntry>,
+}
 
-    let date = parse_date_or_today(date);
+/// Compute the report for the ISO week containing `date`.
+pub fn week_report(repo: &Repository, date: NaiveDate) -> Result<WeekReport, PersistenceError> {
     let weekday = date.weekday().num_days_from_monday(); // Mon=0 … Sun=6
     let monday = date - Duration::days(weekday as i64);
     let sunday = monday + Duration::days(6);
@@ -469,955 +1120,3389 @@ pub fn print_week_report(repo: &Repository, date: Option<String>) {
     let (this_start, this_end) = (day_bounds(monday).0, day_bounds(sunday).1);
     let (prev_start, prev_end) = (day_bounds(prev_monday).0, day_bounds(prev_sunday).1);
 
-    let (this_entries, this_interrupts) = fetch_data(repo, this_start, this_end);
-    let (prev_entries, prev_interrupts) = fetch_data(repo, prev_start, prev_end);
+    let (this_entries, this_interrupts) = fetch_data(repo, this_start, this_end)?;
+    let (prev_entries, prev_interrupts) = fetch_data(repo, prev_start, prev_end)?;
+
+    let days = compute_day_stats(&this_entries, &this_interrupts, monday, sunday);
+    let summary = compute_aggregate(&this_entries, &this_interrupts);
+    let previous = compute_aggregate(&prev_entries, &prev_interrupts);
+    let (best_day, worst_day) = best_and_worst_day(&days);
+    let hints = check_pattern_hints(&days, &summary, &previous);
+
+    Ok(WeekReport {
+        start: monday,
+        end: sunday,
+        days,
+        summary,
+        previous,
+        best_day,
+        worst_day,
+        hints,
+        timeline: None,
+        entries: Vec::new(),
+    })
+}
 
-    let day_stats = compute_day_stats(&this_entries, &this_interrupts, monday, sunday);
-    let week = compute_aggregate(&this_entries, &this_interrupts);
-    let prev_week = compute_aggregate(&prev_entries, &prev_interrupts);
+impl Render for WeekReport {
+    const NAME: &'static str = "week";
 
-    // Best / worst day
-    let best_day = day_stats
-        .iter()
-        .filter(|d| d.pomodori_completed > 0)
-        .max_by_key(|d| d.pomodori_completed);
-    let worst_day = day_stats
-        .iter()
-        .filter(|d| d.pomodori_completed + d.pomodori_cancelled > 0)
-        .min_by_key(|d| (d.pomodori_completed as i64) - (d.pomodori_cancelled as i64));
-
-    // ── Header ────────────────────────────────────────────
-    rpt.blank();
-    rpt.line(format_args!(
-        "Weekly Report: {} \u{2013} {}",
-        monday.format("%b %d"),
-        sunday.format("%b %d, %Y")
-    ));
-    if prev_week.completed > 0 || prev_week.cancelled > 0 || prev_week.breaks_taken > 0 {
+    fn text(&self) -> String {
+        let mut rpt = Report::new();
+        let (week, prev_week) = (&self.summary, &self.previous);
+
+        // ── Header ────────────────────────────────────────────
+        rpt.blank();
         rpt.line(format_args!(
-            "(vs week of {} \u{2013} {})",
-            prev_monday.format("%b %d"),
-            prev_sunday.format("%b %d")
+            "Weekly Report: {} \u{2013} {}",
+            self.start.format("%b %d"),
+            self.end.format("%b %d, %Y")
         ));
-    }
-    rpt.separator(52);
-    rpt.blank();
-
-    // ── Day-by-day table ──────────────────────────────────
-    rpt.line("Day-by-day breakdown:");
-    rpt.indent("Day       Done   Canc  Brk \u{25bc}  Brk \u{2717}   Interr.");
-    rpt.indent("\u{2500}".repeat(50));
-    let has_any = day_stats
-        .iter()
-        .any(|d| d.pomodori_completed > 0 || d.pomodori_cancelled > 0 || d.breaks_taken > 0);
-    if !has_any {
-        rpt.indent("(nothing recorded this week)");
-    } else {
-        for ds in &day_stats {
-            let day_name = ds.date.format("%a");
-            let star = if Some(ds.date) == best_day.map(|d| d.date) {
-                " \u{2605}"
-            } else if Some(ds.date) == worst_day.map(|d| d.date) {
-                " \u{2297}"
-            } else {
-                "  "
-            };
-            rpt.indent(format_args!(
-                "{:6}{} {:>4}  {:>4}  {:>4}  {:>4}  {:>7}",
-                day_name,
-                star,
-                ds.pomodori_completed,
-                ds.pomodori_cancelled,
-                ds.breaks_taken,
-                ds.breaks_cancelled,
-                ds.interruptions,
+        if prev_week.has_data() {
+            rpt.line(format_args!(
+                "(vs week of {} \u{2013} {})",
+                (self.start - Duration::days(7)).format("%b %d"),
+                (self.end - Duration::days(7)).format("%b %d")
             ));
         }
-    }
-    rpt.blank();
-
-    // ── Weekly summary ────────────────────────────────────
-    rpt.line("Weekly summary:");
-    if week.completed == 0 && week.cancelled == 0 && week.breaks_taken == 0 {
-        rpt.indent("No pomodori or breaks recorded this week.");
+        rpt.separator(52);
         rpt.blank();
-        print!("{}", rpt.into_string());
-        return;
-    }
-
-    write_metrics(&mut rpt, &week, Some(prev_week.completion_rate), None);
-    rpt.blank();
 
-    // ── Interruptions ─────────────────────────────────────
-    print_interruption_summary(&mut rpt, &week, Some("prev week"), Some(&prev_week));
+        write_timeline(&mut rpt, self.timeline.as_ref());
 
-    // ── Best / worst day ──────────────────────────────────
-    if let Some(best) = best_day {
-        rpt.line(format_args!(
-            "\u{2605}  Best day: {} ({} completed)",
-            best.date.format("%A"),
-            best.pomodori_completed
-        ));
+        // ── Day-by-day table ──────────────────────────────────
+        rpt.line("Day-by-day breakdown:");
+        rpt.indent("Day       Done   Canc  Brk \u{25bc}  Brk \u{2717}   Interr.");
+        rpt.indent("\u{2500}".repeat(50));
+        if !self.days.iter().any(DayStats::has_data) {
+            rpt.indent("(nothing recorded this week)");
+        } else {
+            for ds in &self.days {
+                rpt.indent(format_args!(
+                    "{:6}{} {:>4}  {:>4}  {:>4}  {:>4}  {:>7}",
+                    ds.date.format("%a"),
+                    day_marker(ds.date, self.best_day, self.worst_day),
+                    ds.pomodori_completed,
+                    ds.pomodori_cancelled,
+                    ds.breaks_taken,
+                    ds.breaks_cancelled,
+                    ds.interruptions,
+                ));
+            }
+        }
+        rpt.blank();
+
+        // ── Weekly summary ────────────────────────────────────
+        rpt.line("Weekly summary:");
+        if !week.has_data() {
+            rpt.indent("No pomodori or breaks recorded this week.");
+            rpt.blank();
+            return rpt.into_string();
+        }
+
+        write_metrics(&mut rpt, week, Some(prev_week.completion_rate), None);
+        rpt.blank();
+
+        // ── Interruptions ─────────────────────────────────────
+        print_interruption_summary(&mut rpt, week, Some("prev week"), Some(prev_week));
+
+        // ── Best / worst day ──────────────────────────────────
+        let day = |date: Option<NaiveDate>| self.days.iter().find(|d| Some(d.date) == date);
+        if let Some(best) = day(self.best_day) {
+            rpt.line(format_args!(
+                "\u{2605}  Best day: {} ({} completed)",
+                best.date.format("%A"),
+                best.pomodori_completed
+            ));
+        }
+        if let Some(worst) = day(self.worst_day) {
+            rpt.line(format_args!(
+                "\u{2297}  Worst day: {} ({} completed, {} cancelled)",
+                worst.date.format("%A"),
+                worst.pomodori_completed,
+                worst.pomodori_cancelled
+            ));
+        }
+        rpt.blank();
+
+        // ── Hints ─────────────────────────────────────────────
+        print_hints(&mut rpt, &self.hints);
+
+        rpt.into_string()
     }
-    if let Some(worst) = worst_day
-        && (worst.pomodori_completed > 0 || worst.pomodori_cancelled > 0)
-    {
+
+    fn markdown(&self) -> String {
+        let mut rpt = Report::new();
         rpt.line(format_args!(
-            "\u{2297}  Worst day: {} ({} completed, {} cancelled)",
-            worst.date.format("%A"),
-            worst.pomodori_completed,
-            worst.pomodori_cancelled
+            "# Weekly Report: {} – {}",
+            self.start.format("%b %d"),
+            self.end.format("%b %d, %Y")
         ));
+        rpt.blank();
+        markdown_timeline(&mut rpt, self.timeline.as_ref());
+        rpt.table(&markdown_headers("Day"), &day_rows(&self.days, "%a"));
+        rpt.blank();
+        rpt.line("## Summary");
+        rpt.blank();
+        markdown_metrics(&mut rpt, &self.summary, None);
+        if self.previous.has_data() {
+            rpt.line(format_args!(
+                "- **Previous week:** {} completed · {} cancelled · {}% completion rate",
+                self.previous.completed, self.previous.cancelled, self.previous.completion_rate
+            ));
+        }
+        markdown_hints(&mut rpt, &self.hints);
+        rpt.into_string()
     }
-    rpt.blank();
 
-    // ── Hints ─────────────────────────────────────────────
-    let hints = check_pattern_hints(&day_stats, &week, &prev_week);
-    if !hints.is_empty() {
-        rpt.line("Insights:");
-        for hint in &hints {
-            rpt.indent(hint);
+    fn html(&self) -> String {
+        let title = format!(
+            "Weekly Report: {} – {}",
+            self.start.format("%b %d"),
+            self.end.format("%b %d, %Y")
+        );
+        let mut html = Html::new(&title);
+        html.element("h1", &title);
+        html_timeline(&mut html, self.timeline.as_ref());
+
+        html.element("h2", "Day by day");
+        html.chart(&daily_chart("Pomodori per day".to_string(), &self.days));
+        html.table(&markdown_headers("Day"), &day_rows(&self.days, "%a"));
+
+        html.element("h2", "Summary");
+        if !self.summary.has_data() {
+            html.muted("No pomodori or breaks recorded this week.");
+            return html.into_string();
         }
-        rpt.blank();
+        let mut items = metric_items(&self.summary, None);
+        if self.previous.has_data() {
+            items.push((
+                "Previous week",
+                format!(
+                    "{} completed · {} cancelled · {}% completion rate",
+                    self.previous.completed, self.previous.cancelled, self.previous.completion_rate
+                ),
+            ));
+        }
+        let day = |date: Option<NaiveDate>| self.days.iter().find(|d| Some(d.date) == date);
+        if let Some(best) = day(self.best_day) {
+            items.push((
+                "Best day",
+                format!(
+                    "{} ({} completed)",
+                    best.date.format("%A"),
+                    best.pomodori_completed
+                ),
+            ));
+        }
+        if let Some(worst) = day(self.worst_day) {
+            items.push((
+                "Worst day",
+                format!(
+                    "{} ({} completed, {} cancelled)",
+                    worst.date.format("%A"),
+                    worst.pomodori_completed,
+                    worst.pomodori_cancelled
+                ),
+            ));
+        }
+        html.list(&items);
+
+        html_interruptions(&mut html, &self.summary);
+        html_entries(&mut html, &self.entries, true);
+        html_hints(&mut html, &self.hints);
+        html.into_string()
     }
+}
 
-    print!("{}", rpt.into_string());
+/// Print a weekly productivity report covering the ISO week containing the given
+/// date (defaults to today), with day-by-day breakdown, week-over-week comparison,
+/// best/worst day, and actionable hints.
+pub fn print_week_report(repo: &Repository, date: Option<String>, timeline: bool, format: Format) {
+    let report = week_report(repo, parse_date_or_today(date)).and_then(|report| {
+        Ok(WeekReport {
+            timeline: collect_timeline(repo, report.start, report.end, timeline)?,
+            entries: collect_entries(repo, report.start, report.end, format == Format::Html)?,
+            ..report
+        })
+    });
+    print(report, format);
 }
 
 // ── Interruption patterns report ────────────────────────────
 
-/// Print an interruption pattern report covering the last N days from the given
-/// date, broken down by hour of day and day of week with internal/external split.
-pub fn print_interruptions_report(repo: &Repository, date: Option<String>, days: u32) {
-    let mut rpt = Report::new();
+/// The interrupt log of a number of days, by hour of day and day of week.
+#[derive(Debug, Serialize)]
+pub struct InterruptionsReport {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub days: u32,
+    /// Hours with at least one interruption, in order
+    pub by_hour: Vec<InterruptionBucket<Hour>>,
+    /// Days of the week with at least one interruption, from Monday
+    pub by_weekday: Vec<InterruptionBucket<Day>>,
+    pub total: usize,
+    pub internal: usize,
+    pub external: usize,
+    /// Sum of the interruption counters, which includes interruptions from
+    /// before the interrupt log was introduced
+    pub counted: i64,
+}
 
-    let date = parse_date_or_today(date);
+/// Compute the interruption patterns of the `days` days ending on `date`.
+pub fn interruptions_report(
+    repo: &Repository,
+    date: NaiveDate,
+    days: u32,
+) -> Result<InterruptionsReport, PersistenceError> {
     let start_date = date - Duration::days(days as i64 - 1);
     let (start, end) = (day_bounds(start_date).0, day_bounds(date).1);
 
-    let (entries, interrupts) = fetch_data(repo, start, end);
+    let (entries, interrupts) = fetch_data(repo, start, end)?;
 
-    let period_label = if days == 1 {
-        format!("{}", date.format("%b %d, %Y"))
-    } else {
-        format!(
-            "{} – {} (last {} days)",
-            start_date.format("%b %d"),
-            date.format("%b %d, %Y"),
-            days
-        )
-    };
+    let mut by_hour: BTreeMap<Hour, (usize, usize)> = BTreeMap::new();
+    let mut by_weekday: BTreeMap<u32, (Weekday, usize, usize)> = BTreeMap::new();
+
+    let clock = timezone::clock();
+    for interrupt in &interrupts {
+        let Some(dt) = clock.datetime(interrupt.created_at) else {
+            continue;
+        };
+        let weekday = dt.date_naive().weekday();
+        let (internal, external) = match interrupt.kind {
+            InterruptionKind::Internal => (1, 0),
+            InterruptionKind::External => (0, 1),
+        };
+
+        let entry_h = by_hour.entry(Hour { hour: dt.hour() }).or_insert((0, 0));
+        entry_h.0 += internal;
+        entry_h.1 += external;
+
+        let entry_w = by_weekday
+            .entry(weekday.num_days_from_monday())
+            .or_insert((weekday, 0, 0));
+        entry_w.1 += internal;
+        entry_w.2 += external;
+    }
 
-    rpt.blank();
-    rpt.line(format_args!("Interruption Patterns: {}", period_label));
-    rpt.separator(52);
-    rpt.blank();
+    let internal = interrupts
+        .iter()
+        .filter(|l| l.kind == InterruptionKind::Internal)
+        .count();
 
-    if interrupts.is_empty() {
-        let total_interruptions: i64 = entries
+    Ok(InterruptionsReport {
+        start: start_date,
+        end: date,
+        days,
+        by_hour: by_hour
+            .into_iter()
+            .map(|(key, (internal, external))| InterruptionBucket {
+                key,
+                total: internal + external,
+                internal,
+                external,
+            })
+            .collect(),
+        by_weekday: by_weekday
+            .into_values()
+            .map(|(weekday, internal, external)| InterruptionBucket {
+                key: Day { weekday },
+                total: internal + external,
+                internal,
+                external,
+            })
+            .collect(),
+        total: interrupts.len(),
+        internal,
+        external: interrupts.len() - internal,
+        counted: entries
             .iter()
             .filter(|e| e.kind == Kind::Pomodoro)
             .map(|e| e.interruptions)
-            .sum();
-        if total_interruptions > 0 {
-            rpt.indent(format_args!("{} interruption(s) recorded via counter in this period, but the interrupt log\n  (which provides kind/hour/day breakdown) is empty. Interruptions recorded\n  before the upgrade are not included in this report.", total_interruptions));
+            .sum(),
+    })
+}
+
+impl InterruptionsReport {
+    fn period_label(&self) -> String {
+        if self.days == 1 {
+            format!("{}", self.end.format("%b %d, %Y"))
         } else {
-            rpt.indent("No interruptions recorded in this period.");
+            format!(
+                "{} – {} (last {} days)",
+                self.start.format("%b %d"),
+                self.end.format("%b %d, %Y"),
+                self.days
+            )
         }
-        rpt.blank();
-        print!("{}", rpt.into_string());
-        return;
     }
+}
 
-    // ── Group by hour of day ──────────────────────────────
-    let mut by_hour: BTreeMap<u32, (usize, usize, usize)> = BTreeMap::new();
-    // ── Group by day of week ──────────────────────────────
-    let mut by_weekday: BTreeMap<u32, (usize, usize, usize)> = BTreeMap::new();
+impl Render for InterruptionsReport {
+    const NAME: &'static str = "interruptions";
 
-    for interrupt in &interrupts {
-        let Some(utc_dt) = chrono::DateTime::from_timestamp(interrupt.created_at, 0) else {
-            continue;
-        };
-        let dt = utc_dt.with_timezone(&Local);
-        let hour = dt.hour();
-        let weekday = dt.date_naive().weekday().num_days_from_monday();
+    fn text(&self) -> String {
+        let mut rpt = Report::new();
 
-        let entry_h = by_hour.entry(hour).or_insert((0, 0, 0));
-        entry_h.0 += 1;
-        match interrupt.kind {
-            InterruptionKind::Internal => entry_h.1 += 1,
-            InterruptionKind::External => entry_h.2 += 1,
-        }
+        rpt.blank();
+        rpt.line(format_args!(
+            "Interruption Patterns: {}",
+            self.period_label()
+        ));
+        rpt.separator(52);
+        rpt.blank();
 
-        let entry_w = by_weekday.entry(weekday).or_insert((0, 0, 0));
-        entry_w.0 += 1;
-        match interrupt.kind {
-            InterruptionKind::Internal => entry_w.1 += 1,
-            InterruptionKind::External => entry_w.2 += 1,
+        if self.total == 0 {
+            if self.counted > 0 {
+                rpt.indent(format_args!("{} interruption(s) recorded via counter in this period, but the interrupt log\n  (which provides kind/hour/day breakdown) is empty. Interruptions recorded\n  before the upgrade are not included in this report.", self.counted));
+            } else {
+                rpt.indent("No interruptions recorded in this period.");
+            }
+            rpt.blank();
+            return rpt.into_string();
         }
-    }
 
-    let max_hour_total = by_hour.values().map(|(t, _, _)| *t).max().unwrap_or(0);
-    let max_wd_total = by_weekday.values().map(|(t, _, _)| *t).max().unwrap_or(0);
+        let max_hour_total = self.by_hour.iter().map(|b| b.total).max().unwrap_or(0);
+        let max_wd_total = self.by_weekday.iter().map(|b| b.total).max().unwrap_or(0);
+        let marker = |total: usize, max: usize| if total == max { "  ⚠" } else { "   " };
 
-    // ── Hourly breakdown ──────────────────────────────────
-    rpt.line("By hour of day:");
-    rpt.indent("Hour      Total  Internal  External");
-    rpt.indent(format_args!("{}", "─".repeat(40)));
-    for hour in 0..24 {
-        let (total, internal, external) = by_hour.get(&hour).copied().unwrap_or((0, 0, 0));
-        if total > 0 {
-            let marker = if total == max_hour_total && total > 0 {
-                "  ⚠"
-            } else {
-                "   "
-            };
+        // ── Hourly breakdown ──────────────────────────────────
+        rpt.line("By hour of day:");
+        rpt.indent("Hour      Total  Internal  External");
+        rpt.indent(format_args!("{}", "─".repeat(40)));
+        for b in &self.by_hour {
             rpt.indent(format_args!(
                 "{:02}:00    {:>5}  {:>8}  {:>8}{}",
-                hour, total, internal, external, marker
+                b.key.hour,
+                b.total,
+                b.internal,
+                b.external,
+                marker(b.total, max_hour_total)
             ));
         }
-    }
-    rpt.blank();
+        rpt.blank();
 
-    // ── Day-of-week breakdown ─────────────────────────────
-    let weekday_names = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
-    rpt.line("By day of week:");
-    rpt.indent("Day       Total  Internal  External");
-    rpt.indent(format_args!("{}", "─".repeat(40)));
-    for wd in 0..7 {
-        let (total, internal, external) = by_weekday.get(&wd).copied().unwrap_or((0, 0, 0));
-        if total > 0 {
-            let marker = if total == max_wd_total && total > 0 {
-                "  ⚠"
-            } else {
-                "   "
-            };
+        // ── Day-of-week breakdown ─────────────────────────────
+        rpt.line("By day of week:");
+        rpt.indent("Day       Total  Internal  External");
+        rpt.indent(format_args!("{}", "─".repeat(40)));
+        for b in &self.by_weekday {
             rpt.indent(format_args!(
                 "{:6}  {:>5}  {:>8}  {:>8}{}",
-                weekday_names[wd as usize], total, internal, external, marker
+                b.key.weekday.to_string(),
+                b.total,
+                b.internal,
+                b.external,
+                marker(b.total, max_wd_total)
             ));
         }
+        rpt.blank();
+
+        // ── Summary stats ─────────────────────────────────────
+        let internal_pct = (self.internal as f64 / self.total as f64 * 100.0) as u32;
+        let external_pct = (self.external as f64 / self.total as f64 * 100.0) as u32;
+
+        rpt.line(format_args!(
+            "Total: {} interruptions ({} internal · {} external, {}% / {}%)",
+            self.total, self.internal, self.external, internal_pct, external_pct
+        ));
+        rpt.blank();
+
+        rpt.into_string()
     }
-    rpt.blank();
 
-    // ── Summary stats ─────────────────────────────────────
-    let total_count: usize = interrupts.len();
-    let internal_count = interrupts
-        .iter()
-        .filter(|l| l.kind == InterruptionKind::Internal)
-        .count();
-    let external_count = total_count - internal_count;
-    let internal_pct = (internal_count as f64 / total_count as f64 * 100.0) as u32;
-    let external_pct = (external_count as f64 / total_count as f64 * 100.0) as u32;
+    fn markdown(&self) -> String {
+        let mut rpt = Report::new();
+        rpt.line(format_args!(
+            "# Interruption Patterns: {}",
+            self.period_label()
+        ));
+        rpt.blank();
+        if self.total == 0 {
+            rpt.line(format_args!(
+                "No interruptions in the interrupt log ({} counted).",
+                self.counted
+            ));
+            return rpt.into_string();
+        }
 
-    rpt.line(format_args!(
-        "Total: {} interruptions ({} internal · {} external, {}% / {}%)",
-        total_count, internal_count, external_count, internal_pct, external_pct
-    ));
-    rpt.blank();
+        let headers = ["Total", "Internal", "External"];
+        let row = |label: String, total: usize, internal: usize, external: usize| {
+            vec![
+                label,
+                total.to_string(),
+                internal.to_string(),
+                external.to_string(),
+            ]
+        };
+        rpt.line("## By hour of day");
+        rpt.blank();
+        let rows: Vec<Vec<String>> = self
+            .by_hour
+            .iter()
+            .map(|b| {
+                row(
+                    format!("{:02}:00", b.key.hour),
+                    b.total,
+                    b.internal,
+                    b.external,
+                )
+            })
+            .collect();
+        rpt.table(&[&["Hour"][..], &headers].concat(), &rows);
+        rpt.blank();
+        rpt.line("## By day of week");
+        rpt.blank();
+        let rows: Vec<Vec<String>> = self
+            .by_weekday
+            .iter()
+            .map(|b| row(b.key.weekday.to_string(), b.total, b.internal, b.external))
+            .collect();
+        rpt.table(&[&["Day"][..], &headers].concat(), &rows);
+        rpt.blank();
+        rpt.line(format_args!(
+            "**Total:** {} interruptions ({} internal · {} external)",
+            self.total, self.internal, self.external
+        ));
+        rpt.into_string()
+    }
+}
 
-    print!("{}", rpt.into_string());
+/// Print an interruption pattern report covering the last N days from the given
+/// date, broken down by hour of day and day of week with internal/external split.
+pub fn print_interruptions_report(
+    repo: &Repository,
+    date: Option<String>,
+    days: u32,
+    format: Format,
+) {
+    print(
+        interruptions_report(repo, parse_date_or_today(date), days),
+        format,
+    );
 }
 
-// ── Monthly report ───────────────────────────────────────────
+// ── Time-of-day report ───────────────────────────────────────
+
+/// Pomodori started in one hour of the day, or in one hour of one day of the
+/// week, by how they ended.
+#[derive(Debug, Serialize)]
+pub struct OutputBucket<K> {
+    #[serde(flatten)]
+    pub key: K,
+    pub completed: usize,
+    pub cancelled: usize,
+    /// Percentage of pomodori that were completed rather than cancelled
+    pub completion_rate: u32,
+}
 
-/// Parse a date string that may be `YYYY-MM` (defaults to the 1st) or
-/// `YYYY-MM-DD`, or defaults to today.
-fn parse_month_date(date: Option<String>) -> NaiveDate {
-    match date {
-        Some(d) => {
-            // Try YYYY-MM first (inject day 01)
-            if let Ok(dt) = NaiveDate::parse_from_str(&format!("{}-01", d), "%Y-%m-%d") {
-                return dt;
-            }
-            // Try YYYY-MM-DD
-            if let Ok(dt) = NaiveDate::parse_from_str(&d, "%Y-%m-%d") {
-                return dt;
-            }
-            eprintln!(
-                "Error: invalid date '{}'. Expected YYYY-MM or YYYY-MM-DD",
-                d
-            );
-            std::process::exit(1);
+impl<K> OutputBucket<K> {
+    fn new(key: K, (completed, cancelled): (usize, usize)) -> Self {
+        OutputBucket {
+            key,
+            completed,
+            cancelled,
+            completion_rate: (completed as f64 / (completed + cancelled) as f64 * 100.0) as u32,
         }
-        None => Local::now().date_naive(),
     }
 }
 
-/// Return the last calendar day of the given month (handles leap years).
-fn last_day_of_month(year: i32, month: u32) -> NaiveDate {
-    if month == 12 {
-        NaiveDate::from_ymd_opt(year + 1, 1, 1).unwrap() - Duration::days(1)
-    } else {
-        NaiveDate::from_ymd_opt(year, month + 1, 1).unwrap() - Duration::days(1)
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Slot {
+    pub weekday: Weekday,
+    pub hour: u32,
 }
 
-/// Compute (year, month) for `offset` months before `(year, month)`.
-fn prev_month(year: i32, month: u32, offset: u32) -> (i32, u32) {
-    let total = (year * 12 + month as i32) - offset as i32;
-    let y = (total - 1).div_euclid(12);
-    let m = (((total - 1).rem_euclid(12)) + 1) as u32;
-    (y, m)
+/// The consecutive hours in which the most pomodori were completed.
+#[derive(Debug, Serialize)]
+pub struct FocusWindow {
+    /// First hour of the window
+    pub start: u32,
+    /// Hour at which the window ends, e.g. 11 for 09:00 – 10:59
+    pub end: u32,
+    pub completed: usize,
+    pub completion_rate: u32,
 }
 
-/// Count the number of days in the range with at least one completed pomodoro,
-/// and the longest consecutive streak of active days.
-fn active_day_stats(
-    entries: &[Schedulable],
-    first_day: NaiveDate,
-    last_day: NaiveDate,
-) -> (u32, u32) {
-    use std::collections::HashSet;
+/// How many hours the [`FocusWindow`] spans.
+const FOCUS_WINDOW_HOURS: u32 = 2;
+
+/// The pomodori of a number of days by the hour they were started in.
+#[derive(Debug, Serialize)]
+pub struct HoursReport {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub days: u32,
+    /// Hours with at least one pomodoro, in order
+    pub by_hour: Vec<OutputBucket<Hour>>,
+    /// Hours of the days of the week with at least one pomodoro, from Monday
+    pub by_weekday_hour: Vec<OutputBucket<Slot>>,
+    pub best_window: Option<FocusWindow>,
+    pub hints: Vec<Hint>,
+}
 
-    let mut active = HashSet::new();
-    for e in entries {
-        if e.kind == Kind::Pomodoro
-            && e.finished_at != 0
-            && let Some(utc_dt) = chrono::DateTime::from_timestamp(e.started_at, 0)
-        {
-            let date = utc_dt.date_naive();
-            if date >= first_day && date <= last_day {
-                active.insert(date);
-            }
-        }
-    }
+/// Compute the time-of-day profile of the `days` days ending on `date`.
+pub fn hours_report(
+    repo: &Repository,
+    date: NaiveDate,
+    days: u32,
+) -> Result<HoursReport, PersistenceError> {
+    let start_date = date - Duration::days(days as i64 - 1);
+    let (start, end) = (day_bounds(start_date).0, day_bounds(date).1);
 
-    let active_count = active.len() as u32;
+    let (entries, _) = fetch_data(repo, start, end)?;
 
-    let mut longest = 0u32;
-    let mut current = 0u32;
-    let mut day = first_day;
-    while day <= last_day {
-        if active.contains(&day) {
-            current += 1;
-            longest = longest.max(current);
-        } else {
-            current = 0;
-        }
-        day += Duration::days(1);
+    let mut by_hour: BTreeMap<u32, (usize, usize)> = BTreeMap::new();
+    let mut by_slot: BTreeMap<(u32, u32), (Weekday, usize, usize)> = BTreeMap::new();
+
+    let clock = timezone::clock();
+    for entry in entries.iter().filter(|e| e.kind == Kind::Pomodoro) {
+        let (completed, cancelled) = match (entry.finished_at, entry.cancelled_at) {
+            (f, _) if f != 0 => (1, 0),
+            (_, c) if c != 0 => (0, 1),
+            _ => continue,
+        };
+        let Some(dt) = clock.datetime(entry.started_at) else {
+            continue;
+        };
+        let weekday = dt.date_naive().weekday();
+
+        let h = by_hour.entry(dt.hour()).or_insert((0, 0));
+        h.0 += completed;
+        h.1 += cancelled;
+
+        let s = by_slot
+            .entry((weekday.num_days_from_monday(), dt.hour()))
+            .or_insert((weekday, 0, 0));
+        s.1 += completed;
+        s.2 += cancelled;
     }
 
-    (active_count, longest)
+    let by_hour: Vec<OutputBucket<Hour>> = by_hour
+        .into_iter()
+        .map(|(hour, counts)| OutputBucket::new(Hour { hour }, counts))
+        .collect();
+    let best_window = best_focus_window(&by_hour);
+    let hints = check_hour_hints(&by_hour, best_window.as_ref());
+
+    Ok(HoursReport {
+        start: start_date,
+        end: date,
+        days,
+        by_weekday_hour: by_slot
+            .into_iter()
+            .map(|((_, hour), (weekday, completed, cancelled))| {
+                OutputBucket::new(Slot { weekday, hour }, (completed, cancelled))
+            })
+            .collect(),
+        by_hour,
+        best_window,
+        hints,
+    })
 }
 
-/// Split a month into ISO-week-aligned chunks and compute per-week aggregates.
-fn compute_weekly_chunks(
-    entries: &[Schedulable],
-    interrupts: &[InterruptLog],
-    first_day: NaiveDate,
-    last_day: NaiveDate,
-) -> Vec<(NaiveDate, AggregateStats)> {
-    let monday = first_day - Duration::days(first_day.weekday().num_days_from_monday() as i64);
-    let mut weeks = Vec::new();
-    let mut current = monday;
-    while current <= last_day {
-        let chunk_end = (current + Duration::days(6)).min(last_day);
-        let (s, e) = (
-            day_bounds(current.max(first_day)).0,
-            day_bounds(chunk_end).1,
-        );
-
-        let week_entries: Vec<Schedulable> = entries
-            .iter()
-            .filter(|en| en.started_at >= s && en.started_at <= e)
-            .cloned()
-            .collect();
-        let week_interrupts: Vec<InterruptLog> = interrupts
+/// The [`FOCUS_WINDOW_HOURS`] consecutive hours with the most completed
+/// pomodori; the earliest of equally good windows.
+fn best_focus_window(by_hour: &[OutputBucket<Hour>]) -> Option<FocusWindow> {
+    let counts = |start: u32| {
+        by_hour
             .iter()
-            .filter(|l| l.created_at >= s && l.created_at <= e)
-            .cloned()
-            .collect();
+            .filter(|b| (start..start + FOCUS_WINDOW_HOURS).contains(&b.key.hour))
+            .fold((0, 0), |(done, canc), b| {
+                (done + b.completed, canc + b.cancelled)
+            })
+    };
+    let (start, (completed, cancelled)) = (0..=24 - FOCUS_WINDOW_HOURS)
+        .map(|start| (start, counts(start)))
+        .rev()
+        .max_by_key(|(_, (completed, _))| *completed)?;
+
+    (completed > 0).then(|| FocusWindow {
+        start,
+        end: start + FOCUS_WINDOW_HOURS,
+        completed,
+        completion_rate: (completed as f64 / (completed + cancelled) as f64 * 100.0) as u32,
+    })
+}
 
-        let agg = compute_aggregate(&week_entries, &week_interrupts);
-        weeks.push((current, agg));
+/// Hints about when to focus and when to schedule meetings.
+fn check_hour_hints(by_hour: &[OutputBucket<Hour>], best: Option<&FocusWindow>) -> Vec<Hint> {
+    let mut hints = Vec::new();
 
-        current += Duration::days(7);
+    if let Some(window) = best {
+        hints.push(Hint::new(
+            HintKind::Tip,
+            format!(
+                "Your best focus window is {:02}–{:02} ({} completed, {}% completion rate). Consider keeping it free of meetings.",
+                window.start, window.end, window.completed, window.completion_rate
+            ),
+        ));
     }
-    weeks
-}
 
-/// Generate monthly-specific hints.
-fn check_monthly_hints(
-    month: &AggregateStats,
-    prev_months: &[((i32, u32), AggregateStats)],
-    active_days: u32,
-    total_days: u32,
-    streak: u32,
-    week_stats: &[(NaiveDate, AggregateStats)],
-) -> Vec<String> {
-    let mut hints = Vec::new();
-    let n_prev = prev_months.len();
+    // The hour with the lowest completion rate, if there is enough data to judge
+    if let Some(worst) = by_hour
+        .iter()
+        .filter(|b| b.completed + b.cancelled >= 3 && b.completion_rate < 70)
+        .min_by_key(|b| b.completion_rate)
+    {
+        hints.push(Hint::new(
+            HintKind::Warning,
+            format!(
+                "Pomodori started at {:02}:00 are often cancelled ({}% completion rate). This may be a better time for meetings.",
+                worst.key.hour, worst.completion_rate
+            ),
+        ));
+    }
 
-    // Active days ratio
-    if active_days > 0 && total_days > 0 {
-        let pct = active_days as f64 / total_days as f64 * 100.0;
-        if pct < 70.0 {
-            let day = if streak == 1 { "day" } else { "days" };
-            hints.push(format!(
-                "⚠ You completed at least one pomodoro on {} of {} days ({:.0}%). Aim for 70%+ for consistent momentum. Best run: {} consecutive {}.",
-                active_days, total_days, pct, streak, day
-            ));
+    hints
+}
+
+impl HoursReport {
+    fn period_label(&self) -> String {
+        if self.days == 1 {
+            format!("{}", self.end.format("%b %d, %Y"))
+        } else {
+            format!(
+                "{} – {} (last {} days)",
+                self.start.format("%b %d"),
+                self.end.format("%b %d, %Y"),
+                self.days
+            )
         }
     }
 
-    // Multi-month trend (need at least 2 previous months for 3-month view)
-    if n_prev >= 2 {
+    /// The hours from the first to the last one with any pomodoro.
+    fn hours(&self) -> std::ops::RangeInclusive<u32> {
+        let first = self.by_hour.first().map_or(0, |b| b.key.hour);
+        let last = self.by_hour.last().map_or(0, |b| b.key.hour);
+        first..=last
+    }
+
+    /// Completed pomodori per day of the week (rows, from Monday) and hour
+    /// (columns, see [`Self::hours`]); `None` where no pomodoro was started.
+    fn grid(&self) -> Vec<(Weekday, Vec<Option<usize>>)> {
+        (0..7)
+            .map(|n| {
+                let weekday = Weekday::try_from(n as u8).unwrap();
+                let cells = self
+                    .hours()
+                    .map(|hour| {
+                        self.by_weekday_hour
+                            .iter()
+                            .find(|b| b.key == Slot { weekday, hour })
+                            .map(|b| b.completed)
+                    })
+                    .collect();
+                (weekday, cells)
+            })
+            .collect()
+    }
+
+    fn in_best_window(&self, hour: u32) -> bool {
+        self.best_window
+            .as_ref()
+            .is_some_and(|w| (w.start..w.end).contains(&hour))
+    }
+}
+
+impl Render for HoursReport {
+    const NAME: &'static str = "hours";
+
+    fn text(&self) -> String {
+        let mut rpt = Report::new();
+
+        rpt.blank();
+        rpt.line(format_args!(
+            "Productivity by Hour: {}",
+            self.period_label()
+        ));
+        rpt.separator(52);
+        rpt.blank();
+
+        if self.by_hour.is_empty() {
+            rpt.indent("No pomodori recorded in this period.");
+            rpt.blank();
+            return rpt.into_string();
+        }
+
+        // ── Hourly breakdown ──────────────────────────────────
+        rpt.line("By hour of day:");
+        rpt.indent("Hour      Done   Canc    Rate");
+        rpt.indent("─".repeat(34));
+        for b in &self.by_hour {
+            rpt.indent(format_args!(
+                "{:02}:00    {:>4}   {:>4}   {:>4}%{}",
+                b.key.hour,
+                b.completed,
+                b.cancelled,
+                b.completion_rate,
+                if self.in_best_window(b.key.hour) {
+                    "  ★"
+                } else {
+                    ""
+                }
+            ));
+        }
+        rpt.blank();
+
+        // ── Weekday × hour grid ───────────────────────────────
+        rpt.line("Completed by day and hour:");
+        let header: String = self.hours().map(|h| format!(" {:02}", h)).collect();
+        rpt.indent(format_args!("     {}", header));
+        for (weekday, cells) in self.grid() {
+            let row: String = cells
+                .iter()
+                .map(|c| match c {
+                    Some(n) => format!("{:>3}", n),
+                    None => "  ·".to_string(),
+                })
+                .collect();
+            rpt.indent(format_args!("{}  {}", weekday, row));
+        }
+        rpt.blank();
+
+        // ── Hints ─────────────────────────────────────────────
+        print_hints(&mut rpt, &self.hints);
+
+        rpt.into_string()
+    }
+
+    fn markdown(&self) -> String {
+        let mut rpt = Report::new();
+        rpt.line(format_args!(
+            "# Productivity by Hour: {}",
+            self.period_label()
+        ));
+        rpt.blank();
+        if self.by_hour.is_empty() {
+            rpt.line("No pomodori recorded in this period.");
+            return rpt.into_string();
+        }
+
+        rpt.line("## By hour of day");
+        rpt.blank();
+        let rows: Vec<Vec<String>> = self
+            .by_hour
+            .iter()
+            .map(|b| {
+                vec![
+                    format!("{:02}:00", b.key.hour),
+                    b.completed.to_string(),
+                    b.cancelled.to_string(),
+                    format!("{}%", b.completion_rate),
+                ]
+            })
+            .collect();
+        rpt.table(&["Hour", "Done", "Cancelled", "Completion rate"], &rows);
+        rpt.blank();
+
+        rpt.line("## Completed by day and hour");
+        rpt.blank();
+        let hours: Vec<String> = self.hours().map(|h| format!("{:02}", h)).collect();
+        let headers: Vec<&str> = std::iter::once("Day")
+            .chain(hours.iter().map(String::as_str))
+            .collect();
+        let rows: Vec<Vec<String>> = self
+            .grid()
+            .into_iter()
+            .map(|(weekday, cells)| {
+                std::iter::once(weekday.to_string())
+                    .chain(
+                        cells
+                            .into_iter()
+                            .map(|c| c.map(|n| n.to_string()).unwrap_or_default()),
+                    )
+                    .collect()
+            })
+            .collect();
+        rpt.table(&headers, &rows);
+        markdown_hints(&mut rpt, &self.hints);
+        rpt.into_string()
+    }
+}
+
+/// Print a time-of-day productivity profile covering the last N days from the
+/// given date, with finished and cancelled pomodori per hour of day and per
+/// day of week and hour, and the best focus window.
+pub fn print_hours_report(repo: &Repository, date: Option<String>, days: u32, format: Format) {
+    print(hours_report(repo, parse_date_or_today(date), days), format);
+}
+
+// ── Break adherence report ───────────────────────────────────
+
+/// Breaks at least this long (in minutes) are long breaks, like in
+/// [`Repository::consecutive_pomodoro_count_at`].
+const LONG_BREAK_MINUTES: i64 = 10;
+
+/// A gap longer than this (in seconds) ends a session, e.g. for lunch; the
+/// entries on either side of it are not checked against each other.
+const SESSION_GAP: i64 = 60 * 60;
+
+/// How closely the breaks of a period followed the technique: a break after
+/// every pomodoro, a long one after every fourth, each as long as planned.
+#[derive(Debug, Default, Serialize)]
+pub struct BreakAdherence {
+    /// Pomodori finished
+    pub pomodori: usize,
+    /// Pomodori followed by another entry in the same session, i.e. all but the last ones
+    pub followed_by_anything: usize,
+    /// Of those, the ones followed by a break within the allowed gap
+    pub followed_by_break: usize,
+    /// Long breaks due after every fourth pomodoro
+    pub long_breaks_due: usize,
+    pub long_breaks_taken: usize,
+    /// Breaks finished or cancelled
+    pub breaks: usize,
+    /// Breaks cancelled or finished early
+    pub cut_short: usize,
+    /// Breaks after which the next pomodoro started later than the allowed gap
+    pub ran_over: usize,
+    /// Average minutes from the end of a pomodoro to the start of the break after it
+    pub average_gap_before: Option<f64>,
+    /// Average minutes from the end of a break to the start of the pomodoro after it
+    pub average_gap_after: Option<f64>,
+    /// Percentage of the checks above that were passed; `None` without any
+    pub score: Option<u32>,
+    /// Breaks that were cut short, ran over, or both
+    #[serde(skip)]
+    breaks_missed: usize,
+    #[serde(skip)]
+    gaps_before: Vec<i64>,
+    #[serde(skip)]
+    gaps_after: Vec<i64>,
+}
+
+impl BreakAdherence {
+    fn add(&mut self, other: &BreakAdherence) {
+        self.pomodori += other.pomodori;
+        self.followed_by_anything += other.followed_by_anything;
+        self.followed_by_break += other.followed_by_break;
+        self.long_breaks_due += other.long_breaks_due;
+        self.long_breaks_taken += other.long_breaks_taken;
+        self.breaks += other.breaks;
+        self.cut_short += other.cut_short;
+        self.ran_over += other.ran_over;
+        self.breaks_missed += other.breaks_missed;
+        self.gaps_before.extend(&other.gaps_before);
+        self.gaps_after.extend(&other.gaps_after);
+        self.finish();
+    }
+
+    /// Compute the averages and the score from the counts.
+    fn finish(&mut self) {
+        let average = |gaps: &[i64]| {
+            (!gaps.is_empty()).then(|| gaps.iter().sum::<i64>() as f64 / gaps.len() as f64 / 60.0)
+        };
+        self.average_gap_before = average(&self.gaps_before);
+        self.average_gap_after = average(&self.gaps_after);
+
+        let checks = self.followed_by_anything + self.long_breaks_due + self.breaks;
+        let passed = self.followed_by_break + self.long_breaks_taken + self.breaks
+            - (self.cut_short + self.ran_over).min(self.breaks);
+        self.score = (checks > 0).then(|| (passed as f64 / checks as f64 * 100.0) as u32);
+    }
+
+    fn has_data(&self) -> bool {
+        self.pomodori > 0 || self.breaks > 0
+    }
+}
+
+/// Check the entries of one day, in the order they were started, against
+/// the technique. `within` is the longest gap in seconds between a pomodoro
+/// and its break, and between the planned end of a break and the next pomodoro.
+fn break_adherence(
+    repo: &Repository,
+    entries: &[&Schedulable],
+    within: i64,
+) -> Result<BreakAdherence, PersistenceError> {
+    let mut tally = BreakAdherence::default();
+
+    for (i, entry) in entries.iter().enumerate() {
+        let ended_at = entry.finished_at.max(entry.cancelled_at);
+        let next = entries
+            .get(i + 1)
+            .filter(|n| n.started_at - ended_at <= SESSION_GAP);
+        match entry.kind {
+            Kind::Pomodoro if entry.finished_at != 0 => {
+                tally.pomodori += 1;
+                let Some(next) = next else {
+                    continue; // the last one of the session
+                };
+                tally.followed_by_anything += 1;
+
+                let count = repo.consecutive_pomodoro_count_at(entry.finished_at)?;
+                let long_due = count > 0 && count % 4 == 0;
+                if long_due {
+                    tally.long_breaks_due += 1;
+                }
+
+                if next.kind == Kind::Break {
+                    let gap = next.started_at - entry.finished_at;
+                    tally.gaps_before.push(gap);
+                    if gap <= within {
+                        tally.followed_by_break += 1;
+                    }
+                    if long_due && next.duration >= LONG_BREAK_MINUTES {
+                        tally.long_breaks_taken += 1;
+                    }
+                }
+            }
+            Kind::Break if entry.finished_at != 0 || entry.cancelled_at != 0 => {
+                tally.breaks += 1;
+                let planned_end = entry.started_at + entry.duration * 60;
+                // A minute of slack for finishing by hand
+                let cut_short = entry.cancelled_at != 0 || ended_at < planned_end - 60;
+                let mut ran_over = false;
+                if let Some(next) = next.filter(|n| n.kind == Kind::Pomodoro) {
+                    tally.gaps_after.push(next.started_at - ended_at);
+                    ran_over = next.started_at - planned_end.max(ended_at) > within;
+                }
+                tally.cut_short += usize::from(cut_short);
+                tally.ran_over += usize::from(ran_over);
+                tally.breaks_missed += usize::from(cut_short || ran_over);
+            }
+            _ => {}
+        }
+    }
+
+    tally.finish();
+    Ok(tally)
+}
+
+/// The break adherence of one day.
+#[derive(Debug, Serialize)]
+pub struct BreakDay {
+    pub date: NaiveDate,
+    #[serde(flatten)]
+    pub adherence: BreakAdherence,
+}
+
+/// The breaks of a number of days, checked against the technique, compared
+/// to the days before.
+#[derive(Debug, Serialize)]
+pub struct BreaksReport {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub days: Vec<BreakDay>,
+    /// The longest gap, in minutes, that still counts as taking a break right
+    /// away or returning from it in time
+    pub within: u32,
+    pub summary: BreakAdherence,
+    pub previous: BreakAdherence,
+    pub hints: Vec<Hint>,
+}
+
+/// Check the breaks of the `days` days ending on `date`.
+pub fn breaks_report(
+    repo: &Repository,
+    date: NaiveDate,
+    days: u32,
+    within: u32,
+) -> Result<BreaksReport, PersistenceError> {
+    let start_date = date - Duration::days(days as i64 - 1);
+    let prev_start_date = start_date - Duration::days(days as i64);
+
+    let by_day = |first: NaiveDate, last: NaiveDate| -> Result<Vec<BreakDay>, PersistenceError> {
+        let entries = repo.entries_between(day_bounds(first).0, day_bounds(last).1)?;
+        first
+            .iter_days()
+            .take_while(|d| *d <= last)
+            .map(|date| {
+                let (start, end) = day_bounds(date);
+                let day_entries: Vec<&Schedulable> = entries
+                    .iter()
+                    .filter(|e| e.started_at >= start && e.started_at <= end)
+                    .collect();
+                Ok(BreakDay {
+                    date,
+                    adherence: break_adherence(repo, &day_entries, within as i64 * 60)?,
+                })
+            })
+            .collect()
+    };
+    let total = |days: &[BreakDay]| {
+        let mut total = BreakAdherence::default();
+        for day in days {
+            total.add(&day.adherence);
+        }
+        total
+    };
+
+    let days = by_day(start_date, date)?;
+    let summary = total(&days);
+    let previous = total(&by_day(prev_start_date, start_date - Duration::days(1))?);
+    let hints = check_break_hints(&summary, &previous, within);
+
+    Ok(BreaksReport {
+        start: start_date,
+        end: date,
+        days,
+        within,
+        summary,
+        previous,
+        hints,
+    })
+}
+
+fn percent(part: usize, whole: usize) -> u32 {
+    (part as f64 / whole as f64 * 100.0) as u32
+}
+
+/// Hints for a period's breaks, compared to the period before.
+pub fn check_break_hints(
+    current: &BreakAdherence,
+    previous: &BreakAdherence,
+    within: u32,
+) -> Vec<Hint> {
+    let mut hints = Vec::new();
+
+    let followed = current.followed_by_anything;
+    if followed >= 3 && percent(current.followed_by_break, followed) < 70 {
+        hints.push(Hint::new(
+            HintKind::Warning,
+            format!(
+                "Only {}% of pomodori were followed by a break within {} minutes. Breaks keep you fresh for the next pomodoro.",
+                percent(current.followed_by_break, followed),
+                within
+            ),
+        ));
+    }
+
+    if current.long_breaks_due > current.long_breaks_taken {
+        hints.push(Hint::new(
+            HintKind::Tip,
+            format!(
+                "{} of {} long breaks after every fourth pomodoro were skipped. A longer rest helps to consolidate what you worked on.",
+                current.long_breaks_due - current.long_breaks_taken,
+                current.long_breaks_due
+            ),
+        ));
+    }
+
+    if current.breaks >= 3 && current.cut_short * 3 > current.breaks {
+        hints.push(Hint::new(
+            HintKind::Warning,
+            format!(
+                "{} of {} breaks were cut short. Try to step away for the whole break.",
+                current.cut_short, current.breaks
+            ),
+        ));
+    }
+
+    if current.breaks >= 3 && current.ran_over * 3 > current.breaks {
+        hints.push(Hint::new(
+            HintKind::Warning,
+            format!(
+                "{} of {} breaks ran over by more than {} minutes. A timer for the way back can help.",
+                current.ran_over, current.breaks, within
+            ),
+        ));
+    }
+
+    // Trend
+    if let (Some(score), Some(prev)) = (current.score, previous.score) {
+        if score >= prev + 10 {
+            hints.push(Hint::new(
+                HintKind::Improvement,
+                format!(
+                    "Break adherence rose from {}% to {}%. Keep it up!",
+                    prev, score
+                ),
+            ));
+        } else if score + 10 <= prev {
+            hints.push(Hint::new(
+                HintKind::Decline,
+                format!(
+                    "Break adherence fell from {}% to {}%. Consider whether workload has changed.",
+                    prev, score
+                ),
+            ));
+        }
+    }
+
+    hints
+}
+
+impl BreaksReport {
+    fn period_label(&self) -> String {
+        if self.days.len() == 1 {
+            format!("{}", self.end.format("%b %d, %Y"))
+        } else {
+            format!(
+                "{} – {} (last {} days)",
+                self.start.format("%b %d"),
+                self.end.format("%b %d, %Y"),
+                self.days.len()
+            )
+        }
+    }
+}
+
+fn ratio(part: usize, whole: usize) -> String {
+    format!("{}/{}", part, whole)
+}
+
+fn score(score: Option<u32>) -> String {
+    score.map_or_else(|| "–".to_string(), |s| format!("{}%", s))
+}
+
+fn minutes(gap: Option<f64>) -> String {
+    gap.map_or_else(|| "–".to_string(), |m| format!("{:.0}m", m))
+}
+
+impl Render for BreaksReport {
+    const NAME: &'static str = "breaks";
+
+    fn text(&self) -> String {
+        let mut rpt = Report::new();
+        let s = &self.summary;
+
+        rpt.blank();
+        rpt.line(format_args!("Break Adherence: {}", self.period_label()));
+        rpt.separator(52);
+        rpt.blank();
+
+        if !s.has_data() {
+            rpt.indent("No pomodori or breaks recorded in this period.");
+            rpt.blank();
+            return rpt.into_string();
+        }
+
+        // ── Day-by-day table ──────────────────────────────────
+        if self.days.len() > 1 {
+            rpt.line("Day-by-day:");
+            rpt.line("  Day      Pom.  Break  Long  Short  Over   Gap  Score");
+            rpt.separator(56);
+            for day in self.days.iter().filter(|d| d.adherence.has_data()) {
+                let a = &day.adherence;
+                rpt.line(format_args!(
+                    "  {:7} {:>4}  {:>5}  {:>4}  {:>5}  {:>4}  {:>4}  {:>5}",
+                    day.date.format("%a %-d").to_string(),
+                    a.pomodori,
+                    ratio(a.followed_by_break, a.followed_by_anything),
+                    ratio(a.long_breaks_taken, a.long_breaks_due),
+                    a.cut_short,
+                    a.ran_over,
+                    minutes(a.average_gap_before),
+                    score(a.score),
+                ));
+            }
+            rpt.blank();
+        }
+
+        // ── Summary ───────────────────────────────────────────
+        rpt.line("Summary:");
+        rpt.indent(format_args!(
+            "Break after pomodoro:  {} of {} within {} min",
+            s.followed_by_break, s.followed_by_anything, self.within
+        ));
+        rpt.indent(format_args!(
+            "Long breaks:           {} of {} after every fourth pomodoro",
+            s.long_breaks_taken, s.long_breaks_due
+        ));
+        rpt.indent(format_args!(
+            "Cut short:             {} of {} breaks",
+            s.cut_short, s.breaks
+        ));
+        rpt.indent(format_args!(
+            "Ran over:              {} of {} breaks",
+            s.ran_over, s.breaks
+        ));
+        if s.average_gap_before.is_some() || s.average_gap_after.is_some() {
+            rpt.indent(format_args!(
+                "Gaps:                  {} before a break  \u{00b7}  {} after it",
+                minutes(s.average_gap_before),
+                minutes(s.average_gap_after)
+            ));
+        }
+        let prev = self
+            .previous
+            .score
+            .map(|p| format!(" (prev: {}%)", p))
+            .unwrap_or_default();
+        rpt.indent(format_args!(
+            "Adherence score:       {}{}",
+            score(s.score),
+            prev
+        ));
+        rpt.blank();
+
+        // ── Hints ─────────────────────────────────────────────
+        print_hints(&mut rpt, &self.hints);
+
+        rpt.into_string()
+    }
+
+    fn markdown(&self) -> String {
+        let mut rpt = Report::new();
+        let s = &self.summary;
+        rpt.line(format_args!("# Break Adherence: {}", self.period_label()));
+        rpt.blank();
+        let rows: Vec<Vec<String>> = self
+            .days
+            .iter()
+            .filter(|d| d.adherence.has_data())
+            .map(|day| {
+                let a = &day.adherence;
+                vec![
+                    day.date.format("%Y-%m-%d").to_string(),
+                    a.pomodori.to_string(),
+                    ratio(a.followed_by_break, a.followed_by_anything),
+                    ratio(a.long_breaks_taken, a.long_breaks_due),
+                    a.cut_short.to_string(),
+                    a.ran_over.to_string(),
+                    minutes(a.average_gap_before),
+                    score(a.score),
+                ]
+            })
+            .collect();
+        if !rows.is_empty() {
+            rpt.table(
+                &[
+                    "Day",
+                    "Pomodori",
+                    "Break after",
+                    "Long breaks",
+                    "Cut short",
+                    "Ran over",
+                    "Gap",
+                    "Score",
+                ],
+                &rows,
+            );
+            rpt.blank();
+        }
+        rpt.line("## Summary");
+        rpt.blank();
+        rpt.line(format_args!(
+            "- **Break after pomodoro:** {} of {} within {} min",
+            s.followed_by_break, s.followed_by_anything, self.within
+        ));
+        rpt.line(format_args!(
+            "- **Long breaks:** {} of {}",
+            s.long_breaks_taken, s.long_breaks_due
+        ));
+        rpt.line(format_args!(
+            "- **Cut short:** {} · **Ran over:** {} of {} breaks",
+            s.cut_short, s.ran_over, s.breaks
+        ));
+        rpt.line(format_args!("- **Adherence score:** {}", score(s.score)));
+        markdown_hints(&mut rpt, &self.hints);
+        rpt.into_string()
+    }
+}
+
+/// Print a break adherence report covering the last N days from the given
+/// date, with per-day scores, comparison to the days before, and hints.
+pub fn print_breaks_report(
+    repo: &Repository,
+    date: Option<String>,
+    days: u32,
+    within: u32,
+    format: Format,
+) {
+    print(
+        breaks_report(repo, parse_date_or_today(date), days, within),
+        format,
+    );
+}
+
+// ── Monthly report ───────────────────────────────────────────
+
+/// Parse a date string that may be `YYYY-MM` (defaults to the 1st) or
+/// `YYYY-MM-DD`, or defaults to today.
+fn parse_month_date(date: Option<String>) -> NaiveDate {
+    match date {
+        Some(d) => {
+            // Try YYYY-MM first (inject day 01)
+            if let Ok(dt) = NaiveDate::parse_from_str(&format!("{}-01", d), "%Y-%m-%d") {
+                return dt;
+            }
+            // Try YYYY-MM-DD
+            if let Ok(dt) = NaiveDate::parse_from_str(&d, "%Y-%m-%d") {
+                return dt;
+            }
+            eprintln!(
+                "Error: invalid date '{}'. Expected YYYY-MM or YYYY-MM-DD",
+                d
+            );
+            std::process::exit(1);
+        }
+        None => timezone::clock().today(),
+    }
+}
+
+/// Return the last calendar day of the given month (handles leap years).
+fn last_day_of_month(year: i32, month: u32) -> NaiveDate {
+    if month == 12 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1).unwrap() - Duration::days(1)
+    } else {
+        NaiveDate::from_ymd_opt(year, month + 1, 1).unwrap() - Duration::days(1)
+    }
+}
+
+/// Compute (year, month) for `offset` months before `(year, month)`.
+fn prev_month(year: i32, month: u32, offset: u32) -> (i32, u32) {
+    let total = (year * 12 + month as i32) - offset as i32;
+    let y = (total - 1).div_euclid(12);
+    let m = (((total - 1).rem_euclid(12)) + 1) as u32;
+    (y, m)
+}
+
+/// Count the number of days in the range with at least one completed pomodoro,
+/// and the longest consecutive streak of active days.
+pub fn active_day_stats(
+    entries: &[Schedulable],
+    first_day: NaiveDate,
+    last_day: NaiveDate,
+) -> ActiveDays {
+    use std::collections::HashSet;
+
+    let clock = timezone::clock();
+    let mut active = HashSet::new();
+    for e in entries {
+        if e.kind == Kind::Pomodoro
+            && e.finished_at != 0
+            && let Some(dt) = clock.datetime(e.started_at)
+        {
+            let date = dt.date_naive();
+            if date >= first_day && date <= last_day {
+                active.insert(date);
+            }
+        }
+    }
+
+    let mut longest = 0u32;
+    let mut current = 0u32;
+    let mut day = first_day;
+    while day <= last_day {
+        if active.contains(&day) {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+        day += Duration::days(1);
+    }
+
+    ActiveDays {
+        active: active.len() as u32,
+        total: ((last_day - first_day).num_days() + 1).max(0) as u32,
+        best_streak: longest,
+    }
+}
+
+/// Split a month into ISO-week-aligned chunks and compute per-week aggregates.
+pub fn compute_weekly_chunks(
+    entries: &[Schedulable],
+    interrupts: &[InterruptLog],
+    first_day: NaiveDate,
+    last_day: NaiveDate,
+) -> Vec<WeekChunk> {
+    let monday = first_day - Duration::days(first_day.weekday().num_days_from_monday() as i64);
+    let mut weeks = Vec::new();
+    let mut current = monday;
+    while current <= last_day {
+        let chunk_end = (current + Duration::days(6)).min(last_day);
+        let (s, e) = (
+            day_bounds(current.max(first_day)).0,
+            day_bounds(chunk_end).1,
+        );
+
+        let week_entries: Vec<Schedulable> = entries
+            .iter()
+            .filter(|en| en.started_at >= s && en.started_at <= e)
+            .cloned()
+            .collect();
+        let week_interrupts: Vec<InterruptLog> = interrupts
+            .iter()
+            .filter(|l| l.created_at >= s && l.created_at <= e)
+            .cloned()
+            .collect();
+
+        weeks.push(WeekChunk {
+            week_start: current,
+            stats: compute_aggregate(&week_entries, &week_interrupts),
+        });
+
+        current += Duration::days(7);
+    }
+    weeks
+}
+
+/// Generate monthly-specific hints.
+pub fn check_monthly_hints(
+    month: &AggregateStats,
+    prev_months: &[MonthStats],
+    active_days: &ActiveDays,
+    week_stats: &[WeekChunk],
+) -> Vec<Hint> {
+    let mut hints = Vec::new();
+    let n_prev = prev_months.len();
+
+    // Active days ratio
+    if active_days.active > 0 && active_days.total > 0 {
+        let pct = active_days.active as f64 / active_days.total as f64 * 100.0;
+        if pct < 70.0 {
+            let day = if active_days.best_streak == 1 {
+                "day"
+            } else {
+                "days"
+            };
+            hints.push(Hint::new(
+                HintKind::Warning,
+                format!(
+                    "You completed at least one pomodoro on {} of {} days ({:.0}%). Aim for 70%+ for consistent momentum. Best run: {} consecutive {}.",
+                    active_days.active, active_days.total, pct, active_days.best_streak, day
+                ),
+            ));
+        }
+    }
+
+    // Multi-month trend (need at least 2 previous months for 3-month view)
+    if n_prev >= 2 {
         let three = [
-            &prev_months[n_prev - 2].1,
-            &prev_months[n_prev - 1].1,
+            &prev_months[n_prev - 2].stats,
+            &prev_months[n_prev - 1].stats,
             month,
         ];
         let rates: Vec<u32> = three.iter().map(|a| a.completion_rate).collect();
 
-        if rates[0] > rates[1] && rates[1] > rates[2] && rates[2] > 0 {
-            hints.push(format!(
-                "📉 Your completion rate has declined for 3 months in a row ({}% → {}% → {}%). This pattern often precedes burnout — consider a recovery day or adjusting pomodoro duration.",
-                rates[0], rates[1], rates[2]
-            ));
-        } else if rates[0] < rates[1] && rates[1] < rates[2] && rates[2] >= rates[1] {
-            hints.push(format!(
-                "📈 Consistent improvement over 3 months ({}% → {}% → {}%). Your adjustments are working.",
-                rates[0], rates[1], rates[2]
-            ));
+        if rates[0] > rates[1] && rates[1] > rates[2] && rates[2] > 0 {
+            hints.push(Hint::new(
+                HintKind::Decline,
+                format!(
+                    "Your completion rate has declined for 3 months in a row ({}% → {}% → {}%). This pattern often precedes burnout — consider a recovery day or adjusting pomodoro duration.",
+                    rates[0], rates[1], rates[2]
+                ),
+            ));
+        } else if rates[0] < rates[1] && rates[1] < rates[2] && rates[2] >= rates[1] {
+            hints.push(Hint::new(
+                HintKind::Improvement,
+                format!(
+                    "Consistent improvement over 3 months ({}% → {}% → {}%). Your adjustments are working.",
+                    rates[0], rates[1], rates[2]
+                ),
+            ));
+        }
+    }
+
+    // Month-over-month changes (need at least 1 previous)
+    if n_prev >= 1 {
+        let prev = &prev_months[n_prev - 1].stats;
+
+        if prev.internal_count > 0 && month.internal_count < prev.internal_count {
+            let drop = ((prev.internal_count - month.internal_count) as f64
+                / prev.internal_count as f64
+                * 100.0) as u32;
+            hints.push(Hint::new(
+                HintKind::Progress,
+                format!(
+                    "Internal interruptions are down {}% vs last month. Your focus practices are paying off.",
+                    drop
+                ),
+            ));
+        }
+        if prev.external_count > 0 && month.external_count > prev.external_count {
+            let rise = ((month.external_count - prev.external_count) as f64
+                / prev.external_count as f64
+                * 100.0) as u32;
+            hints.push(Hint::new(
+                HintKind::Warning,
+                format!(
+                    "External interruptions increased {}% this month. Were there specific environmental changes?",
+                    rise
+                ),
+            ));
+        }
+
+        if prev.max_focus_block > 0 && month.max_focus_block < prev.max_focus_block {
+            hints.push(Hint::new(
+                HintKind::Warning,
+                format!(
+                    "Longest focus block shrank from {} → {} consecutive pomodori. Consider whether task-switching demands have increased.",
+                    prev.max_focus_block, month.max_focus_block
+                ),
+            ));
+        }
+    }
+
+    // Week-to-week variation
+    let with_data: Vec<&WeekChunk> = week_stats
+        .iter()
+        .filter(|w| w.stats.completed > 0)
+        .collect();
+    if with_data.len() >= 2 {
+        let max = with_data
+            .iter()
+            .map(|w| w.stats.completed)
+            .max()
+            .unwrap_or(0);
+        let min = with_data
+            .iter()
+            .map(|w| w.stats.completed)
+            .min()
+            .unwrap_or(0);
+        if min > 0 && max > min * 2 {
+            hints.push(Hint::new(
+                HintKind::Tip,
+                format!(
+                    "Your most productive week had {}× the pomodori of your least productive week. What changed between weeks?",
+                    (max as f64 / min as f64) as u32
+                ),
+            ));
+        }
+    }
+
+    hints
+}
+
+/// A calendar month, week by week, compared to the months before.
+#[derive(Debug, Serialize)]
+pub struct MonthReport {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub weeks: Vec<WeekChunk>,
+    pub summary: AggregateStats,
+    /// The months before, most recent first
+    pub previous_months: Vec<MonthStats>,
+    pub active_days: ActiveDays,
+    /// Completion rate over this and the previous months, if there are any
+    pub average_completion_rate: Option<u32>,
+    pub best_week: Option<NaiveDate>,
+    pub worst_week: Option<NaiveDate>,
+    pub hints: Vec<Hint>,
+    /// The entries with their annotations, for the HTML report
+    #[serde(skip)]
+    pub entries: Vec<DayEntry>,
+}
+
+/// Compute the report for the calendar month containing `date`, together
+/// with the `months_to_show - 1` months before it.
+pub fn month_report(
+    repo: &Repository,
+    date: NaiveDate,
+    months_to_show: u32,
+) -> Result<MonthReport, PersistenceError> {
+    let (year, month_num) = (date.year(), date.month());
+
+    let first_day = NaiveDate::from_ymd_opt(year, month_num, 1).unwrap();
+    let last_day = last_day_of_month(year, month_num);
+    let (month_start, month_end) = (day_bounds(first_day).0, day_bounds(last_day).1);
+
+    // Fetch target month
+    let (target_entries, target_interrupts) = fetch_data(repo, month_start, month_end)?;
+    let summary = compute_aggregate(&target_entries, &target_interrupts);
+
+    // Weekly breakdown
+    let weeks = compute_weekly_chunks(&target_entries, &target_interrupts, first_day, last_day);
+
+    // Previous months
+    let mut previous_months = Vec::new();
+    for i in 1..months_to_show {
+        let (py, pm) = prev_month(year, month_num, i);
+        let (pf, pl) = (
+            NaiveDate::from_ymd_opt(py, pm, 1).unwrap(),
+            last_day_of_month(py, pm),
+        );
+        let (ps, pe) = (day_bounds(pf).0, day_bounds(pl).1);
+        let (pe_entries, pe_interrupts) = fetch_data(repo, ps, pe)?;
+        previous_months.push(MonthStats {
+            year: py,
+            month: pm,
+            stats: compute_aggregate(&pe_entries, &pe_interrupts),
+        });
+    }
+
+    let active_days = active_day_stats(&target_entries, first_day, last_day);
+
+    // Multi-month average completion rate
+    let average_completion_rate = (months_to_show > 1).then(|| {
+        let total_comp: usize = previous_months
+            .iter()
+            .map(|m| m.stats.completed)
+            .sum::<usize>()
+            + summary.completed;
+        let total_canc: usize = previous_months
+            .iter()
+            .map(|m| m.stats.cancelled)
+            .sum::<usize>()
+            + summary.cancelled;
+        let total = total_comp + total_canc;
+        if total > 0 {
+            (total_comp as f64 / total as f64 * 100.0) as u32
+        } else {
+            0
+        }
+    });
+
+    let best_week = weeks
+        .iter()
+        .filter(|w| w.stats.completed > 0)
+        .max_by_key(|w| w.stats.completed)
+        .map(|w| w.week_start);
+    let worst_week = weeks
+        .iter()
+        .filter(|w| w.stats.completed > 0 || w.stats.cancelled > 0)
+        .min_by_key(|w| (w.stats.completed as i64) - (w.stats.cancelled as i64))
+        .map(|w| w.week_start);
+
+    let hints = check_monthly_hints(&summary, &previous_months, &active_days, &weeks);
+
+    Ok(MonthReport {
+        start: first_day,
+        end: last_day,
+        weeks,
+        summary,
+        previous_months,
+        active_days,
+        average_completion_rate,
+        best_week,
+        worst_week,
+        hints,
+        entries: Vec::new(),
+    })
+}
+
+impl MonthReport {
+    fn week(&self, start: Option<NaiveDate>) -> Option<&WeekChunk> {
+        self.weeks.iter().find(|w| Some(w.week_start) == start)
+    }
+
+    fn week_rows(&self) -> Vec<Vec<String>> {
+        self.weeks
+            .iter()
+            .map(|w| {
+                vec![
+                    w.week_start.format("%b %-d").to_string(),
+                    w.stats.completed.to_string(),
+                    w.stats.cancelled.to_string(),
+                    w.stats.breaks_taken.to_string(),
+                    w.stats.breaks_cancelled.to_string(),
+                    w.stats.total_interruptions.to_string(),
+                ]
+            })
+            .collect()
+    }
+
+    fn previous_month_rows(&self) -> Vec<Vec<String>> {
+        self.previous_months
+            .iter()
+            .map(|m| {
+                vec![
+                    format!("{}-{:02}", m.year, m.month),
+                    m.stats.completed.to_string(),
+                    m.stats.cancelled.to_string(),
+                    format!("{}%", m.stats.completion_rate),
+                ]
+            })
+            .collect()
+    }
+}
+
+impl Render for MonthReport {
+    const NAME: &'static str = "month";
+
+    fn text(&self) -> String {
+        let target_agg = &self.summary;
+
+        // ── Print header ──────────────────────────────────────
+        let mut rpt = Report::new();
+        rpt.blank();
+        rpt.line(format_args!(
+            "Monthly Report: {}",
+            self.start.format("%B %Y")
+        ));
+        if !self.previous_months.is_empty() {
+            let labels: Vec<String> = self
+                .previous_months
+                .iter()
+                .rev()
+                .map(|m| {
+                    let d = NaiveDate::from_ymd_opt(m.year, m.month, 1).unwrap();
+                    format!("{}", d.format("%b %Y"))
+                })
+                .collect();
+            rpt.line(format_args!("(vs {})", labels.join(" · ")));
+        }
+        rpt.separator(52);
+        rpt.blank();
+
+        // ── Week-by-week table ────────────────────────────────
+        rpt.line("Week-by-week breakdown:");
+        rpt.indent("Week        Done   Canc  Brk ▼  Brk ✗   Interr.");
+        rpt.indent("─".repeat(52));
+        if !self.weeks.iter().any(|w| w.stats.has_data()) {
+            rpt.indent("(nothing recorded this month)");
+        } else {
+            for WeekChunk { week_start, stats } in &self.weeks {
+                if !stats.has_data() {
+                    continue;
+                }
+                let label = format!(
+                    "{} {}",
+                    week_start.format("%b %-d"),
+                    (*week_start + Duration::days(6)).format("– %-d")
+                );
+                rpt.line(format_args!(
+                    "  {:>12}{} {:>4}  {:>4}  {:>4}  {:>4}  {:>7}",
+                    label,
+                    day_marker(*week_start, self.best_week, self.worst_week),
+                    stats.completed,
+                    stats.cancelled,
+                    stats.breaks_taken,
+                    stats.breaks_cancelled,
+                    stats.total_interruptions,
+                ));
+            }
+        }
+        rpt.blank();
+
+        // ── Monthly summary ───────────────────────────────────
+        rpt.line("Monthly summary:");
+        if !target_agg.has_data() {
+            rpt.indent("No pomodori or breaks recorded this month.");
+            rpt.blank();
+            return rpt.into_string();
+        }
+
+        let prev_agg = self.previous_months.last().map(|m| &m.stats);
+        write_metrics(
+            &mut rpt,
+            target_agg,
+            prev_agg.map(|prev| prev.completion_rate),
+            Some(&self.active_days),
+        );
+
+        if let Some(average) = self.average_completion_rate.filter(|a| *a > 0) {
+            rpt.indent(format_args!(
+                "{}-month avg completion rate: {}%",
+                (self.previous_months.len() + 1).min(12),
+                average
+            ));
+        }
+        rpt.blank();
+
+        // ── Interruptions ─────────────────────────────────────
+        print_interruption_summary(&mut rpt, target_agg, Some("prev month"), prev_agg);
+
+        // ── Best / worst week ─────────────────────────────────
+        if let Some(week) = self.week(self.best_week) {
+            rpt.line(format_args!(
+                "★  Best week: {} ({} completed)",
+                week.week_start.format("%b %-d"),
+                week.stats.completed
+            ));
+        }
+        if let Some(week) = self.week(self.worst_week) {
+            rpt.line(format_args!(
+                "⊗  Worst week: {} ({} completed, {} cancelled)",
+                week.week_start.format("%b %-d"),
+                week.stats.completed,
+                week.stats.cancelled
+            ));
+        }
+        rpt.blank();
+
+        // ── Hints ─────────────────────────────────────────────
+        print_hints(&mut rpt, &self.hints);
+
+        rpt.into_string()
+    }
+
+    fn markdown(&self) -> String {
+        let mut rpt = Report::new();
+        rpt.line(format_args!(
+            "# Monthly Report: {}",
+            self.start.format("%B %Y")
+        ));
+        rpt.blank();
+        rpt.table(&markdown_headers("Week of"), &self.week_rows());
+        rpt.blank();
+        rpt.line("## Summary");
+        rpt.blank();
+        markdown_metrics(&mut rpt, &self.summary, Some(&self.active_days));
+        if !self.previous_months.is_empty() {
+            rpt.blank();
+            rpt.line("## Previous months");
+            rpt.blank();
+            rpt.table(
+                &["Month", "Done", "Cancelled", "Completion rate"],
+                &self.previous_month_rows(),
+            );
         }
+        markdown_hints(&mut rpt, &self.hints);
+        rpt.into_string()
     }
 
-    // Month-over-month changes (need at least 1 previous)
-    if n_prev >= 1 {
-        let prev = &prev_months[n_prev - 1].1;
+    fn html(&self) -> String {
+        let title = format!("Monthly Report: {}", self.start.format("%B %Y"));
+        let mut html = Html::new(&title);
+        html.element("h1", &title);
 
-        if prev.internal_count > 0 && month.internal_count < prev.internal_count {
-            let drop = ((prev.internal_count - month.internal_count) as f64
-                / prev.internal_count as f64
-                * 100.0) as u32;
-            hints.push(format!(
-                "✅ Internal interruptions are down {}% vs last month. Your focus practices are paying off.",
-                drop
+        html.element("h2", "Week by week");
+        html.chart(&weekly_chart(
+            "Pomodori per week".to_string(),
+            &self.weeks,
+            Style::Bars,
+        ));
+        html.table(&markdown_headers("Week of"), &self.week_rows());
+
+        html.element("h2", "Summary");
+        if !self.summary.has_data() {
+            html.muted("No pomodori or breaks recorded this month.");
+            return html.into_string();
+        }
+        let mut items = metric_items(&self.summary, Some(&self.active_days));
+        if let Some(average) = self.average_completion_rate.filter(|a| *a > 0) {
+            items.push((
+                "Average completion rate",
+                format!(
+                    "{}% over {} months",
+                    average,
+                    (self.previous_months.len() + 1).min(12)
+                ),
             ));
         }
-        if prev.external_count > 0 && month.external_count > prev.external_count {
-            let rise = ((month.external_count - prev.external_count) as f64
-                / prev.external_count as f64
-                * 100.0) as u32;
-            hints.push(format!(
-                "⚠ External interruptions increased {}% this month. Were there specific environmental changes?",
-                rise
+        if let Some(week) = self.week(self.best_week) {
+            items.push((
+                "Best week",
+                format!(
+                    "{} ({} completed)",
+                    week.week_start.format("%b %-d"),
+                    week.stats.completed
+                ),
             ));
         }
-
-        if prev.max_focus_block > 0 && month.max_focus_block < prev.max_focus_block {
-            hints.push(format!(
-                "⚠ Longest focus block shrank from {} → {} consecutive pomodori. Consider whether task-switching demands have increased.",
-                prev.max_focus_block, month.max_focus_block
+        if let Some(week) = self.week(self.worst_week) {
+            items.push((
+                "Worst week",
+                format!(
+                    "{} ({} completed, {} cancelled)",
+                    week.week_start.format("%b %-d"),
+                    week.stats.completed,
+                    week.stats.cancelled
+                ),
             ));
         }
+        html.list(&items);
+        if !self.previous_months.is_empty() {
+            html.element("h2", "Previous months");
+            html.table(
+                &["Month", "Done", "Cancelled", "Completion rate"],
+                &self.previous_month_rows(),
+            );
+        }
+
+        html_interruptions(&mut html, &self.summary);
+        html_entries(&mut html, &self.entries, true);
+        html_hints(&mut html, &self.hints);
+        html.into_string()
     }
+}
 
-    // Week-to-week variation
-    let with_data: Vec<&(NaiveDate, AggregateStats)> =
-        week_stats.iter().filter(|(_, a)| a.completed > 0).collect();
-    if with_data.len() >= 2 {
-        let max = with_data
+/// Print a monthly productivity report covering the calendar month containing
+/// the given date (defaults to today), with week-by-week breakdown,
+/// multi-month comparison, active-day stats, and actionable hints.
+pub fn print_month_report(
+    repo: &Repository,
+    date: Option<String>,
+    months_to_show: u32,
+    format: Format,
+) {
+    let report = month_report(repo, parse_month_date(date), months_to_show).and_then(|report| {
+        Ok(MonthReport {
+            entries: collect_entries(repo, report.start, report.end, format == Format::Html)?,
+            ..report
+        })
+    });
+    print(report, format);
+}
+
+// ── Yearly report ────────────────────────────────────────────
+
+/// The characters of the heatmap cells, from no pomodoro to the most.
+const SHADES: [char; 5] = ['·', '░', '▒', '▓', '█'];
+
+/// The ANSI 256-colour palette of the heatmap cells, from grey to bright green.
+const COLOURS: [u8; 5] = [238, 22, 28, 34, 77];
+
+/// A calendar year, day by day, compared to the year before.
+#[derive(Debug, Serialize)]
+pub struct YearReport {
+    pub year: i32,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub days: Vec<DayStats>,
+    pub months: Vec<MonthStats>,
+    pub summary: AggregateStats,
+    pub previous: AggregateStats,
+    pub active_days: ActiveDays,
+    pub best_week: Option<WeekChunk>,
+    /// Whether the text heatmap uses ANSI colours instead of shade blocks
+    #[serde(skip)]
+    pub colour: bool,
+}
+
+/// Compute the report for a calendar year.
+pub fn year_report(repo: &Repository, year: i32) -> Result<YearReport, PersistenceError> {
+    let bounds = |year: i32| {
+        let first = NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
+        let last = NaiveDate::from_ymd_opt(year, 12, 31).unwrap();
+        (first, last, day_bounds(first).0, day_bounds(last).1)
+    };
+
+    let (first_day, last_day, start, end) = bounds(year);
+    let (entries, interrupts) = fetch_data(repo, start, end)?;
+    let (_, _, prev_start, prev_end) = bounds(year - 1);
+    let (prev_entries, prev_interrupts) = fetch_data(repo, prev_start, prev_end)?;
+
+    let months = (1..=12)
+        .map(|month| {
+            let (s, e) = (
+                day_bounds(NaiveDate::from_ymd_opt(year, month, 1).unwrap()).0,
+                day_bounds(last_day_of_month(year, month)).1,
+            );
+            let month_entries: Vec<Schedulable> = entries
+                .iter()
+                .filter(|en| en.started_at >= s && en.started_at <= e)
+                .cloned()
+                .collect();
+            let month_interrupts: Vec<InterruptLog> = interrupts
+                .iter()
+                .filter(|l| l.created_at >= s && l.created_at <= e)
+                .cloned()
+                .collect();
+            MonthStats {
+                year,
+                month,
+                stats: compute_aggregate(&month_entries, &month_interrupts),
+            }
+        })
+        .collect();
+
+    let best_week = compute_weekly_chunks(&entries, &interrupts, first_day, last_day)
+        .into_iter()
+        .filter(|w| w.stats.completed > 0)
+        .rev()
+        .max_by_key(|w| w.stats.completed);
+
+    Ok(YearReport {
+        year,
+        start: first_day,
+        end: last_day,
+        days: compute_day_stats(&entries, &interrupts, first_day, last_day),
+        months,
+        summary: compute_aggregate(&entries, &interrupts),
+        previous: compute_aggregate(&prev_entries, &prev_interrupts),
+        active_days: active_day_stats(&entries, first_day, last_day),
+        best_week,
+        colour: false,
+    })
+}
+
+/// The shade of a day with `count` finished pomodori, from 0 (none) to 4,
+/// in quarters of the busiest day like GitHub's contribution graph.
+fn heat_level(count: usize, max: usize) -> usize {
+    if count == 0 || max == 0 {
+        0
+    } else {
+        (count * 4).div_ceil(max).clamp(1, 4)
+    }
+}
+
+impl YearReport {
+    fn cell(level: usize, colour: bool) -> String {
+        if colour {
+            format!("\x1b[38;5;{}m■\x1b[0m", COLOURS[level])
+        } else {
+            SHADES[level].to_string()
+        }
+    }
+
+    /// A calendar heatmap with one column per week, Monday to Sunday from
+    /// top to bottom, and the months labelled above.
+    fn heatmap(&self, colour: bool) -> Vec<String> {
+        let max = self
+            .days
             .iter()
-            .map(|(_, a)| a.completed)
+            .map(|d| d.pomodori_completed)
             .max()
             .unwrap_or(0);
-        let min = with_data
-            .iter()
-            .map(|(_, a)| a.completed)
-            .min()
-            .unwrap_or(0);
-        if min > 0 && max > min * 2 {
-            hints.push(format!(
-                "💡 Your most productive week had {}× the pomodori of your least productive week. What changed between weeks?",
-                (max as f64 / min as f64) as u32
-            ));
+        let grid_start =
+            self.start - Duration::days(self.start.weekday().num_days_from_monday() as i64);
+        let column = |date: NaiveDate| ((date - grid_start).num_days() / 7) as usize;
+        let columns = column(self.end) + 1;
+
+        let mut labels = vec![' '; columns + 3];
+        for month in 1..=12 {
+            let first = NaiveDate::from_ymd_opt(self.year, month, 1).unwrap();
+            for (i, c) in first.format("%b").to_string().chars().enumerate() {
+                labels[column(first) + i] = c;
+            }
+        }
+        let mut lines = vec![format!(
+            "    {}",
+            labels.iter().collect::<String>().trim_end()
+        )];
+
+        let mut rows = vec![vec![" ".to_string(); columns]; 7];
+        for d in &self.days {
+            rows[d.date.weekday().num_days_from_monday() as usize][column(d.date)] =
+                Self::cell(heat_level(d.pomodori_completed, max), colour);
+        }
+        for (weekday, row) in rows.iter().enumerate() {
+            let label = match weekday {
+                0 => "Mon",
+                2 => "Wed",
+                4 => "Fri",
+                _ => "",
+            };
+            lines.push(
+                format!("{:4}{}", label, row.concat())
+                    .trim_end()
+                    .to_string(),
+            );
         }
+
+        let legend: Vec<String> = (0..SHADES.len()).map(|l| Self::cell(l, colour)).collect();
+        lines.push(format!("    Less {} More", legend.join(" ")));
+        lines
     }
 
-    hints
+    /// How this year compares to the one before, e.g. `+25%`.
+    fn change(&self) -> Option<String> {
+        (self.previous.completed > 0).then(|| {
+            let change =
+                (self.summary.completed as f64 / self.previous.completed as f64 - 1.0) * 100.0;
+            format!("{:+.0}%", change)
+        })
+    }
 }
 
-/// Print a monthly productivity report covering the calendar month containing
-/// the given date (defaults to today), with week-by-week breakdown,
-/// multi-month comparison, active-day stats, and actionable hints.
-pub fn print_month_report(repo: &Repository, date: Option<String>, months_to_show: u32) {
-    let date = parse_month_date(date);
-    let (year, month_num) = (date.year(), date.month());
+impl Render for YearReport {
+    const NAME: &'static str = "year";
 
-    let first_day = NaiveDate::from_ymd_opt(year, month_num, 1).unwrap();
-    let last_day = last_day_of_month(year, month_num);
-    let (month_start, month_end) = (day_bounds(first_day).0, day_bounds(last_day).1);
+    fn text(&self) -> String {
+        let mut rpt = Report::new();
 
-    // Fetch target month
-    let (target_entries, target_interrupts) = fetch_data(repo, month_start, month_end);
-    let target_agg = compute_aggregate(&target_entries, &target_interrupts);
+        rpt.blank();
+        rpt.line(format_args!("Year Report: {}", self.year));
+        if self.previous.has_data() {
+            rpt.line(format_args!("(vs {})", self.year - 1));
+        }
+        rpt.separator(52);
+        rpt.blank();
 
-    // Weekly breakdown
-    let week_stats =
-        compute_weekly_chunks(&target_entries, &target_interrupts, first_day, last_day);
+        for line in self.heatmap(self.colour) {
+            rpt.line(line);
+        }
+        rpt.blank();
 
-    // Previous months
-    let mut prev_months: Vec<((i32, u32), AggregateStats)> = Vec::new();
-    for i in 1..months_to_show {
-        let (py, pm) = prev_month(year, month_num, i);
-        let (pf, pl) = (
-            NaiveDate::from_ymd_opt(py, pm, 1).unwrap(),
-            last_day_of_month(py, pm),
-        );
-        let (ps, pe) = (day_bounds(pf).0, day_bounds(pl).1);
-        let (pe_entries, pe_interrupts) = fetch_data(repo, ps, pe);
-        let pa = compute_aggregate(&pe_entries, &pe_interrupts);
-        prev_months.push(((py, pm), pa));
-    }
+        if !self.summary.has_data() {
+            rpt.line("Yearly summary:");
+            rpt.indent("No pomodori or breaks recorded this year.");
+            rpt.blank();
+            return rpt.into_string();
+        }
 
-    // Active days
-    let total_days = (last_day - first_day).num_days() as u32 + 1;
-    let (active_count, streak) = active_day_stats(&target_entries, first_day, last_day);
+        // ── Monthly totals ────────────────────────────────────
+        rpt.line("Monthly totals:");
+        rpt.indent("Month   Done   Canc  Brk ▼   Interr.");
+        rpt.indent("─".repeat(36));
+        for m in &self.months {
+            let name = NaiveDate::from_ymd_opt(m.year, m.month, 1).unwrap();
+            rpt.indent(format_args!(
+                "{:5} {:>6}  {:>5}  {:>5}  {:>8}",
+                name.format("%b").to_string(),
+                m.stats.completed,
+                m.stats.cancelled,
+                m.stats.breaks_taken,
+                m.stats.total_interruptions,
+            ));
+        }
+        rpt.blank();
 
-    // Multi-month average completion rate
-    let multi_month_avg = if months_to_show > 1 {
-        let total_comp: usize =
-            prev_months.iter().map(|(_, a)| a.completed).sum::<usize>() + target_agg.completed;
-        let total_canc: usize =
-            prev_months.iter().map(|(_, a)| a.cancelled).sum::<usize>() + target_agg.cancelled;
-        let total = total_comp + total_canc;
-        if total > 0 {
-            (total_comp as f64 / total as f64 * 100.0) as u32
-        } else {
-            0
+        // ── Yearly summary ────────────────────────────────────
+        rpt.line("Yearly summary:");
+        let prev_rate = self
+            .previous
+            .has_data()
+            .then_some(self.previous.completion_rate);
+        write_metrics(&mut rpt, &self.summary, prev_rate, Some(&self.active_days));
+        if let Some(change) = self.change() {
+            rpt.indent(format_args!(
+                "Year over year: {} completed vs {} in {} ({})",
+                self.summary.completed,
+                self.previous.completed,
+                self.year - 1,
+                change
+            ));
         }
-    } else {
-        0
-    };
+        rpt.blank();
+
+        // ── Interruptions ─────────────────────────────────────
+        print_interruption_summary(
+            &mut rpt,
+            &self.summary,
+            Some("prev year"),
+            Some(&self.previous),
+        );
 
-    // ── Print header ──────────────────────────────────────
-    let mut rpt = Report::new();
-    rpt.blank();
-    rpt.line(format_args!("Monthly Report: {}", date.format("%B %Y")));
-    if !prev_months.is_empty() {
-        let labels: Vec<String> = prev_months
+        // ── Best week ─────────────────────────────────────────
+        if let Some(week) = &self.best_week {
+            rpt.line(format_args!(
+                "★  Best week: {} ({} completed)",
+                week.week_start.format("%b %-d"),
+                week.stats.completed
+            ));
+            rpt.blank();
+        }
+
+        rpt.into_string()
+    }
+
+    fn markdown(&self) -> String {
+        let mut rpt = Report::new();
+        rpt.line(format_args!("# Year Report: {}", self.year));
+        rpt.blank();
+        rpt.line("```");
+        for line in self.heatmap(false) {
+            rpt.line(line);
+        }
+        rpt.line("```");
+        rpt.blank();
+        let rows: Vec<Vec<String>> = self
+            .months
             .iter()
-            .rev()
-            .map(|((y, m), _)| {
-                let d = NaiveDate::from_ymd_opt(*y, *m, 1).unwrap();
-                format!("{}", d.format("%b %Y"))
+            .map(|m| {
+                vec![
+                    NaiveDate::from_ymd_opt(m.year, m.month, 1)
+                        .unwrap()
+                        .format("%b")
+                        .to_string(),
+                    m.stats.completed.to_string(),
+                    m.stats.cancelled.to_string(),
+                    m.stats.breaks_taken.to_string(),
+                    m.stats.total_interruptions.to_string(),
+                ]
             })
             .collect();
-        rpt.line(format_args!("(vs {})", labels.join(" · ")));
+        rpt.table(
+            &["Month", "Done", "Cancelled", "Breaks", "Interruptions"],
+            &rows,
+        );
+        rpt.blank();
+        rpt.line("## Summary");
+        rpt.blank();
+        markdown_metrics(&mut rpt, &self.summary, Some(&self.active_days));
+        if let Some(week) = &self.best_week {
+            rpt.line(format_args!(
+                "- **Best week:** {} ({} completed)",
+                week.week_start.format("%b %-d"),
+                week.stats.completed
+            ));
+        }
+        if let Some(change) = self.change() {
+            rpt.line(format_args!(
+                "- **Year over year:** {} completed vs {} in {} ({})",
+                self.summary.completed,
+                self.previous.completed,
+                self.year - 1,
+                change
+            ));
+        }
+        rpt.into_string()
     }
-    rpt.separator(52);
-    rpt.blank();
+}
 
-    // ── Week-by-week table ────────────────────────────────
-    rpt.line("Week-by-week breakdown:");
-    rpt.indent("Week        Done   Canc  Brk ▼  Brk ✗   Interr.");
-    rpt.indent("─".repeat(52));
-    let has_any = week_stats
-        .iter()
-        .any(|(_, a)| a.completed > 0 || a.cancelled > 0 || a.breaks_taken > 0);
-    if !has_any {
-        rpt.indent("(nothing recorded this month)");
-    } else {
-        let best_week = week_stats
-            .iter()
-            .filter(|(_, a)| a.completed > 0)
-            .max_by_key(|(_, a)| a.completed);
-        let worst_week = week_stats
-            .iter()
-            .filter(|(_, a)| a.completed > 0 || a.cancelled > 0)
-            .min_by_key(|(_, a)| (a.completed as i64) - (a.cancelled as i64));
+/// Print a yearly report with a calendar heatmap of finished pomodori per day
+/// for the given year (defaults to the current one), monthly totals, the best
+/// week and a comparison to the year before.
+pub fn print_year_report(repo: &Repository, year: Option<i32>, colour: bool, format: Format) {
+    let year = year.unwrap_or_else(|| timezone::clock().today().year());
+    print(
+        // Only the terminal gets ANSI colours, not the text inside other formats
+        year_report(repo, year).map(|report| YearReport {
+            colour: colour && format == Format::Text,
+            ..report
+        }),
+        format,
+    );
+}
 
-        for (week_start, agg) in &week_stats {
-            if agg.completed == 0 && agg.cancelled == 0 && agg.breaks_taken == 0 {
-                continue;
-            }
-            let star = if best_week.map(|(d, _)| *d) == Some(*week_start) {
-                " ★"
-            } else if worst_week.map(|(d, _)| *d) == Some(*week_start) {
-                " ⊗"
-            } else {
-                "  "
-            };
-            let label = format!(
-                "{} {}",
-                week_start.format("%b %-d"),
-                (*week_start + Duration::days(6)).format("– %-d")
-            );
+// ── Rolling window report ─────────────────────────────────────
+
+/// The last N days, compared to the N days before.
+#[derive(Debug, Serialize)]
+pub struct LastReport {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub days: Vec<DayStats>,
+    pub summary: AggregateStats,
+    pub previous_start: NaiveDate,
+    pub previous_end: NaiveDate,
+    pub previous: AggregateStats,
+    pub active_days: ActiveDays,
+    pub best_day: Option<NaiveDate>,
+    pub worst_day: Option<NaiveDate>,
+    pub hints: Vec<Hint>,
+    /// The entries with their annotations, for the HTML report
+    #[serde(skip)]
+    pub entries: Vec<DayEntry>,
+}
+
+/// Compute the report for the `days` days ending on `end_date`.
+pub fn last_report(
+    repo: &Repository,
+    end_date: NaiveDate,
+    days: u32,
+) -> Result<LastReport, PersistenceError> {
+    let start_date = end_date - Duration::days(days as i64 - 1);
+
+    // Current window
+    let (cur_start, cur_end) = (day_bounds(start_date).0, day_bounds(end_date).1);
+    let (cur_entries, cur_interrupts) = fetch_data(repo, cur_start, cur_end)?;
+
+    // Previous window
+    let prev_window_end = start_date - Duration::days(1);
+    let prev_window_start = prev_window_end - Duration::days(days as i64 - 1);
+    let (pr_start, pr_end) = (
+        day_bounds(prev_window_start).0,
+        day_bounds(prev_window_end).1,
+    );
+    let (pr_entries, pr_interrupts) = fetch_data(repo, pr_start, pr_end)?;
+
+    let day_stats = compute_day_stats(&cur_entries, &cur_interrupts, start_date, end_date);
+    let summary = compute_aggregate(&cur_entries, &cur_interrupts);
+    let previous = compute_aggregate(&pr_entries, &pr_interrupts);
+    let active_days = active_day_stats(&cur_entries, start_date, end_date);
+    let (best_day, worst_day) = best_and_worst_day(&day_stats);
+    let hints = check_pattern_hints(&day_stats, &summary, &previous);
+
+    Ok(LastReport {
+        start: start_date,
+        end: end_date,
+        days: day_stats,
+        summary,
+        previous_start: prev_window_start,
+        previous_end: prev_window_end,
+        previous,
+        active_days,
+        best_day,
+        worst_day,
+        hints,
+        entries: Vec::new(),
+    })
+}
+
+impl LastReport {
+    fn title(&self) -> String {
+        if self.days.len() == 1 {
+            format!("Last 1 day – {}", self.end.format("%b %d, %Y"))
+        } else {
+            format!(
+                "Last {} days – {} – {}",
+                self.days.len(),
+                self.start.format("%b %d"),
+                self.end.format("%b %d, %Y")
+            )
+        }
+    }
+}
+
+impl Render for LastReport {
+    const NAME: &'static str = "last";
+
+    fn text(&self) -> String {
+        let mut rpt = Report::new();
+        let total_days = self.days.len();
+        let (cur_agg, pr_agg) = (&self.summary, &self.previous);
+
+        // ── Print header ──────────────────────────────────────
+        rpt.blank();
+        rpt.line(self.title());
+        let has_prev = pr_agg.has_data();
+        if has_prev {
             rpt.line(format_args!(
-                "  {:>12}{} {:>4}  {:>4}  {:>4}  {:>4}  {:>7}",
-                label,
-                star,
-                agg.completed,
-                agg.cancelled,
-                agg.breaks_taken,
-                agg.breaks_cancelled,
-                agg.total_interruptions,
+                "(prev {} days: {} – {})",
+                total_days,
+                self.previous_start.format("%b %d"),
+                self.previous_end.format("%b %d")
+            ));
+        }
+        rpt.separator(52);
+        rpt.blank();
+
+        if !cur_agg.has_data() {
+            rpt.indent("Nothing recorded in this period.");
+            rpt.blank();
+            return rpt.into_string();
+        }
+
+        // ── Summary ───────────────────────────────────────────
+        rpt.line("Summary:");
+        let prev_rate = has_prev.then_some(pr_agg.completion_rate);
+        let active_days =
+            (self.active_days.active > 0 && total_days > 1).then_some(&self.active_days);
+        write_metrics(&mut rpt, cur_agg, prev_rate, active_days);
+        rpt.blank();
+
+        // ── Interruptions ─────────────────────────────────────
+        let prev_agg = has_prev.then_some(pr_agg);
+        print_interruption_summary(&mut rpt, cur_agg, Some("prev"), prev_agg);
+
+        // ── Day-by-day table ──────────────────────────────────
+        if total_days > 1 {
+            write_day_table(&mut rpt, &self.days, self.best_day, self.worst_day);
+        }
+
+        // ── Hints ─────────────────────────────────────────────
+        print_hints(&mut rpt, &self.hints);
+
+        rpt.into_string()
+    }
+
+    fn markdown(&self) -> String {
+        let mut rpt = Report::new();
+        rpt.line(format_args!("# {}", self.title()));
+        rpt.blank();
+        rpt.line("## Summary");
+        rpt.blank();
+        markdown_metrics(&mut rpt, &self.summary, Some(&self.active_days));
+        if self.previous.has_data() {
+            rpt.line(format_args!(
+                "- **Previous {} days:** {} completed · {} cancelled · {}% completion rate",
+                self.days.len(),
+                self.previous.completed,
+                self.previous.cancelled,
+                self.previous.completion_rate
+            ));
+        }
+        let active: Vec<&DayStats> = self.days.iter().filter(|d| d.has_data()).collect();
+        if !active.is_empty() {
+            rpt.blank();
+            rpt.line("## Day by day");
+            rpt.blank();
+            rpt.table(&markdown_headers("Day"), &day_rows(active, "%Y-%m-%d"));
+        }
+        markdown_hints(&mut rpt, &self.hints);
+        rpt.into_string()
+    }
+
+    fn html(&self) -> String {
+        let title = self.title();
+        let mut html = Html::new(&title);
+        html.element("h1", &title);
+
+        html.element("h2", "Summary");
+        if !self.summary.has_data() {
+            html.muted("Nothing recorded in this period.");
+            return html.into_string();
+        }
+        let mut items = metric_items(&self.summary, Some(&self.active_days));
+        if self.previous.has_data() {
+            items.push((
+                "Previous period",
+                format!(
+                    "{} – {}: {} completed · {} cancelled · {}% completion rate",
+                    self.previous_start.format("%b %d"),
+                    self.previous_end.format("%b %d"),
+                    self.previous.completed,
+                    self.previous.cancelled,
+                    self.previous.completion_rate
+                ),
             ));
         }
+        html.list(&items);
+        html_interruptions(&mut html, &self.summary);
+
+        if self.days.len() > 1 {
+            html.element("h2", "Day by day");
+            html.chart(&daily_chart("Pomodori per day".to_string(), &self.days));
+            let active: Vec<&DayStats> = self.days.iter().filter(|d| d.has_data()).collect();
+            html.table(&markdown_headers("Day"), &day_rows(active, "%Y-%m-%d"));
+        }
+
+        html_entries(&mut html, &self.entries, true);
+        html_hints(&mut html, &self.hints);
+        html.into_string()
+    }
+}
+
+/// Print a rolling-window productivity report covering the last N days ending on
+/// the given date (defaults to today), with day-by-day breakdown, comparison to
+/// the previous window, and actionable hints.
+pub fn print_last_report(repo: &Repository, date: Option<String>, days: u32, format: Format) {
+    let report = last_report(repo, parse_date_or_today(date), days).and_then(|report| {
+        Ok(LastReport {
+            entries: collect_entries(repo, report.start, report.end, format == Format::Html)?,
+            ..report
+        })
+    });
+    print(report, format);
+}
+
+// ── Date range report ────────────────────────────────────────
+
+/// Parse a period given as `FROM..TO`, `YYYY-MM` (the whole month) or
+/// `YYYY-MM-DD` (a single day).
+fn parse_range(range: &str) -> (NaiveDate, NaiveDate) {
+    let date = |d: &str| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok();
+    let parsed = match range.split_once("..") {
+        Some((from, to)) => date(from).zip(date(to)),
+        None => date(range).map(|d| (d, d)).or_else(|| {
+            date(&format!("{}-01", range)).map(|d| (d, last_day_of_month(d.year(), d.month())))
+        }),
+    };
+    let Some((from, to)) = parsed else {
+        eprintln!(
+            "Error: invalid period '{}'. Expected YYYY-MM-DD..YYYY-MM-DD, YYYY-MM or YYYY-MM-DD.",
+            range
+        );
+        std::process::exit(1);
+    };
+    check_range(from, to);
+    (from, to)
+}
+
+fn check_range(from: NaiveDate, to: NaiveDate) {
+    if to < from {
+        eprintln!(
+            "Error: the period ends on {} before it starts on {}.",
+            to, from
+        );
+        std::process::exit(1);
+    }
+}
+
+fn format_range(start: NaiveDate, end: NaiveDate) -> String {
+    if start == end {
+        end.format("%b %d, %Y").to_string()
+    } else if start.year() == end.year() {
+        format!("{} – {}", start.format("%b %d"), end.format("%b %d, %Y"))
+    } else {
+        format!(
+            "{} – {}",
+            start.format("%b %d, %Y"),
+            end.format("%b %d, %Y")
+        )
+    }
+}
+
+/// Any range of days, e.g. a sprint.
+#[derive(Debug, Serialize)]
+pub struct RangeReport {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub days: Vec<DayStats>,
+    pub summary: AggregateStats,
+    pub active_days: ActiveDays,
+    pub best_day: Option<NaiveDate>,
+    pub worst_day: Option<NaiveDate>,
+    pub hints: Vec<Hint>,
+}
+
+/// Compute the report for the days from `start` to `end`, both included.
+pub fn range_report(
+    repo: &Repository,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<RangeReport, PersistenceError> {
+    let (entries, interrupts) = fetch_data(repo, day_bounds(start).0, day_bounds(end).1)?;
+
+    let days = compute_day_stats(&entries, &interrupts, start, end);
+    let summary = compute_aggregate(&entries, &interrupts);
+    let (best_day, worst_day) = best_and_worst_day(&days);
+    // Without a period to compare to, only the hints about this one apply
+    let hints = check_pattern_hints(&days, &summary, &AggregateStats::default());
+
+    Ok(RangeReport {
+        active_days: active_day_stats(&entries, start, end),
+        start,
+        end,
+        days,
+        summary,
+        best_day,
+        worst_day,
+        hints,
+    })
+}
+
+impl RangeReport {
+    fn title(&self) -> String {
+        let days = self.days.len();
+        format!(
+            "{} ({} {})",
+            format_range(self.start, self.end),
+            days,
+            if days == 1 { "day" } else { "days" }
+        )
+    }
+}
+
+impl Render for RangeReport {
+    const NAME: &'static str = "range";
+
+    fn text(&self) -> String {
+        let mut rpt = Report::new();
+
+        rpt.blank();
+        rpt.line(self.title());
+        rpt.separator(52);
+        rpt.blank();
+
+        if !self.summary.has_data() {
+            rpt.indent("Nothing recorded in this period.");
+            rpt.blank();
+            return rpt.into_string();
+        }
+
+        rpt.line("Summary:");
+        let active_days = (self.days.len() > 1).then_some(&self.active_days);
+        write_metrics(&mut rpt, &self.summary, None, active_days);
+        rpt.blank();
+
+        print_interruption_summary(&mut rpt, &self.summary, None, None);
+
+        if self.days.len() > 1 {
+            write_day_table(&mut rpt, &self.days, self.best_day, self.worst_day);
+        }
+
+        print_hints(&mut rpt, &self.hints);
+
+        rpt.into_string()
     }
-    rpt.blank();
 
-    // ── Monthly summary ───────────────────────────────────
-    rpt.line("Monthly summary:");
-    if target_agg.completed == 0 && target_agg.cancelled == 0 && target_agg.breaks_taken == 0 {
-        rpt.indent("No pomodori or breaks recorded this month.");
+    fn markdown(&self) -> String {
+        let mut rpt = Report::new();
+        rpt.line(format_args!("# {}", self.title()));
         rpt.blank();
-        print!("{}", rpt.into_string());
-        return;
+        rpt.line("## Summary");
+        rpt.blank();
+        markdown_metrics(&mut rpt, &self.summary, Some(&self.active_days));
+        let active: Vec<&DayStats> = self.days.iter().filter(|d| d.has_data()).collect();
+        if !active.is_empty() {
+            rpt.blank();
+            rpt.line("## Day by day");
+            rpt.blank();
+            rpt.table(&markdown_headers("Day"), &day_rows(active, "%Y-%m-%d"));
+        }
+        markdown_hints(&mut rpt, &self.hints);
+        rpt.into_string()
     }
+}
 
-    let prev_rate = prev_months
-        .last()
-        .map(|((_, _), prev)| prev.completion_rate);
-    write_metrics(
-        &mut rpt,
-        &target_agg,
-        prev_rate,
-        Some((active_count, total_days, streak)),
-    );
+/// Print a productivity report for the days from `from` to `to` (defaults to
+/// today), both included, with day-by-day breakdown and hints.
+pub fn print_range_report(repo: &Repository, from: String, to: Option<String>, format: Format) {
+    let (from, to) = (parse_date_or_today(Some(from)), parse_date_or_today(to));
+    check_range(from, to);
+    print(range_report(repo, from, to), format);
+}
 
-    if multi_month_avg > 0 && months_to_show > 1 {
-        rpt.indent(format_args!(
-            "{}-month avg completion rate: {}%",
-            months_to_show.min(12),
-            multi_month_avg
-        ));
-    }
-    rpt.blank();
+// ── Period comparison ────────────────────────────────────────
 
-    // ── Interruptions ─────────────────────────────────────
-    let prev_agg = prev_months.last().map(|((_, _), a)| a);
-    print_interruption_summary(&mut rpt, &target_agg, Some("prev month"), prev_agg);
+/// One of the periods in a [`CompareReport`].
+#[derive(Debug, Serialize)]
+pub struct Period {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub summary: AggregateStats,
+    pub active_days: ActiveDays,
+}
 
-    // ── Best / worst week ─────────────────────────────────
-    let best_week = week_stats
-        .iter()
-        .filter(|(_, a)| a.completed > 0)
-        .max_by_key(|(_, a)| a.completed);
-    let worst_week = week_stats
-        .iter()
-        .filter(|(_, a)| a.completed > 0 || a.cancelled > 0)
-        .min_by_key(|(_, a)| (a.completed as i64) - (a.cancelled as i64));
+impl Period {
+    /// Completed pomodori per day, which makes periods of different lengths comparable.
+    fn completed_per_day(&self) -> f64 {
+        self.summary.completed as f64 / self.active_days.total.max(1) as f64
+    }
+}
 
-    if let Some((ws, agg)) = best_week
-        && agg.completed > 0
-    {
-        rpt.line(format_args!(
-            "★  Best week: {} ({} completed)",
-            ws.format("%b %-d"),
-            agg.completed
-        ));
+/// How a metric changed from the first period to the second one.
+#[derive(Debug, Serialize)]
+pub struct Change {
+    pub metric: &'static str,
+    pub a: f64,
+    pub b: f64,
+    pub delta: f64,
+    /// The delta relative to the first period; `None` if that was 0
+    pub percent: Option<f64>,
+    #[serde(skip)]
+    label: &'static str,
+    #[serde(skip)]
+    decimals: usize,
+}
+
+impl Change {
+    fn new(metric: &'static str, label: &'static str, decimals: usize, a: f64, b: f64) -> Self {
+        Self {
+            metric,
+            a,
+            b,
+            delta: b - a,
+            percent: (a != 0.0).then(|| (b - a) / a * 100.0),
+            label,
+            decimals,
+        }
     }
-    if let Some((ws, agg)) = worst_week
-        && (agg.completed > 0 || agg.cancelled > 0)
-    {
-        rpt.line(format_args!(
-            "⊗  Worst week: {} ({} completed, {} cancelled)",
-            ws.format("%b %-d"),
-            agg.completed,
-            agg.cancelled
-        ));
+
+    fn value(&self, value: f64) -> String {
+        format!("{:.*}", self.decimals, value)
     }
-    rpt.blank();
 
-    // ── Hints ─────────────────────────────────────────────
-    let hints = check_monthly_hints(
-        &target_agg,
-        &prev_months,
-        active_count,
-        total_days,
-        streak,
-        &week_stats,
-    );
-    if !hints.is_empty() {
-        rpt.line("Insights:");
-        for hint in &hints {
-            rpt.indent(hint);
-        }
-        rpt.blank();
+    fn delta(&self) -> String {
+        format!("{:+.*}", self.decimals, self.delta)
     }
 
-    print!("{}", rpt.into_string());
+    fn percent(&self) -> String {
+        self.percent
+            .map(|p| format!("{:+.0}%", p))
+            .unwrap_or_else(|| "–".to_string())
+    }
 }
 
-// ── Rolling window report ─────────────────────────────────────
-
-/// Print a rolling-window productivity report covering the last N days ending on
-/// the given date (defaults to today), with day-by-day breakdown, comparison to
-/// the previous window, and actionable hints.
-pub fn print_last_report(repo: &Repository, date: Option<String>, days: u32) {
-    let mut rpt = Report::new();
+/// The aggregate metrics of two periods side by side, e.g. two sprints.
+#[derive(Debug, Serialize)]
+pub struct CompareReport {
+    pub a: Period,
+    pub b: Period,
+    pub changes: Vec<Change>,
+}
 
-    let end_date = parse_date_or_today(date);
-    let start_date = end_date - Duration::days(days as i64 - 1);
+/// Compare the metrics of two periods, each from its start to its end date.
+pub fn compare_report(
+    repo: &Repository,
+    a: (NaiveDate, NaiveDate),
+    b: (NaiveDate, NaiveDate),
+) -> Result<CompareReport, PersistenceError> {
+    let period = |(start, end): (NaiveDate, NaiveDate)| -> Result<Period, PersistenceError> {
+        let (entries, interrupts) = fetch_data(repo, day_bounds(start).0, day_bounds(end).1)?;
+        Ok(Period {
+            start,
+            end,
+            summary: compute_aggregate(&entries, &interrupts),
+            active_days: active_day_stats(&entries, start, end),
+        })
+    };
+    let (a, b) = (period(a)?, period(b)?);
+
+    // The name in JSON, the label in text, the decimals shown and the value
+    type Metric = (&'static str, &'static str, usize, fn(&Period) -> f64);
+    let metrics: [Metric; 13] = [
+        ("completed", "Completed", 0, |p| p.summary.completed as f64),
+        ("cancelled", "Cancelled", 0, |p| p.summary.cancelled as f64),
+        ("completion_rate", "Completion rate %", 0, |p| {
+            p.summary.completion_rate as f64
+        }),
+        (
+            "completed_per_day",
+            "Completed per day",
+            1,
+            Period::completed_per_day,
+        ),
+        ("active_days", "Active days", 0, |p| {
+            p.active_days.active as f64
+        }),
+        ("best_streak", "Best streak", 0, |p| {
+            p.active_days.best_streak as f64
+        }),
+        ("breaks_taken", "Breaks taken", 0, |p| {
+            p.summary.breaks_taken as f64
+        }),
+        ("breaks_cancelled", "Breaks cancelled", 0, |p| {
+            p.summary.breaks_cancelled as f64
+        }),
+        ("break_ratio", "Breaks per pomodoro", 1, |p| {
+            p.summary.break_ratio
+        }),
+        ("total_interruptions", "Interruptions", 0, |p| {
+            p.summary.total_interruptions as f64
+        }),
+        ("internal_count", "Internal", 0, |p| {
+            p.summary.internal_count as f64
+        }),
+        ("external_count", "External", 0, |p| {
+            p.summary.external_count as f64
+        }),
+        ("max_focus_block", "Focus block", 0, |p| {
+            p.summary.max_focus_block as f64
+        }),
+    ];
+    let changes = metrics
+        .into_iter()
+        .map(|(metric, label, decimals, value)| {
+            Change::new(metric, label, decimals, value(&a), value(&b))
+        })
+        .collect();
 
-    // Current window
-    let (cur_start, cur_end) = (day_bounds(start_date).0, day_bounds(end_date).1);
-    let (cur_entries, cur_interrupts) = fetch_data(repo, cur_start, cur_end);
+    Ok(CompareReport { a, b, changes })
+}
 
-    // Previous window
-    let prev_window_end = start_date - Duration::days(1);
-    let prev_window_start = prev_window_end - Duration::days(days as i64 - 1);
-    let (pr_start, pr_end) = (
-        day_bounds(prev_window_start).0,
-        day_bounds(prev_window_end).1,
-    );
-    let (pr_entries, pr_interrupts) = fetch_data(repo, pr_start, pr_end);
+impl CompareReport {
+    fn period_line(name: &str, p: &Period) -> String {
+        let days = p.active_days.total;
+        format!(
+            "{}: {} ({} {})",
+            name,
+            format_range(p.start, p.end),
+            days,
+            if days == 1 { "day" } else { "days" }
+        )
+    }
+}
 
-    // Day-by-day breakdown
-    let day_stats = compute_day_stats(&cur_entries, &cur_interrupts, start_date, end_date);
+impl Render for CompareReport {
+    const NAME: &'static str = "compare";
 
-    // Aggregates
-    let cur_agg = compute_aggregate(&cur_entries, &cur_interrupts);
-    let pr_agg = compute_aggregate(&pr_entries, &pr_interrupts);
+    fn text(&self) -> String {
+        let mut rpt = Report::new();
 
-    // Active days
-    let total_days = days;
-    let (active_count, streak) = active_day_stats(&cur_entries, start_date, end_date);
+        rpt.blank();
+        rpt.line("Comparison");
+        rpt.line(Self::period_line("A", &self.a));
+        rpt.line(Self::period_line("B", &self.b));
+        rpt.separator(62);
+        rpt.blank();
 
-    // ── Print header ──────────────────────────────────────
-    rpt.blank();
-    if days == 1 {
-        rpt.line(format_args!(
-            "Last 1 day – {}",
-            end_date.format("%b %d, %Y")
-        ));
-    } else {
-        rpt.line(format_args!(
-            "Last {} days – {} – {}",
-            days,
-            start_date.format("%b %d"),
-            end_date.format("%b %d, %Y")
-        ));
-    }
-    let has_prev = pr_agg.completed > 0 || pr_agg.cancelled > 0 || pr_agg.breaks_taken > 0;
-    if has_prev {
         rpt.line(format_args!(
-            "(prev {} days: {} – {})",
-            days,
-            prev_window_start.format("%b %d"),
-            prev_window_end.format("%b %d")
+            "  {:<20} {:>8} {:>8} {:>10} {:>8}",
+            "Metric", "A", "B", "Change", "%"
         ));
+        rpt.indent("─".repeat(58));
+        for c in &self.changes {
+            rpt.indent(format_args!(
+                "{:<20} {:>8} {:>8} {:>10} {:>8}",
+                c.label,
+                c.value(c.a),
+                c.value(c.b),
+                c.delta(),
+                c.percent()
+            ));
+        }
+        rpt.blank();
+
+        rpt.into_string()
     }
-    rpt.separator(52);
-    rpt.blank();
 
-    let is_empty = cur_agg.completed == 0 && cur_agg.cancelled == 0 && cur_agg.breaks_taken == 0;
-    if is_empty {
-        rpt.indent("Nothing recorded in this period.");
+    fn markdown(&self) -> String {
+        let mut rpt = Report::new();
+        rpt.line("# Comparison");
         rpt.blank();
-        print!("{}", rpt.into_string());
-        return;
+        rpt.line(format_args!("- **{}**", Self::period_line("A", &self.a)));
+        rpt.line(format_args!("- **{}**", Self::period_line("B", &self.b)));
+        rpt.blank();
+        let rows: Vec<Vec<String>> = self
+            .changes
+            .iter()
+            .map(|c| {
+                vec![
+                    c.label.to_string(),
+                    c.value(c.a),
+                    c.value(c.b),
+                    c.delta(),
+                    c.percent(),
+                ]
+            })
+            .collect();
+        rpt.table(&["Metric", "A", "B", "Change", "%"], &rows);
+        rpt.into_string()
     }
+}
 
-    // ── Summary ───────────────────────────────────────────
-    rpt.line("Summary:");
-    let prev_rate = if has_prev {
-        Some(pr_agg.completion_rate)
-    } else {
-        None
-    };
-    let active_days_opt = if active_count > 0 && total_days > 1 {
-        Some((active_count, total_days, streak))
-    } else {
-        None
-    };
-    write_metrics(&mut rpt, &cur_agg, prev_rate, active_days_opt);
-    rpt.blank();
-
-    // ── Interruptions ─────────────────────────────────────
-    let prev_agg = if has_prev { Some(&pr_agg) } else { None };
-    print_interruption_summary(&mut rpt, &cur_agg, Some("prev"), prev_agg);
-
-    // ── Day-by-day table ──────────────────────────────────
-    if total_days > 1 {
-        rpt.line("Day-by-day:");
-        rpt.line("  Day       Done   Canc  Brk ▼  Brk ✗   Interr.");
-        rpt.separator(50);
-        let has_any = day_stats
-            .iter()
-            .any(|d| d.pomodori_completed > 0 || d.pomodori_cancelled > 0 || d.breaks_taken > 0);
-        if !has_any {
-            rpt.indent("(nothing recorded)");
-        } else {
-            let best_day = day_stats
-                .iter()
-                .filter(|d| d.pomodori_completed > 0)
-                .max_by_key(|d| d.pomodori_completed);
-            let worst_day = day_stats
-                .iter()
-                .filter(|d| d.pomodori_completed > 0 || d.pomodori_cancelled > 0)
-                .min_by_key(|d| (d.pomodori_completed as i64) - (d.pomodori_cancelled as i64));
+/// Print the metrics of two periods side by side. Each period is given as
+/// `FROM..TO`, `YYYY-MM` or `YYYY-MM-DD`.
+pub fn print_compare_report(repo: &Repository, a: &str, b: &str, format: Format) {
+    print(compare_report(repo, parse_range(a), parse_range(b)), format);
+}
 
-            for ds in &day_stats {
-                if ds.pomodori_completed == 0 && ds.pomodori_cancelled == 0 && ds.breaks_taken == 0
-                {
-                    continue;
-                }
-                let star = if best_day.map(|d| d.date) == Some(ds.date) {
-                    " ★"
-                } else if worst_day.map(|d| d.date) == Some(ds.date) {
-                    " ⊗"
-                } else {
-                    "  "
-                };
-                rpt.line(format_args!(
-                    "  {:6}{} {:>4}  {:>4}  {:>4}  {:>4}  {:>7}",
-                    ds.date.format("%b %-d"),
-                    star,
-                    ds.pomodori_completed,
-                    ds.pomodori_cancelled,
-                    ds.breaks_taken,
-                    ds.breaks_cancelled,
-                    ds.interruptions,
-                ));
-            }
+// ── Charts ───────────────────────────────────────────────────
+
+/// What `report chart` draws.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChartKind {
+    /// Completed and cancelled pomodori per day
+    Daily,
+    /// Completed and cancelled pomodori by the hour they were started in
+    Hourly,
+    /// Internal and external interruptions by hour of day
+    Interruptions,
+    /// Completed and cancelled pomodori per week, as a trend
+    Weekly,
+}
+
+impl ChartKind {
+    /// How many days the chart covers unless asked otherwise.
+    pub fn default_days(self) -> u32 {
+        match self {
+            ChartKind::Daily => 14,
+            ChartKind::Hourly | ChartKind::Interruptions => 28,
+            ChartKind::Weekly => 84,
         }
-        rpt.blank();
     }
+}
 
-    // ── Hints ─────────────────────────────────────────────
-    let hints = check_pattern_hints(&day_stats, &cur_agg, &pr_agg);
-    if !hints.is_empty() {
-        rpt.line("Insights:");
-        for hint in &hints {
-            rpt.indent(hint);
-        }
-        rpt.blank();
+/// The hours from the first to the last one in `hours`, with gaps filled in,
+/// so that the bars of a chart stay in place.
+fn hour_labels(hours: impl Iterator<Item = u32> + Clone) -> Vec<u32> {
+    match (hours.clone().min(), hours.max()) {
+        (Some(first), Some(last)) => (first..=last).collect(),
+        _ => Vec::new(),
     }
+}
 
-    print!("{}", rpt.into_string());
+fn completed_and_cancelled(completed: Vec<f64>, cancelled: Vec<f64>) -> Vec<Series> {
+    vec![
+        Series {
+            name: "Completed",
+            colour: chart::GREEN,
+            values: completed,
+        },
+        Series {
+            name: "Cancelled",
+            colour: chart::RED,
+            values: cancelled,
+        },
+    ]
 }
 
-/// Print a single-day report.
-pub fn print_day_report(repo: &Repository, date: Option<String>) {
-    use crate::{Annotation, Status, format_time};
-    use std::collections::HashMap;
+/// Completed and cancelled pomodori per day, side by side.
+fn daily_chart(title: String, days: &[DayStats]) -> Chart {
+    Chart {
+        title,
+        style: Style::Bars,
+        labels: days
+            .iter()
+            .map(|d| d.date.format("%b %d").to_string())
+            .collect(),
+        series: completed_and_cancelled(
+            days.iter().map(|d| d.pomodori_completed as f64).collect(),
+            days.iter().map(|d| d.pomodori_cancelled as f64).collect(),
+        ),
+    }
+}
+
+/// Completed and cancelled pomodori per week.
+fn weekly_chart(title: String, weeks: &[WeekChunk], style: Style) -> Chart {
+    Chart {
+        title,
+        style,
+        labels: weeks
+            .iter()
+            .map(|w| w.week_start.format("%b %d").to_string())
+            .collect(),
+        series: completed_and_cancelled(
+            weeks.iter().map(|w| w.stats.completed as f64).collect(),
+            weeks.iter().map(|w| w.stats.cancelled as f64).collect(),
+        ),
+    }
+}
 
-    let mut rpt = Report::new();
+/// Compute the chart of the `days` days ending on `date`.
+pub fn chart(
+    repo: &Repository,
+    kind: ChartKind,
+    date: NaiveDate,
+    days: u32,
+) -> Result<Chart, PersistenceError> {
+    let start_date = date - Duration::days(days as i64 - 1);
+    let period = format_range(start_date, date);
+
+    Ok(match kind {
+        ChartKind::Daily => {
+            let (entries, interrupts) =
+                fetch_data(repo, day_bounds(start_date).0, day_bounds(date).1)?;
+            daily_chart(
+                format!("Pomodori per day – {}", period),
+                &compute_day_stats(&entries, &interrupts, start_date, date),
+            )
+        }
+        ChartKind::Hourly => {
+            let report = hours_report(repo, date, days)?;
+            let hours = hour_labels(report.by_hour.iter().map(|b| b.key.hour));
+            let count = |hour: u32, f: fn(&OutputBucket<Hour>) -> usize| {
+                report
+                    .by_hour
+                    .iter()
+                    .find(|b| b.key.hour == hour)
+                    .map_or(0.0, |b| f(b) as f64)
+            };
+            Chart {
+                title: format!("Pomodori by hour of day – {}", period),
+                style: Style::Bars,
+                labels: hours.iter().map(|h| format!("{:02}", h)).collect(),
+                series: completed_and_cancelled(
+                    hours.iter().map(|h| count(*h, |b| b.completed)).collect(),
+                    hours.iter().map(|h| count(*h, |b| b.cancelled)).collect(),
+                ),
+            }
+        }
+        ChartKind::Interruptions => {
+            let report = interruptions_report(repo, date, days)?;
+            let hours = hour_labels(report.by_hour.iter().map(|b| b.key.hour));
+            let count = |hour: u32, f: fn(&InterruptionBucket<Hour>) -> usize| {
+                report
+                    .by_hour
+                    .iter()
+                    .find(|b| b.key.hour == hour)
+                    .map_or(0.0, |b| f(b) as f64)
+            };
+            Chart {
+                title: format!("Interruptions by hour of day – {}", period),
+                style: Style::StackedBars,
+                labels: hours.iter().map(|h| format!("{:02}", h)).collect(),
+                series: vec![
+                    Series {
+                        name: "Internal",
+                        colour: chart::ORANGE,
+                        values: hours.iter().map(|h| count(*h, |b| b.internal)).collect(),
+                    },
+                    Series {
+                        name: "External",
+                        colour: chart::BLUE,
+                        values: hours.iter().map(|h| count(*h, |b| b.external)).collect(),
+                    },
+                ],
+            }
+        }
+        ChartKind::Weekly => {
+            let (entries, interrupts) =
+                fetch_data(repo, day_bounds(start_date).0, day_bounds(date).1)?;
+            weekly_chart(
+                format!("Pomodori per week – {}", period),
+                &compute_weekly_chunks(&entries, &interrupts, start_date, date),
+                Style::Lines,
+            )
+        }
+    })
+}
 
+/// Write the chart of `kind` as SVG to `out`, or to stdout without one.
+pub fn print_chart(
+    repo: &Repository,
+    kind: ChartKind,
+    date: Option<String>,
+    days: Option<u32>,
+    out: Option<&Path>,
+) {
     let date = parse_date_or_today(date);
-    let (start_of_day, end_of_day) = day_bounds(date);
+    let svg = match chart(repo, kind, date, days.unwrap_or(kind.default_days())) {
+        Ok(chart) => chart.svg(),
+        Err(e) => {
+            eprintln!("Error: {}.", e);
+            std::process::exit(e.exit_code());
+        }
+    };
+    match out {
+        Some(path) => {
+            if let Err(e) = std::fs::write(path, svg) {
+                eprintln!("Error: cannot write {}: {}.", path.display(), e);
+                std::process::exit(crate::exit_code::IO);
+            }
+        }
+        None => print!("{}", svg),
+    }
+}
 
-    let entries = repo
-        .entries_between(start_of_day, end_of_day)
-        .unwrap_or_else(|e| {
-            eprintln!("Error: {}", e);
-            std::process::exit(1);
-        });
+// ── Daily report ──────────────────────────────────────────────
+
+/// A pomodoro or break in the [`DayReport`].
+#[derive(Debug, Serialize)]
+pub struct DayEntry {
+    pub uuid: String,
+    #[serde(serialize_with = "display")]
+    pub kind: Kind,
+    #[serde(serialize_with = "status")]
+    pub status: Status,
+    pub planned_duration: i64,
+    #[serde(serialize_with = "timestamp")]
+    pub started_at: i64,
+    /// When the entry was finished or cancelled; 0 while it is running
+    #[serde(serialize_with = "timestamp")]
+    pub ended_at: i64,
+    pub interruptions: i64,
+    pub annotations: Vec<String>,
+}
 
-    let interrupt_logs = repo
-        .interrupts_between(start_of_day, end_of_day)
-        .unwrap_or_else(|e| {
-            eprintln!("Error: {}", e);
-            std::process::exit(1);
-        });
+/// Every pomodoro and break of a day.
+#[derive(Debug, Serialize)]
+pub struct DayReport {
+    pub date: NaiveDate,
+    pub entries: Vec<DayEntry>,
+    pub summary: AggregateStats,
+    /// The day drawn on an hour scale, if asked for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeline: Option<Timeline>,
+}
 
-    let annotations = repo
-        .annotations_between(start_of_day, end_of_day)
-        .unwrap_or_else(|e| {
-            eprintln!("Error: {}", e);
-            std::process::exit(1);
-        });
+/// Compute the report for a single day.
+pub fn day_report(repo: &Repository, date: NaiveDate) -> Result<DayReport, PersistenceError> {
+    let (start_of_day, end_of_day) = day_bounds(date);
 
-    // ── Group annotations by schedulable UUID ───────────────
-    let mut ann_by_uuid: HashMap<String, Vec<&Annotation>> = HashMap::new();
-    for a in &annotations {
-        ann_by_uuid
-            .entry(a.schedulable_uuid.to_string())
-            .or_default()
-            .push(a);
-    }
+    let entries = repo.entries_between(start_of_day, end_of_day)?;
+    let interrupt_logs = repo.interrupts_between(start_of_day, end_of_day)?;
+    let annotations = repo.annotations_between(start_of_day, end_of_day)?;
 
-    // ── Header ──────────────────────────────────────────────
-    let day_name = date.format("%A");
-    rpt.line(format_args!("Report for {} ({})", date, day_name));
-    rpt.separator(35);
-    rpt.blank();
+    let summary = compute_aggregate(&entries, &interrupt_logs);
 
-    if entries.is_empty() {
-        rpt.line("Nothing recorded for this day.");
-        print!("{}", rpt.into_string());
-        return;
+    Ok(DayReport {
+        date,
+        entries: day_entries(entries, &annotations),
+        summary,
+        timeline: None,
+    })
+}
+
+/// The entries with their annotations.
+fn day_entries(entries: Vec<Schedulable>, annotations: &[Annotation]) -> Vec<DayEntry> {
+    entries
+        .into_iter()
+        .map(|entry| DayEntry {
+            annotations: annotations
+                .iter()
+                .filter(|a| a.schedulable_uuid.to_string() == entry.uuid.to_string())
+                .map(|a| a.body.clone())
+                .collect(),
+            uuid: entry.uuid.to_string(),
+            kind: entry.kind,
+            status: entry.status(),
+            planned_duration: entry.duration,
+            started_at: entry.started_at,
+            ended_at: if entry.finished_at != 0 {
+                entry.finished_at
+            } else {
+                entry.cancelled_at
+            },
+            interruptions: entry.interruptions,
+        })
+        .collect()
+}
+
+/// The entries of the days from `first` to `last` with their annotations, if
+/// `wanted`; the HTML report lists them, the others only count them.
+fn collect_entries(
+    repo: &Repository,
+    first: NaiveDate,
+    last: NaiveDate,
+    wanted: bool,
+) -> Result<Vec<DayEntry>, PersistenceError> {
+    if !wanted {
+        return Ok(Vec::new());
     }
+    let (start, end) = (day_bounds(first).0, day_bounds(last).1);
+    Ok(day_entries(
+        repo.entries_between(start, end)?,
+        &repo.annotations_between(start, end)?,
+    ))
+}
 
-    // ── Entry list with annotations ─────────────────────────
-    for entry in &entries {
-        let start = format_time(entry.started_at);
-        let end = if entry.finished_at != 0 {
-            format_time(entry.finished_at)
-        } else if entry.cancelled_at != 0 {
-            format_time(entry.cancelled_at)
+impl DayEntry {
+    fn end(&self) -> String {
+        if self.ended_at != 0 {
+            crate::format_time(self.ended_at)
         } else {
             "...".to_string()
-        };
+        }
+    }
 
-        let status_icon = match entry.status() {
+    fn status_icon(&self) -> &'static str {
+        match self.status {
             Status::Finished => "\u{2713}",
             Status::Cancelled => "\u{2717}",
             Status::Active => "\u{2026}",
             Status::Stale => "?",
             Status::New => "?",
-        };
+        }
+    }
+}
 
-        let interrupt_info = if entry.interruptions > 0 {
-            format!(" ({} int.)", entry.interruptions)
-        } else {
-            String::new()
-        };
+impl Render for DayReport {
+    const NAME: &'static str = "day";
+
+    fn text(&self) -> String {
+        let mut rpt = Report::new();
 
+        // ── Header ──────────────────────────────────────────────
         rpt.line(format_args!(
-            " {:>5} - {:<5}  {:<9} ({:>2} min)  {}{}",
-            start,
-            end,
-            format!("{}", entry.kind),
-            entry.duration,
-            status_icon,
-            interrupt_info,
+            "Report for {} ({})",
+            self.date,
+            self.date.format("%A")
         ));
+        rpt.separator(35);
+        rpt.blank();
+
+        if self.entries.is_empty() {
+            rpt.line("Nothing recorded for this day.");
+            return rpt.into_string();
+        }
+
+        write_timeline(&mut rpt, self.timeline.as_ref());
+
+        // ── Entry list with annotations ─────────────────────────
+        for entry in &self.entries {
+            let interrupt_info = if entry.interruptions > 0 {
+                format!(" ({} int.)", entry.interruptions)
+            } else {
+                String::new()
+            };
+
+            rpt.line(format_args!(
+                " {:>5} - {:<5}  {:<9} ({:>2} min)  {}{}",
+                crate::format_time(entry.started_at),
+                entry.end(),
+                format!("{}", entry.kind),
+                entry.planned_duration,
+                entry.status_icon(),
+                interrupt_info,
+            ));
 
-        // Annotations for this entry
-        if let Some(notes) = ann_by_uuid.get(&entry.uuid.to_string()) {
-            for note in notes {
-                rpt.line(format_args!("    \u{2192} {}", note.body));
+            for note in &entry.annotations {
+                rpt.line(format_args!("    \u{2192} {}", note));
             }
         }
-    }
-    rpt.blank();
+        rpt.blank();
 
-    // ── Metrics ─────────────────────────────────────────────
-    let agg = compute_aggregate(&entries, &interrupt_logs);
+        // ── Metrics ─────────────────────────────────────────────
+        let agg = &self.summary;
 
-    rpt.line(format_args!(
-        "Pomodori    {} completed  \u{00b7}  {} cancelled  \u{00b7}  {}% completion rate",
-        agg.completed, agg.cancelled, agg.completion_rate
-    ));
-    rpt.line(format_args!(
-        "Breaks      {} taken      \u{00b7}  {} cancelled",
-        agg.breaks_taken, agg.breaks_cancelled
-    ));
-    if agg.completed > 0 && agg.breaks_taken > 0 {
         rpt.line(format_args!(
-            "Ratio       {:.1} break per pomodoro  {}",
-            agg.break_ratio,
-            ratio_indicator(agg.break_ratio)
+            "Pomodori    {} completed  \u{00b7}  {} cancelled  \u{00b7}  {}% completion rate",
+            agg.completed, agg.cancelled, agg.completion_rate
+        ));
+        rpt.line(format_args!(
+            "Breaks      {} taken      \u{00b7}  {} cancelled",
+            agg.breaks_taken, agg.breaks_cancelled
         ));
+        if agg.completed > 0 && agg.breaks_taken > 0 {
+            rpt.line(format_args!(
+                "Ratio       {:.1} break per pomodoro  {}",
+                agg.break_ratio,
+                ratio_indicator(agg.break_ratio)
+            ));
+        }
+        rpt.blank();
+
+        if agg.max_focus_block > 1 {
+            rpt.line(format_args!(
+                "Longest focus block:  {} consecutive pomodori without interruption",
+                agg.max_focus_block
+            ));
+            rpt.blank();
+        }
+
+        // ── Interruptions ─────────────────────────────────────
+        print_interruption_summary(&mut rpt, agg, None, None);
+
+        rpt.into_string()
     }
-    rpt.blank();
 
-    if agg.max_focus_block > 1 {
+    fn markdown(&self) -> String {
+        let mut rpt = Report::new();
         rpt.line(format_args!(
-            "Longest focus block:  {} consecutive pomodori without interruption",
-            agg.max_focus_block
+            "# Report for {} ({})",
+            self.date,
+            self.date.format("%A")
         ));
         rpt.blank();
+        if self.entries.is_empty() {
+            rpt.line("Nothing recorded for this day.");
+            return rpt.into_string();
+        }
+        markdown_timeline(&mut rpt, self.timeline.as_ref());
+
+        let rows: Vec<Vec<String>> = self
+            .entries
+            .iter()
+            .map(|e| {
+                vec![
+                    crate::format_time(e.started_at),
+                    e.end(),
+                    e.kind.to_string(),
+                    format!("{} min", e.planned_duration),
+                    e.status.as_str().to_string(),
+                    e.interruptions.to_string(),
+                    e.annotations.join("\n"),
+                ]
+            })
+            .collect();
+        rpt.table(
+            &[
+                "Start",
+                "End",
+                "Kind",
+                "Planned",
+                "Status",
+                "Interruptions",
+                "Annotations",
+            ],
+            &rows,
+        );
+        rpt.blank();
+        rpt.line("## Summary");
+        rpt.blank();
+        markdown_metrics(&mut rpt, &self.summary, None);
+        rpt.into_string()
+    }
+
+    fn html(&self) -> String {
+        let title = format!("Report for {} ({})", self.date, self.date.format("%A"));
+        let mut html = Html::new(&title);
+        html.element("h1", &title);
+        if self.entries.is_empty() {
+            html.muted("Nothing recorded for this day.");
+            return html.into_string();
+        }
+        html_timeline(&mut html, self.timeline.as_ref());
+        html.chart(&self.hour_chart());
+        html_entries(&mut html, &self.entries, false);
+        html.element("h2", "Summary");
+        html.list(&metric_items(&self.summary, None));
+        html_interruptions(&mut html, &self.summary);
+        html.into_string()
+    }
+}
+
+impl DayReport {
+    /// Completed and cancelled pomodori by the hour they were started in.
+    fn hour_chart(&self) -> Chart {
+        let mut by_hour: BTreeMap<u32, (f64, f64)> = BTreeMap::new();
+        for entry in self.entries.iter().filter(|e| e.kind == Kind::Pomodoro) {
+            let Some(dt) = timezone::clock().datetime(entry.started_at) else {
+                continue;
+            };
+            let counts = by_hour.entry(dt.hour()).or_default();
+            match entry.status {
+                Status::Finished => counts.0 += 1.0,
+                Status::Cancelled => counts.1 += 1.0,
+                _ => {}
+            }
+        }
+        let hours = hour_labels(by_hour.keys().copied());
+        let count = |hour: &u32| by_hour.get(hour).copied().unwrap_or_default();
+        Chart {
+            title: "Pomodori by hour of day".to_string(),
+            style: Style::Bars,
+            labels: hours.iter().map(|h| format!("{:02}", h)).collect(),
+            series: completed_and_cancelled(
+                hours.iter().map(|h| count(h).0).collect(),
+                hours.iter().map(|h| count(h).1).collect(),
+            ),
+        }
+    }
+}
+
+/// Print a single-day report.
+pub fn print_day_report(repo: &Repository, date: Option<String>, timeline: bool, format: Format) {
+    let date = parse_date_or_today(date);
+    let report = day_report(repo, date).and_then(|report| {
+        Ok(DayReport {
+            timeline: collect_timeline(repo, date, date, timeline)?,
+            ..report
+        })
+    });
+    print(report, format);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_table_escapes_cells() {
+        let mut rpt = Report::new();
+        rpt.table(
+            &["A", "B"],
+            &[vec!["a|b".to_string(), "one\ntwo".to_string()]],
+        );
+        assert_eq!(
+            rpt.into_string(),
+            "| A | B |\n|---|---|\n| a\\|b | one<br>two |\n"
+        );
+    }
+
+    #[test]
+    fn test_html_escapes_text() {
+        let mut html = Html::new("A & B");
+        html.table(&["<th>"], &[vec!["<script>\"x\"</script>".to_string()]]);
+        html.pre("\n  <b>\n\n");
+        let html = html.into_string();
+        assert!(html.contains("<title>A &amp; B</title>"));
+        assert!(html.contains("<tr><th>&lt;th&gt;</th></tr>"));
+        assert!(html.contains("<tr><td>&lt;script&gt;&quot;x&quot;&lt;/script&gt;</td></tr>"));
+        assert!(html.contains("<pre>  &lt;b&gt;</pre>"));
+        assert!(html.ends_with("</body>\n</html>\n"));
+    }
+
+    #[test]
+    fn test_hint_display_and_json() {
+        let hint = Hint::new(HintKind::Tip, "Take a walk.");
+        assert_eq!(hint.to_string(), "💡 Take a walk.");
+        assert_eq!(
+            serde_json::to_string(&hint).unwrap(),
+            r#"{"kind":"tip","message":"Take a walk."}"#
+        );
+    }
+
+    #[test]
+    fn test_heat_level() {
+        assert_eq!(heat_level(0, 0), 0);
+        assert_eq!(heat_level(0, 8), 0);
+        assert_eq!(heat_level(1, 8), 1);
+        assert_eq!(heat_level(2, 8), 1);
+        assert_eq!(heat_level(3, 8), 2);
+        assert_eq!(heat_level(7, 8), 4);
+        assert_eq!(heat_level(8, 8), 4);
+        assert_eq!(heat_level(1, 1), 4);
     }
 
-    // ── Interruptions ─────────────────────────────────────
-    print_interruption_summary(&mut rpt, &agg, None, None);
+    #[test]
+    fn test_parse_range() {
+        let date = |s| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        assert_eq!(
+            parse_range("2026-05-04..2026-05-17"),
+            (date("2026-05-04"), date("2026-05-17"))
+        );
+        assert_eq!(
+            parse_range("2024-02"),
+            (date("2024-02-01"), date("2024-02-29"))
+        );
+        assert_eq!(
+            parse_range("2026-05-04"),
+            (date("2026-05-04"), date("2026-05-04"))
+        );
+    }
 
-    print!("{}", rpt.into_string());
+    #[test]
+    fn test_change() {
+        let change = Change::new("completed", "Completed", 0, 4.0, 5.0);
+        assert_eq!(change.delta(), "+1");
+        assert_eq!(change.percent(), "+25%");
+
+        let change = Change::new("break_ratio", "Breaks per pomodoro", 1, 0.0, 0.5);
+        assert_eq!(change.delta(), "+0.5");
+        assert_eq!(change.percent, None);
+        assert_eq!(change.percent(), "–");
+    }
+
+    #[test]
+    fn test_best_focus_window() {
+        let bucket =
+            |hour, completed, cancelled| OutputBucket::new(Hour { hour }, (completed, cancelled));
+        assert!(best_focus_window(&[]).is_none());
+        assert!(best_focus_window(&[bucket(9, 0, 2)]).is_none());
+
+        let window = best_focus_window(&[
+            bucket(8, 3, 0),
+            bucket(9, 1, 1),
+            bucket(14, 2, 0),
+            bucket(15, 2, 2),
+        ])
+        .unwrap();
+        assert_eq!((window.start, window.end), (8, 10));
+        assert_eq!(window.completed, 4);
+        assert_eq!(window.completion_rate, 80);
+
+        // The earliest of equally good windows
+        let window = best_focus_window(&[bucket(23, 1, 0), bucket(6, 1, 0)]).unwrap();
+        assert_eq!((window.start, window.end), (5, 7));
+    }
 }
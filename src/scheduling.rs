@@ -1,6 +1,7 @@
 use super::hooks::{self, HookContext, HookEvent};
 use super::persistence::{PersistenceError, Repository};
 use super::{Annotation, InterruptLog, InterruptionKind, Kind, Schedulable, SqlUuid, Status};
+use crate::exit_code;
 use indicatif::{ProgressBar, ProgressStyle};
 use std::fmt;
 use std::io::IsTerminal;
@@ -34,7 +35,10 @@ pub struct Scheduler {
 
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub enum SchedulingError {
-    ExecutionError,
+    /// The database operation failed; the cause is kept for reporting.
+    Persistence(PersistenceError),
+    /// Waiting for the timer failed.
+    Io(String),
     AlreadyRunning(u32),
     HookRejected,
     NoActiveSchedulable,
@@ -46,7 +50,8 @@ pub enum SchedulingError {
 impl fmt::Display for SchedulingError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            SchedulingError::ExecutionError => write!(f, "cannot execute schedulable"),
+            SchedulingError::Persistence(e) => write!(f, "{}", e),
+            SchedulingError::Io(e) => write!(f, "{}", e),
             SchedulingError::AlreadyRunning(pid) => {
                 write!(
                     f,
@@ -74,6 +79,31 @@ impl fmt::Display for SchedulingError {
     }
 }
 
+impl SchedulingError {
+    /// The process exit code that reports this error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            SchedulingError::Persistence(e) => e.exit_code(),
+            SchedulingError::Io(_) => exit_code::IO,
+            SchedulingError::AlreadyRunning(_) => exit_code::ALREADY_RUNNING,
+            SchedulingError::HookRejected => exit_code::HOOK_REJECTED,
+            SchedulingError::CannotResolveTarget(_) => exit_code::NOT_FOUND,
+            SchedulingError::NoActiveSchedulable
+            | SchedulingError::NothingToAnnotate
+            | SchedulingError::NothingToCancel => exit_code::ERROR,
+        }
+    }
+}
+
+impl From<PersistenceError> for SchedulingError {
+    fn from(e: PersistenceError) -> Self {
+        match e {
+            PersistenceError::AlreadyRunning(pid) => SchedulingError::AlreadyRunning(pid),
+            e => SchedulingError::Persistence(e),
+        }
+    }
+}
+
 impl Scheduler {
     pub fn new(repo: Repository, root: PathBuf, verbose: bool, no_hooks: bool) -> Self {
         Self {
@@ -132,10 +162,7 @@ impl Scheduler {
 
         self.run_hook(before, schedulable)?;
 
-        let saved = self
-            .repo
-            .save_external_finished(schedulable)
-            .map_err(map_exec_err)?;
+        let saved = self.repo.save_external_finished(schedulable)?;
 
         self.run_hook_after(after, &saved);
 
@@ -153,22 +180,14 @@ impl Scheduler {
             ctx.interrupt_kind = Some(kind.as_str().to_string());
         })?;
 
-        // Increment the counter
-        let updated = self
-            .repo
-            .record_interrupt(target.uuid)
-            .map_err(map_exec_err)?;
-
-        // Save to interrupt log
+        // Increment the counter and save to the interrupt log
         let interrupt_log = InterruptLog {
             uuid: SqlUuid::default(),
             schedulable_uuid: target.uuid,
             kind,
             created_at: crate::now(),
         };
-        self.repo
-            .save_interrupt(&interrupt_log)
-            .map_err(map_exec_err)?;
+        let updated = self.repo.interrupt(&interrupt_log)?;
 
         // Run after-interrupt hook
         self.run_hook_after_with(HookEvent::AfterInterruptPomodoro, &updated, |ctx| {
@@ -214,13 +233,13 @@ impl Scheduler {
             Kind::Pomodoro => {
                 self.run_hook(HookEvent::BeforeCancelPomodoro, schedulable)?;
                 schedulable.cancelled_at = crate::now();
-                self.repo.save(schedulable).map_err(map_exec_err)?;
+                self.repo.save(schedulable)?;
                 self.run_hook_after(HookEvent::AfterCancelPomodoro, schedulable);
             }
             Kind::Break => {
                 self.run_hook(HookEvent::BeforeFinishBreak, schedulable)?;
                 schedulable.finished_at = crate::now();
-                self.repo.save(schedulable).map_err(map_exec_err)?;
+                self.repo.save(schedulable)?;
                 self.run_hook_after(HookEvent::AfterFinishBreak, schedulable);
             }
         }
@@ -231,7 +250,7 @@ impl Scheduler {
     /// Pomodoro → cancel (cancelled_at). Break → finish (finished_at).
     /// Returns `NothingToCancel` if nothing is active.
     pub fn cancel(&self) -> Result<Schedulable, SchedulingError> {
-        let active = self.repo.active().map_err(map_exec_err)?;
+        let active = self.repo.active()?;
 
         let mut schedulable = active.ok_or(SchedulingError::NothingToCancel)?;
         self.close_out(&mut schedulable)?;
@@ -258,7 +277,7 @@ impl Scheduler {
                 self.run_hook(HookEvent::BeforeCancelPomodoro, &target)?;
                 target.cancelled_at = crate::now();
                 target.finished_at = 0;
-                self.repo.save(&target).map_err(map_exec_err)?;
+                self.repo.save(&target)?;
                 self.run_hook_after(HookEvent::AfterCancelPomodoro, &target);
                 Ok(target)
             }
@@ -271,7 +290,7 @@ impl Scheduler {
                 self.run_hook(HookEvent::BeforeFinishBreak, &target)?;
                 target.finished_at = crate::now();
                 target.cancelled_at = 0;
-                self.repo.save(&target).map_err(map_exec_err)?;
+                self.repo.save(&target)?;
                 self.run_hook_after(HookEvent::AfterFinishBreak, &target);
                 Ok(target)
             }
@@ -301,7 +320,7 @@ impl Scheduler {
         };
         self.run_hook(before_event, &target)?;
 
-        self.repo.delete(target.uuid).map_err(map_exec_err)?;
+        self.repo.delete(target.uuid)?;
 
         let after_event = match target.kind {
             Kind::Pomodoro => HookEvent::AfterDeletePomodoro,
@@ -319,15 +338,14 @@ impl Scheduler {
 
     /// Annotate the active schedulable, or the most recently ended one.
     pub fn annotate(&self, text: &str) -> Result<Annotation, SchedulingError> {
-        let active = self.repo.active().map_err(map_exec_err)?;
+        let active = self.repo.active()?;
 
         let target = match active {
             Some(s) => s,
             None => {
                 // Nothing active — annotate the most recently ended
                 self.repo
-                    .most_recently_ended()
-                    .map_err(map_exec_err)?
+                    .most_recently_ended()?
                     .ok_or(SchedulingError::NothingToAnnotate)?
             }
         };
@@ -338,7 +356,7 @@ impl Scheduler {
     /// Annotate a schedulable of the given kind. If active and matches kind,
     /// annotates it; otherwise falls back to the most recently finished of that kind.
     pub fn annotate_for_kind(&self, text: &str, kind: Kind) -> Result<Annotation, SchedulingError> {
-        let active = self.repo.active().map_err(map_exec_err)?;
+        let active = self.repo.active()?;
 
         let target = match active {
             Some(s) if s.kind == kind => s,
@@ -348,13 +366,11 @@ impl Scheduler {
                 match kind {
                     Kind::Pomodoro => self
                         .repo
-                        .most_recently_finished_pomodoro()
-                        .map_err(map_exec_err)?
+                        .most_recently_finished_pomodoro()?
                         .ok_or(SchedulingError::NothingToAnnotate)?,
                     Kind::Break => self
                         .repo
-                        .most_recently_finished_break()
-                        .map_err(map_exec_err)?
+                        .most_recently_finished_break()?
                         .ok_or(SchedulingError::NothingToAnnotate)?,
                 }
             }
@@ -389,8 +405,7 @@ impl Scheduler {
         if raw == "0" {
             let active = self
                 .repo
-                .active()
-                .map_err(map_exec_err)?
+                .active()?
                 .ok_or(SchedulingError::NoActiveSchedulable)?;
             if let Some(k) = kind
                 && active.kind != k
@@ -410,8 +425,7 @@ impl Scheduler {
             let exclude = self.repo.active().ok().flatten().map(|s| s.uuid);
             return self
                 .repo
-                .nth_most_recently_started(n, kind, exclude)
-                .map_err(map_exec_err)?
+                .nth_most_recently_started(n, kind, exclude)?
                 .ok_or_else(|| {
                     SchedulingError::CannotResolveTarget(format!("no entry at position -{}", n))
                 });
@@ -419,7 +433,7 @@ impl Scheduler {
 
         // Timestamp (HH:MM, RFC 3339, ISO 8601, or Unix timestamp)
         if let Ok(ts) = super::parse_timestamp(raw)
-            && let Some(s) = self.repo.find_by_timestamp(ts).map_err(map_exec_err)?
+            && let Some(s) = self.repo.find_by_timestamp(ts)?
         {
             return Ok(s);
         }
@@ -457,10 +471,7 @@ impl Scheduler {
             body: text.to_string(),
             created_at: crate::now(),
         };
-        let saved = self
-            .repo
-            .save_annotation(&annotation)
-            .map_err(map_exec_err)?;
+        let saved = self.repo.save_annotation(&annotation)?;
 
         // Run after hook
         let after_event = match target.kind {
@@ -480,6 +491,7 @@ impl Scheduler {
         force: bool,
     ) -> Result<Schedulable, SchedulingError> {
         schedulable.started_at = crate::now();
+        schedulable.utc_offset = Some(crate::timezone::offset_to_record(schedulable.started_at));
 
         // --- force: kill any existing active schedulable, then close it out ---
         if force && let Ok(Some(mut active)) = self.repo.active() {
@@ -499,15 +511,7 @@ impl Scheduler {
         self.run_hook(event, &schedulable)?;
 
         // --- insert into database (active) ---
-        let mut schedulable = match self.repo.save(&schedulable) {
-            Ok(v) => v,
-            Err(e) => match e {
-                PersistenceError::AlreadyRunning(pid) => {
-                    return Err(SchedulingError::AlreadyRunning(pid));
-                }
-                _ => return Err(SchedulingError::ExecutionError),
-            },
-        };
+        let mut schedulable = self.repo.save(&schedulable)?;
 
         // --- after-start-{kind} ---
         let event = match schedulable.kind {
@@ -530,7 +534,7 @@ impl Scheduler {
         .recv()
         {
             Ok(cancelled) => cancelled,
-            Err(_) => return Err(SchedulingError::ExecutionError),
+            Err(e) => return Err(SchedulingError::Io(format!("waiting for the timer: {}", e))),
         };
 
         match schedulable.kind {
@@ -539,7 +543,7 @@ impl Scheduler {
                 self.run_hook(HookEvent::BeforeCancelPomodoro, &schedulable)?;
 
                 schedulable.cancelled_at = crate::now();
-                self.repo.save(&schedulable).map_err(map_exec_err)?;
+                self.repo.save(&schedulable)?;
 
                 self.run_hook_after(HookEvent::AfterCancelPomodoro, &schedulable);
 
@@ -550,7 +554,7 @@ impl Scheduler {
                 self.run_hook(HookEvent::BeforeFinishPomodoro, &schedulable)?;
 
                 schedulable.finished_at = crate::now();
-                self.repo.save(&schedulable).map_err(map_exec_err)?;
+                self.repo.save(&schedulable)?;
 
                 self.run_hook_after(HookEvent::AfterFinishPomodoro, &schedulable);
 
@@ -561,7 +565,7 @@ impl Scheduler {
                 self.run_hook(HookEvent::BeforeFinishBreak, &schedulable)?;
 
                 schedulable.finished_at = crate::now();
-                self.repo.save(&schedulable).map_err(map_exec_err)?;
+                self.repo.save(&schedulable)?;
 
                 self.run_hook_after(HookEvent::AfterFinishBreak, &schedulable);
 
@@ -660,13 +664,6 @@ fn waiter(started_at: i64, duration: i64, kind: Kind) -> Receiver<bool> {
     result_rx
 }
 
-/// Convert a `PersistenceError` to `SchedulingError::ExecutionError`
-/// after printing the original error to stderr.
-fn map_exec_err(e: PersistenceError) -> SchedulingError {
-    eprintln!("Error: {}.", e);
-    SchedulingError::ExecutionError
-}
-
 /// Parse `-N` where N is 1..=9 and return `Some(N)`, or `None`.
 fn parse_negative_index(raw: &str) -> Option<u32> {
     if raw.len() == 2 && raw.starts_with('-') {
@@ -0,0 +1,351 @@
+//! hledger/ledger timeclock files for `import --from timeclock` and
+//! `export --format timeclock`.
+//!
+//! Clocking in names an account and optionally a description, separated by
+//! two spaces; clocking out closes the session. Times are local:
+//!
+//! ```text
+//! i 2026/05/29 10:00:00 work:rustomato  fixed the tests
+//! o 2026/05/29 10:25:00
+//! ```
+
+use crate::import::{self, Record};
+use crate::{Annotation, Kind, Schedulable, Status, timezone};
+
+/// The account used by `export --format timeclock` unless another is given.
+pub const DEFAULT_ACCOUNT: &str = "focus:pomodoro";
+
+/// The placeholders an account template may contain.
+const PLACEHOLDERS: [&str; 3] = ["{kind}", "{tag}", "{annotation}"];
+
+/// Options of `export --format timeclock`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    /// Template for the account of each session, see [`account`]
+    pub account: String,
+    /// Also write cancelled pomodori, up to the time they were cancelled
+    pub include_cancelled: bool,
+}
+
+/// Check that an account template only uses known placeholders.
+pub fn check_template(template: &str) -> Result<(), String> {
+    let mut rest = template.to_string();
+    for placeholder in PLACEHOLDERS {
+        rest = rest.replace(placeholder, "");
+    }
+    if let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .map_or(rest.len(), |e| start + e + 1);
+        return Err(format!(
+            "unknown placeholder '{}' in account '{}'; expected {}",
+            &rest[start..end],
+            template,
+            PLACEHOLDERS.join(", ")
+        ));
+    }
+    if template.trim().is_empty() {
+        return Err("the account must not be empty".to_string());
+    }
+    Ok(())
+}
+
+/// Render pomodori as timeclock sessions, one `i` and one `o` line each, in
+/// the order given. Breaks and running pomodori are left out, and so are
+/// cancelled ones unless asked for. Annotations become the description.
+pub fn sessions(entries: &[(Schedulable, Vec<Annotation>)], options: &Options) -> String {
+    let mut out = String::new();
+    for (s, annotations) in entries {
+        if s.kind != Kind::Pomodoro {
+            continue;
+        }
+        let (end, cancelled) = match s.status() {
+            Status::Finished => (s.finished_at, false),
+            Status::Cancelled if options.include_cancelled => (s.cancelled_at, true),
+            _ => continue,
+        };
+
+        let mut description: Vec<String> =
+            annotations.iter().map(|a| single_line(&a.body)).collect();
+        if cancelled {
+            description.insert(0, "(cancelled)".to_string());
+        }
+        out.push_str(&format!(
+            "i {} {}",
+            local(s.started_at),
+            account(&options.account, s, annotations)
+        ));
+        if !description.is_empty() {
+            out.push_str(&format!("  {}", description.join("; ")));
+        }
+        out.push('\n');
+        out.push_str(&format!("o {}\n", local(end)));
+    }
+    out
+}
+
+/// Expand an account template for `s`:
+///
+/// * `{kind}` is `pomodoro` or `break`
+/// * `{tag}` is the first `#tag` in the annotations, without the `#`, or `untagged`
+/// * `{annotation}` is the first annotation, or `unannotated`
+///
+/// Runs of whitespace are collapsed, as two spaces would end the account.
+pub fn account(template: &str, s: &Schedulable, annotations: &[Annotation]) -> String {
+    let tag = annotations
+        .iter()
+        .flat_map(|a| a.body.split_whitespace())
+        .find_map(|w| w.strip_prefix('#').filter(|t| !t.is_empty()))
+        .unwrap_or("untagged");
+    let annotation = annotations
+        .first()
+        .map(|a| single_line(&a.body))
+        .filter(|a| !a.is_empty())
+        .unwrap_or_else(|| "unannotated".to_string());
+
+    single_line(
+        &template
+            .replace("{kind}", &s.kind.to_string())
+            .replace("{tag}", tag)
+            .replace("{annotation}", &annotation),
+    )
+}
+
+/// Collapse all whitespace, including newlines, into single spaces.
+fn single_line(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Format a timestamp as a timeclock date and time in the zone of the [`timezone::clock`].
+fn local(ts: i64) -> String {
+    timezone::clock().format(ts, "%Y/%m/%d %H:%M:%S")
+}
+
+/// Parse the sessions of a timeclock file. A session that is clocked in but
+/// not out is still running.
+pub fn parse(text: &str) -> Result<Vec<Record>, String> {
+    let mut records = Vec::new();
+    let mut clocked_in: Option<(usize, i64, String)> = None;
+
+    for (i, text) in text.lines().enumerate() {
+        let line = i + 1;
+        let at = |e: String| format!("line {}: {}", line, e);
+        let text = text.trim_end();
+        if text.is_empty() || text.starts_with([';', '#', '*']) {
+            continue;
+        }
+
+        let (code, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+        let (ts, rest) = timestamp(rest.trim_start()).map_err(at)?;
+        match code {
+            "i" | "I" => {
+                if let Some((since, _, _)) = clocked_in {
+                    return Err(at(format!("already clocked in on line {}", since)));
+                }
+                clocked_in = Some((line, ts, rest.to_string()));
+            }
+            "o" | "O" => {
+                let Some((since, started_at, session)) = clocked_in.take() else {
+                    return Err(at("clocked out without clocking in".to_string()));
+                };
+                records.push(session_record(since, started_at, Some(ts), &session).map_err(at)?);
+            }
+            _ => return Err(at(format!("unsupported entry '{}'", code))),
+        }
+    }
+
+    if let Some((since, started_at, session)) = clocked_in {
+        records.push(
+            session_record(since, started_at, None, &session)
+                .map_err(|e| format!("line {}: {}", since, e))?,
+        );
+    }
+    Ok(records)
+}
+
+fn session_record(
+    line: usize,
+    started_at: i64,
+    ended_at: Option<i64>,
+    session: &str,
+) -> Result<Record, String> {
+    let (account, description) = session.split_once("  ").unwrap_or((session, ""));
+    let accounts: Vec<String> = Some(account.trim().to_string())
+        .filter(|a| !a.is_empty())
+        .into_iter()
+        .collect();
+
+    import::external(
+        line,
+        import::kind_of(&accounts),
+        started_at,
+        ended_at,
+        import::note_of(&accounts, description),
+    )
+}
+
+/// Parse the date and time at the start of `s`, in the zone of the
+/// [`timezone::clock`], and return the rest of the line.
+fn timestamp(s: &str) -> Result<(i64, &str), String> {
+    let mut parts = s.splitn(3, char::is_whitespace);
+    let (date, time) = match (parts.next(), parts.next()) {
+        (Some(date), Some(time)) => (date.replace('-', "/"), time),
+        _ => return Err("expected a date and a time".to_string()),
+    };
+    let rest = parts.next().unwrap_or("").trim();
+
+    let naive = ["%Y/%m/%d %H:%M:%S", "%Y/%m/%d %H:%M"]
+        .iter()
+        .find_map(|f| chrono::NaiveDateTime::parse_from_str(&format!("{} {}", date, time), f).ok())
+        .ok_or_else(|| format!("invalid date and time '{} {}'", date, time))?;
+    let ts = timezone::clock()
+        .zone()
+        .timestamp(&naive)
+        .ok_or_else(|| format!("{} does not exist in {}", naive, timezone::clock().zone()))?;
+
+    Ok((ts, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Kind;
+
+    fn local_ts(s: &str) -> i64 {
+        let naive = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap();
+        timezone::clock().zone().timestamp(&naive).unwrap()
+    }
+
+    fn pomodoro(started_at: i64, body: &str) -> (Schedulable, Vec<Annotation>) {
+        let mut s = Schedulable::new(0, Kind::Pomodoro, 25);
+        s.started_at = started_at;
+        s.finished_at = started_at + 1500;
+        let annotations = vec![Annotation {
+            uuid: crate::SqlUuid::default(),
+            schedulable_uuid: s.uuid,
+            body: body.to_string(),
+            created_at: s.finished_at,
+        }];
+        (s, annotations)
+    }
+
+    #[test]
+    fn test_check_template() {
+        assert!(check_template("focus:{kind}:{tag}").is_ok());
+        assert!(check_template("focus:{annotation}").is_ok());
+        assert_eq!(
+            check_template("focus:{project}").unwrap_err(),
+            "unknown placeholder '{project}' in account 'focus:{project}'; \
+             expected {kind}, {tag}, {annotation}"
+        );
+        assert!(check_template(" ").is_err());
+    }
+
+    #[test]
+    fn test_account() {
+        let (s, annotations) = pomodoro(1000, "reviewed  #rustomato\nPR");
+        assert_eq!(
+            account("focus:{tag}:{annotation}", &s, &annotations),
+            "focus:rustomato:reviewed #rustomato PR"
+        );
+        assert_eq!(account("focus:{tag}", &s, &[]), "focus:untagged");
+    }
+
+    #[test]
+    fn test_sessions_round_trip() {
+        let (first, first_annotations) =
+            pomodoro(local_ts("2026-05-29 10:00:00"), "#rustomato  tests");
+        let (mut cancelled, _) = pomodoro(local_ts("2026-05-29 11:00:00"), "");
+        cancelled.finished_at = 0;
+        cancelled.cancelled_at = cancelled.started_at + 600;
+        let mut brk = Schedulable::new(0, Kind::Break, 5);
+        brk.started_at = first.finished_at;
+        brk.finished_at = brk.started_at + 300;
+        let entries = vec![
+            (first, first_annotations),
+            (brk, vec![]),
+            (cancelled, vec![]),
+        ];
+        let options = Options {
+            account: "focus:{tag}".to_string(),
+            include_cancelled: false,
+        };
+
+        let out = sessions(&entries, &options);
+        assert_eq!(
+            out,
+            "i 2026/05/29 10:00:00 focus:rustomato  #rustomato tests\n\
+             o 2026/05/29 10:25:00\n"
+        );
+        let records = parse(&out).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].entry.started_at, entries[0].0.started_at);
+        assert_eq!(records[0].entry.finished_at, entries[0].0.finished_at);
+
+        let out = sessions(
+            &entries,
+            &Options {
+                include_cancelled: true,
+                ..options
+            },
+        );
+        assert!(out.ends_with(
+            "i 2026/05/29 11:00:00 focus:untagged  (cancelled)\n\
+             o 2026/05/29 11:10:00\n"
+        ));
+        assert_eq!(parse(&out).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_parse_sessions() {
+        let records = parse(
+            "; my time\n\
+             i 2026/05/29 10:00:00 work:rustomato  fixed the tests\n\
+             o 2026/05/29 10:25:00\n\
+             i 2026-05-29 10:25 break\n\
+             O 2026-05-29 10:30\n",
+        )
+        .unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].line, 2);
+        assert_eq!(records[0].entry.kind, Kind::Pomodoro);
+        assert_eq!(records[0].entry.started_at, local_ts("2026-05-29 10:00:00"));
+        assert_eq!(
+            records[0].entry.finished_at,
+            local_ts("2026-05-29 10:25:00")
+        );
+        assert_eq!(
+            records[0].annotations[0].body,
+            "work:rustomato: fixed the tests"
+        );
+        assert_eq!(records[1].entry.kind, Kind::Break);
+        assert_eq!(records[1].entry.duration, 5);
+        assert!(records[1].annotations.is_empty());
+    }
+
+    #[test]
+    fn test_parse_session_still_clocked_in() {
+        let records = parse("i 2026/05/29 10:00:00 work\n").unwrap();
+        assert_eq!(records[0].entry.finished_at, 0);
+    }
+
+    #[test]
+    fn test_parse_rejects_clock_out_without_clock_in() {
+        let err = parse("o 2026/05/29 10:25:00\n").unwrap_err();
+        assert_eq!(err, "line 1: clocked out without clocking in");
+    }
+
+    #[test]
+    fn test_parse_rejects_session_longer_than_an_entry_may_last() {
+        let err = parse(
+            "i 2026/05/29 08:00:00 work\n\
+             o 2026/05/29 17:00:00\n",
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            "line 2: the entry lasts 540 minutes, longer than the 480 minutes an entry may last"
+        );
+    }
+}
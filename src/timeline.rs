@@ -0,0 +1,301 @@
+//! Horizontal timelines of days for `report day --timeline` and
+//! `report week --timeline`, one row per day on a common hour scale:
+//!
+//! ```text
+//!        09    10    11    12
+//! Mon 25 █████░··█████x··██!██░░░···
+//! ```
+
+use crate::persistence::{PersistenceError, Repository};
+use crate::report::{display, status, timestamp};
+use crate::{InterruptLog, Kind, Schedulable, Status, timezone};
+use chrono::NaiveDate;
+use serde::{Serialize, Serializer};
+
+const POMODORO: char = '█';
+const CANCELLED: char = 'x';
+const BREAK: char = '░';
+const INTERRUPTION: char = '!';
+const OVERLAP: char = '#';
+const EMPTY: char = '·';
+
+/// Room for the day in front of each row, e.g. `Mon 25 `
+const LABEL_WIDTH: usize = 7;
+
+/// Fewer columns than this would not show anything useful.
+const MIN_COLUMNS: usize = 24;
+
+/// The seconds a column may stand for; each one divides an hour evenly.
+const RESOLUTIONS: [i64; 10] = [60, 120, 180, 300, 600, 900, 1200, 1800, 3600, 7200];
+
+/// A pomodoro or a break, from its start to its end.
+#[derive(Debug, Serialize)]
+struct Span {
+    #[serde(serialize_with = "display")]
+    kind: Kind,
+    #[serde(serialize_with = "status")]
+    status: Status,
+    #[serde(rename = "started_at", serialize_with = "timestamp")]
+    start: i64,
+    /// As drawn: running entries end now, stale ones after their planned time
+    #[serde(rename = "ended_at", serialize_with = "timestamp")]
+    end: i64,
+}
+
+impl Span {
+    fn new(s: &Schedulable, now: i64) -> Self {
+        let end = match s.status() {
+            Status::Finished => s.finished_at,
+            Status::Cancelled => s.cancelled_at,
+            Status::Active => now.min(s.started_at + s.duration * 60),
+            // Never ended; show the planned time
+            _ => s.started_at + s.duration * 60,
+        };
+        Span {
+            kind: s.kind,
+            status: s.status(),
+            start: s.started_at,
+            end: end.max(s.started_at),
+        }
+    }
+
+    fn cell(&self) -> char {
+        match (self.kind, self.status) {
+            (Kind::Break, _) => BREAK,
+            (Kind::Pomodoro, Status::Cancelled) => CANCELLED,
+            (Kind::Pomodoro, _) => POMODORO,
+        }
+    }
+}
+
+/// The pomodori, breaks and interruptions of one day.
+#[derive(Debug, Serialize)]
+struct Row {
+    date: NaiveDate,
+    /// Midnight, as recorded or as viewed depending on the [`timezone::clock`]
+    #[serde(skip)]
+    midnight: i64,
+    #[serde(rename = "entries")]
+    spans: Vec<Span>,
+    #[serde(serialize_with = "timestamps")]
+    interruptions: Vec<i64>,
+}
+
+fn timestamps<S: Serializer>(ts: &[i64], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(ts.iter().map(|ts| timezone::clock().format(*ts, "%+")))
+}
+
+/// Days drawn as rows of characters, one column per few minutes.
+#[derive(Debug)]
+pub struct Timeline {
+    rows: Vec<Row>,
+    /// Total width in characters, including the labels
+    width: usize,
+}
+
+/// In JSON, one object per day with its entries and interruptions; the width
+/// only matters for drawing.
+impl Serialize for Timeline {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(&self.rows)
+    }
+}
+
+/// Collect the timeline of the days from `first` to `last`, to be drawn
+/// `width` characters wide.
+pub fn collect(
+    repo: &Repository,
+    first: NaiveDate,
+    last: NaiveDate,
+    width: usize,
+) -> Result<Timeline, PersistenceError> {
+    let clock = timezone::clock();
+    let (start, end) = (clock.day_bounds(first).0, clock.day_bounds(last).1);
+    let entries = repo.entries_between(start, end)?;
+    let interrupts: Vec<InterruptLog> = repo.interrupts_between(start, end)?;
+    let now = crate::now();
+
+    let rows = first
+        .iter_days()
+        .take_while(|d| *d <= last)
+        .map(|date| {
+            let (midnight, end_of_day) = clock.day_bounds(date);
+            let on_day = |ts: i64| ts >= midnight && ts <= end_of_day;
+            Row {
+                date,
+                midnight,
+                spans: entries
+                    .iter()
+                    .filter(|e| on_day(e.started_at))
+                    .map(|e| Span::new(e, now))
+                    .collect(),
+                interruptions: interrupts
+                    .iter()
+                    .map(|i| i.created_at)
+                    .filter(|ts| on_day(*ts))
+                    .collect(),
+            }
+        })
+        .collect();
+
+    Ok(Timeline { rows, width })
+}
+
+impl Timeline {
+    /// The hours from midnight at which the scale starts and ends, covering
+    /// every entry of every day; `None` without any.
+    fn hours(&self) -> Option<(i64, i64)> {
+        let spans = || {
+            self.rows
+                .iter()
+                .flat_map(|r| r.spans.iter().map(move |s| (r.midnight, s)))
+        };
+        let first = spans().map(|(m, s)| (s.start - m) / 3600).min()?;
+        let last = spans().map(|(m, s)| (s.end - m + 3599) / 3600).max()?;
+        Some((first, last.max(first + 1)))
+    }
+
+    /// The scale, one line per day and a legend.
+    pub fn lines(&self) -> Vec<String> {
+        let Some((first_hour, last_hour)) = self.hours() else {
+            return vec!["(nothing recorded)".to_string()];
+        };
+        let columns = self.width.saturating_sub(LABEL_WIDTH).max(MIN_COLUMNS) as i64;
+        let span = (last_hour - first_hour) * 3600;
+        let resolution = RESOLUTIONS
+            .into_iter()
+            .find(|r| (span + r - 1) / r <= columns)
+            .unwrap_or(RESOLUTIONS[RESOLUTIONS.len() - 1]);
+        let columns = ((span + resolution - 1) / resolution) as usize;
+        let per_hour = (3600 / resolution) as usize;
+
+        // Hour labels, leaving at least one blank between them
+        let mut scale = vec![' '; columns + 2];
+        let step = 3_usize.div_ceil(per_hour);
+        for hour in (first_hour..last_hour).step_by(step) {
+            let col = (hour - first_hour) as usize * per_hour;
+            for (i, c) in format!("{:02}", hour % 24).chars().enumerate() {
+                scale[col + i] = c;
+            }
+        }
+        let mut lines = vec![format!(
+            "{}{}",
+            " ".repeat(LABEL_WIDTH),
+            scale.iter().collect::<String>().trim_end()
+        )];
+
+        for row in &self.rows {
+            let origin = row.midnight + first_hour * 3600;
+            let column = |ts: i64| ((ts - origin).max(0) / resolution) as usize;
+            let until = |ts: i64| (((ts - origin).max(0) + resolution - 1) / resolution) as usize;
+            let mut cells = vec![EMPTY; columns];
+            let mut paint = |start: i64, end: i64, c: char| {
+                let from = column(start).min(columns - 1);
+                let to = until(end).clamp(from + 1, columns);
+                cells[from..to].fill(c);
+            };
+
+            let mut latest_end = i64::MIN;
+            for span in &row.spans {
+                paint(span.start, span.end, span.cell());
+                if span.start < latest_end {
+                    paint(span.start, latest_end.min(span.end), OVERLAP);
+                }
+                latest_end = latest_end.max(span.end);
+            }
+            for ts in &row.interruptions {
+                let col = column(*ts).min(columns - 1);
+                cells[col] = INTERRUPTION;
+            }
+
+            lines.push(format!(
+                "{:<width$}{}",
+                row.date.format("%a %d").to_string(),
+                cells.iter().collect::<String>(),
+                width = LABEL_WIDTH
+            ));
+        }
+
+        lines.push(format!(
+            "{}{} pomodoro  {} cancelled  {} break  {} interruption  {} overlap  ({} min each)",
+            " ".repeat(LABEL_WIDTH),
+            POMODORO,
+            CANCELLED,
+            BREAK,
+            INTERRUPTION,
+            OVERLAP,
+            resolution / 60
+        ));
+        lines
+    }
+}
+
+/// The width of the terminal on stdout: `$COLUMNS` if set, otherwise what the
+/// terminal reports, or 80 if stdout is not a terminal.
+pub fn terminal_width() -> usize {
+    if let Some(columns) = std::env::var("COLUMNS").ok().and_then(|c| c.parse().ok()) {
+        return columns;
+    }
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    if unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } == 0
+        && size.ws_col > 0
+    {
+        return size.ws_col as usize;
+    }
+    80
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(kind: Kind, status: Status, start: i64, end: i64) -> Span {
+        Span {
+            kind,
+            status,
+            start,
+            end,
+        }
+    }
+
+    #[test]
+    fn test_lines() {
+        let date = NaiveDate::from_ymd_opt(2026, 5, 25).unwrap();
+        let midnight = 1_779_667_200; // 2026-05-25T00:00:00Z
+        let at = |h: i64, m: i64| midnight + h * 3600 + m * 60;
+        let timeline = Timeline {
+            rows: vec![Row {
+                date,
+                midnight,
+                spans: vec![
+                    span(Kind::Pomodoro, Status::Finished, at(9, 0), at(9, 25)),
+                    span(Kind::Break, Status::Finished, at(9, 25), at(9, 30)),
+                    span(Kind::Pomodoro, Status::Cancelled, at(9, 40), at(9, 50)),
+                    span(Kind::Pomodoro, Status::Finished, at(10, 0), at(10, 25)),
+                    span(Kind::Break, Status::Finished, at(10, 20), at(10, 30)),
+                ],
+                interruptions: vec![at(10, 10)],
+            }],
+            width: LABEL_WIDTH + 24,
+        };
+
+        assert_eq!(
+            timeline.lines(),
+            vec![
+                "       09          10".to_string(),
+                "Mon 25 █████░··xx··██!█#░······".to_string(),
+                "       █ pomodoro  x cancelled  ░ break  ! interruption  # overlap  (5 min each)"
+                    .to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lines_without_entries() {
+        let timeline = Timeline {
+            rows: vec![],
+            width: 80,
+        };
+        assert_eq!(timeline.lines(), vec!["(nothing recorded)".to_string()]);
+    }
+}
@@ -0,0 +1,198 @@
+//! Timewarrior data files for `import --from timewarrior`.
+//!
+//! Each line is one interval, with times in UTC:
+//!
+//! ```text
+//! inc 20260529T100000Z - 20260529T102500Z # rustomato "code review" # "fixed the tests"
+//! ```
+//!
+//! The tags come after the first `#` and the annotation after the second.
+//! An interval without an end is still being tracked.
+
+use crate::import::{self, Record};
+
+/// Parse the intervals of a Timewarrior data file.
+pub fn parse(text: &str) -> Result<Vec<Record>, String> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| parse_line(i + 1, l).map_err(|e| format!("line {}: {}", i + 1, e)))
+        .collect()
+}
+
+fn parse_line(line: usize, text: &str) -> Result<Record, String> {
+    let tokens = tokenize(text)?;
+    let mut tokens = tokens.iter().peekable();
+
+    match tokens.next() {
+        Some(Token::Word(w)) if w == "inc" => {}
+        _ => return Err("expected an interval starting with 'inc'".to_string()),
+    }
+    let started_at = match tokens.next() {
+        Some(Token::Word(w)) => utc(w)?,
+        _ => return Err("missing start time".to_string()),
+    };
+    let ended_at = if tokens
+        .next_if(|t| **t == Token::Word("-".to_string()))
+        .is_some()
+    {
+        match tokens.next() {
+            Some(Token::Word(w)) => Some(utc(w)?),
+            _ => return Err("missing end time".to_string()),
+        }
+    } else {
+        None
+    };
+
+    let mut tags = Vec::new();
+    let mut annotation = String::new();
+    if tokens.next_if(|t| **t == Token::Hash).is_some() {
+        while let Some(token) = tokens.next_if(|t| **t != Token::Hash) {
+            tags.push(token.text().to_string());
+        }
+        if tokens.next_if(|t| **t == Token::Hash).is_some() {
+            let words: Vec<&str> = tokens.by_ref().map(Token::text).collect();
+            annotation = words.join(" ");
+        }
+    }
+    if tokens.next().is_some() {
+        return Err("unexpected text after the interval".to_string());
+    }
+
+    import::external(
+        line,
+        import::kind_of(&tags),
+        started_at,
+        ended_at,
+        import::note_of(&tags, &annotation),
+    )
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Hash,
+}
+
+impl Token {
+    fn text(&self) -> &str {
+        match self {
+            Token::Word(s) | Token::Quoted(s) => s,
+            Token::Hash => "#",
+        }
+    }
+}
+
+/// Split a line into words, double-quoted strings (with backslash escapes)
+/// and unquoted `#` separators.
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(&ch) = chars.peek() {
+        match ch {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '"' => {
+                chars.next();
+                let mut quoted = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => quoted.push('\n'),
+                            Some('t') => quoted.push('\t'),
+                            Some(c) => quoted.push(c),
+                            None => return Err("unterminated quoted string".to_string()),
+                        },
+                        Some(c) => quoted.push(c),
+                        None => return Err("unterminated quoted string".to_string()),
+                    }
+                }
+                tokens.push(Token::Quoted(quoted));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                    word.push(c);
+                }
+                if word == "#" {
+                    tokens.push(Token::Hash);
+                } else {
+                    tokens.push(Token::Word(word));
+                }
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Parse a Timewarrior timestamp such as `20260529T100000Z`.
+fn utc(s: &str) -> Result<i64, String> {
+    chrono::NaiveDateTime::parse_from_str(s, "%Y%m%dT%H%M%SZ")
+        .map(|dt| dt.and_utc().timestamp())
+        .map_err(|_| format!("invalid time '{}'; expected e.g. 20260529T100000Z", s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Kind;
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize(r#"inc x # a "b \"c\"" # "d""#).unwrap(),
+            vec![
+                Token::Word("inc".to_string()),
+                Token::Word("x".to_string()),
+                Token::Hash,
+                Token::Word("a".to_string()),
+                Token::Quoted("b \"c\"".to_string()),
+                Token::Hash,
+                Token::Quoted("d".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_interval_with_tags_and_annotation() {
+        let records = parse(
+            "inc 20260529T100000Z - 20260529T102500Z # rustomato \"code review\" # \"fixed tests\"\n",
+        )
+        .unwrap();
+
+        let record = &records[0];
+        assert_eq!(record.line, 1);
+        assert_eq!(record.entry.kind, Kind::Pomodoro);
+        assert_eq!(record.entry.started_at, 1_780_048_800);
+        assert_eq!(record.entry.finished_at, 1_780_050_300);
+        assert_eq!(record.entry.duration, 25);
+        assert_eq!(
+            record.annotations[0].body,
+            "rustomato, code review: fixed tests"
+        );
+    }
+
+    #[test]
+    fn test_parse_break_and_open_interval() {
+        let records = parse(
+            "inc 20260529T102500Z - 20260529T103000Z # break\n\ninc 20260529T110000Z # rustomato\n",
+        )
+        .unwrap();
+
+        assert_eq!(records[0].entry.kind, Kind::Break);
+        assert!(records[0].annotations.is_empty());
+        assert_eq!(records[1].line, 3);
+        assert_eq!(records[1].entry.finished_at, 0);
+    }
+
+    #[test]
+    fn test_parse_reports_line() {
+        let err = parse("inc 20260529T100000Z - 20260529T102500Z\nexc 2026\n").unwrap_err();
+        assert!(err.starts_with("line 2:"), "{}", err);
+    }
+}
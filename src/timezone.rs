@@ -0,0 +1,372 @@
+//! The time zone that timestamps are shown and entered in.
+//!
+//! Entries are stored as Unix seconds. When an entry is started, the UTC
+//! offset in effect is recorded with it, so that reports can show times
+//! either as recorded (the wall clock at the time) or as viewed now (the
+//! current zone, or the one given with `--tz`).
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, LazyLock, RwLock};
+
+/// A zone that timestamps are shown in, e.g. from `--tz`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Zone {
+    /// The system's local time zone, including daylight saving time.
+    #[default]
+    Local,
+    /// A fixed offset from UTC.
+    Fixed(FixedOffset),
+}
+
+impl Zone {
+    /// The UTC offset of this zone at the given Unix timestamp.
+    pub fn offset_at(&self, ts: i64) -> FixedOffset {
+        match self {
+            Zone::Local => Local
+                .timestamp_opt(ts, 0)
+                .single()
+                .map(|dt| *dt.offset())
+                .unwrap_or_else(utc),
+            Zone::Fixed(offset) => *offset,
+        }
+    }
+
+    /// The Unix timestamp of a wall-clock time in this zone. Ambiguous times
+    /// resolve to the earlier instant.
+    pub fn timestamp(&self, naive: &NaiveDateTime) -> Option<i64> {
+        match self {
+            Zone::Local => Local
+                .from_local_datetime(naive)
+                .earliest()
+                .map(|dt| dt.timestamp()),
+            Zone::Fixed(offset) => offset
+                .from_local_datetime(naive)
+                .earliest()
+                .map(|dt| dt.timestamp()),
+        }
+    }
+}
+
+impl FromStr for Zone {
+    type Err = String;
+
+    /// Accepts `local`, `UTC` (or `Z`), and offsets of the form `±HH:MM` or
+    /// `±HHMM`, like `+09:00` or `-0530`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "invalid time zone '{}'; expected 'local', 'UTC' or an offset like +09:00 or -0530",
+                s
+            )
+        };
+
+        match s.to_lowercase().as_str() {
+            "local" => return Ok(Zone::Local),
+            "utc" | "z" => return Ok(Zone::Fixed(utc())),
+            _ => {}
+        }
+
+        let (sign, rest) = match s.split_at_checked(1) {
+            Some(("+", rest)) => (1, rest),
+            Some(("-", rest)) => (-1, rest),
+            _ => return Err(invalid()),
+        };
+        if !rest.is_ascii() {
+            return Err(invalid());
+        }
+        let (hours, minutes) = match (rest.len(), rest.as_bytes().get(2)) {
+            (5, Some(b':')) => (&rest[..2], &rest[3..]),
+            (4, _) => (&rest[..2], &rest[2..]),
+            _ => return Err(invalid()),
+        };
+        if !hours
+            .bytes()
+            .chain(minutes.bytes())
+            .all(|b| b.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        let hours: i32 = hours.parse().map_err(|_| invalid())?;
+        let minutes: i32 = minutes.parse().map_err(|_| invalid())?;
+        if hours > 23 || minutes > 59 {
+            return Err(invalid());
+        }
+
+        FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+            .map(Zone::Fixed)
+            .ok_or_else(invalid)
+    }
+}
+
+impl fmt::Display for Zone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Zone::Local => write!(f, "local"),
+            Zone::Fixed(offset) => write!(f, "{}", offset),
+        }
+    }
+}
+
+/// Whether reports show times as they were recorded or as viewed now.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Perspective {
+    /// In the zone of the viewer (the system's or the one from `--tz`).
+    #[default]
+    Viewed,
+    /// In the UTC offset recorded with each entry when it was started.
+    Recorded,
+}
+
+/// Converts between Unix timestamps and wall-clock time.
+#[derive(Debug, Default)]
+pub struct Clock {
+    zone: Zone,
+    perspective: Perspective,
+    /// Start times of entries and the offsets recorded with them, ordered by
+    /// start time. `None` for entries that predate recording offsets.
+    recorded: Vec<(i64, Option<i32>)>,
+}
+
+impl Clock {
+    pub fn new(zone: Zone, perspective: Perspective) -> Self {
+        Self {
+            zone,
+            perspective,
+            recorded: Vec::new(),
+        }
+    }
+
+    /// Use the given start times and recorded offsets (in seconds east of
+    /// UTC) of all entries when showing times as recorded.
+    pub fn with_recorded(mut self, mut recorded: Vec<(i64, Option<i32>)>) -> Self {
+        recorded.sort_by_key(|(started_at, _)| *started_at);
+        self.recorded = recorded;
+        self
+    }
+
+    /// The zone of the viewer. Times entered on the command line are
+    /// interpreted in it, and new entries record its offset.
+    pub fn zone(&self) -> Zone {
+        self.zone
+    }
+
+    /// The UTC offset that a timestamp is shown in.
+    ///
+    /// As recorded, this is the offset of the entry that was started last at
+    /// or before `ts`, so that an entry's start and end are shown in the same
+    /// offset. Timestamps without a recorded offset are shown as viewed.
+    pub fn offset_at(&self, ts: i64) -> FixedOffset {
+        if self.perspective == Perspective::Recorded {
+            let i = self
+                .recorded
+                .partition_point(|(started_at, _)| *started_at <= ts);
+            if i > 0
+                && let (_, Some(seconds)) = self.recorded[i - 1]
+                && let Some(offset) = FixedOffset::east_opt(seconds)
+            {
+                return offset;
+            }
+        }
+        self.zone.offset_at(ts)
+    }
+
+    /// The wall-clock time of a Unix timestamp.
+    pub fn datetime(&self, ts: i64) -> Option<DateTime<FixedOffset>> {
+        DateTime::from_timestamp(ts, 0).map(|dt| dt.with_timezone(&self.offset_at(ts)))
+    }
+
+    /// Format a Unix timestamp with a `strftime`-like format string.
+    pub fn format(&self, ts: i64, fmt: &str) -> String {
+        self.datetime(ts)
+            .map(|dt| dt.format(fmt).to_string())
+            .unwrap_or_else(|| ts.to_string())
+    }
+
+    /// The current date.
+    pub fn today(&self) -> NaiveDate {
+        self.datetime(crate::now())
+            .map(|dt| dt.date_naive())
+            .unwrap_or_else(|| Local::now().date_naive())
+    }
+
+    /// The first and the last Unix timestamp at which a wall-clock time is
+    /// shown. They differ if the offset moves backwards and the time is shown
+    /// twice. If it moves forward and the time is skipped, they are the
+    /// instant of the change and the second before it.
+    fn resolve(&self, naive: &NaiveDateTime) -> Option<(i64, i64)> {
+        let guess = self.zone.timestamp(naive);
+        if self.perspective == Perspective::Viewed {
+            return guess.map(|ts| (ts, ts));
+        }
+
+        let wall = naive.and_utc().timestamp();
+        let mut offsets: Vec<i32> = self.recorded.iter().filter_map(|(_, o)| *o).collect();
+        offsets.push(self.zone.offset_at(guess.unwrap_or(wall)).local_minus_utc());
+        offsets.sort();
+        offsets.dedup();
+
+        let candidates: Vec<(i64, i32)> = offsets.iter().map(|o| (wall - *o as i64, *o)).collect();
+        let shown: Vec<i64> = candidates
+            .iter()
+            .filter(|(ts, o)| self.offset_at(*ts).local_minus_utc() == *o)
+            .map(|(ts, _)| *ts)
+            .collect();
+        if let (Some(earliest), Some(latest)) = (shown.iter().min(), shown.iter().max()) {
+            return Some((*earliest, *latest));
+        }
+
+        let lo = candidates.iter().map(|(ts, _)| *ts).min()?;
+        let hi = candidates.iter().map(|(ts, _)| *ts).max()?;
+        let i = self
+            .recorded
+            .partition_point(|(started_at, _)| *started_at <= lo);
+        self.recorded
+            .get(i)
+            .map(|(started_at, _)| *started_at)
+            .filter(|started_at| *started_at <= hi)
+            .map(|change| (change, change - 1))
+    }
+
+    /// Returns (start_of_day_ts, end_of_day_ts) for a given date.
+    pub fn day_bounds(&self, date: NaiveDate) -> (i64, i64) {
+        let start = date
+            .and_hms_opt(0, 0, 0)
+            .and_then(|dt| self.resolve(&dt))
+            .map(|(earliest, _)| earliest)
+            .unwrap_or(0);
+        let end = date
+            .and_hms_opt(23, 59, 59)
+            .and_then(|dt| self.resolve(&dt))
+            .map(|(_, latest)| latest)
+            .unwrap_or(i64::MAX);
+        (start, end)
+    }
+}
+
+static CLOCK: LazyLock<RwLock<Arc<Clock>>> = LazyLock::new(|| RwLock::new(Arc::default()));
+
+/// The clock used for showing and parsing timestamps in this process. It
+/// uses the system's local time zone until [`set_clock`] is called.
+pub fn clock() -> Arc<Clock> {
+    CLOCK.read().expect("reading the clock").clone()
+}
+
+/// Replace the clock used for showing and parsing timestamps.
+pub fn set_clock(clock: Clock) {
+    *CLOCK.write().expect("setting the clock") = Arc::new(clock);
+}
+
+/// The UTC offset (in seconds east of UTC) to record with an entry started at `ts`.
+pub fn offset_to_record(ts: i64) -> i32 {
+    clock().zone().offset_at(ts).local_minus_utc()
+}
+
+fn utc() -> FixedOffset {
+    FixedOffset::east_opt(0).expect("UTC is a valid offset")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hours(h: i32) -> Zone {
+        Zone::Fixed(FixedOffset::east_opt(h * 3600).unwrap())
+    }
+
+    #[test]
+    fn test_parse_zone() {
+        assert_eq!("local".parse::<Zone>().unwrap(), Zone::Local);
+        assert_eq!("UTC".parse::<Zone>().unwrap(), hours(0));
+        assert_eq!("+09:00".parse::<Zone>().unwrap(), hours(9));
+        assert_eq!("-05:00".parse::<Zone>().unwrap(), hours(-5));
+        assert_eq!(
+            "+0530".parse::<Zone>().unwrap(),
+            Zone::Fixed(FixedOffset::east_opt(5 * 3600 + 1800).unwrap())
+        );
+        assert!("Europe/Berlin".parse::<Zone>().is_err());
+        assert!("+25:00".parse::<Zone>().is_err());
+        assert!("+9".parse::<Zone>().is_err());
+    }
+
+    #[test]
+    fn test_parse_zone_rejects_malformed_offsets() {
+        for zone in [
+            "+0:900",
+            "+09:0",
+            "+9:00",
+            "+09:000",
+            "+090",
+            "+09",
+            "-05",
+            "+09-00",
+            "+0900:",
+            "+09::0",
+            "+09:60",
+            "+0960",
+            "+24:00",
+            "09:00",
+            "+٠٩:٠٠",
+            "+",
+        ] {
+            assert_eq!(
+                zone.parse::<Zone>().unwrap_err(),
+                format!(
+                    "invalid time zone '{}'; expected 'local', 'UTC' or an offset like +09:00 or -0530",
+                    zone
+                ),
+                "{}",
+                zone
+            );
+        }
+    }
+
+    #[test]
+    fn test_viewed_uses_zone() {
+        // 2026-03-10T02:00:00Z
+        let ts = 1_773_108_000;
+        let clock =
+            Clock::new(hours(1), Perspective::Viewed).with_recorded(vec![(ts, Some(32400))]);
+        assert_eq!(clock.format(ts, "%H:%M"), "03:00");
+    }
+
+    #[test]
+    fn test_recorded_uses_offset_of_entry() {
+        let ts = 1_773_108_000;
+        let recorded = vec![(ts - 3600, None), (ts, Some(9 * 3600))];
+        let clock = Clock::new(hours(1), Perspective::Recorded).with_recorded(recorded);
+
+        assert_eq!(clock.format(ts, "%H:%M"), "11:00");
+        assert_eq!(clock.format(ts + 1500, "%H:%M"), "11:25");
+        // Before the entry with a recorded offset, the viewer's zone applies.
+        assert_eq!(clock.format(ts - 60, "%H:%M"), "02:59");
+    }
+
+    #[test]
+    fn test_day_bounds_as_recorded() {
+        let ts = 1_773_108_000;
+        let clock =
+            Clock::new(hours(1), Perspective::Recorded).with_recorded(vec![(ts, Some(9 * 3600))]);
+        let date = NaiveDate::from_ymd_opt(2026, 3, 11).unwrap();
+
+        // 2026-03-11T00:00:00+09:00 and 2026-03-11T23:59:59+09:00
+        assert_eq!(clock.day_bounds(date), (1_773_154_800, 1_773_241_199));
+
+        let viewed = Clock::new(hours(1), Perspective::Viewed);
+        assert_eq!(viewed.day_bounds(date), (1_773_183_600, 1_773_269_999));
+    }
+
+    #[test]
+    fn test_day_bounds_when_midnight_is_skipped() {
+        // Flying east: the entry starts at 05:00 on March 11 at +09:00, while
+        // the time before it is shown at +00:00, i.e. it is 20:00 on March 10.
+        let ts = 1_773_172_800;
+        let clock =
+            Clock::new(hours(0), Perspective::Recorded).with_recorded(vec![(ts, Some(9 * 3600))]);
+        let date = NaiveDate::from_ymd_opt(2026, 3, 11).unwrap();
+
+        assert_eq!(clock.day_bounds(date).0, ts);
+        assert_eq!(clock.day_bounds(date.pred_opt().unwrap()).1, ts - 1);
+    }
+}
@@ -5,10 +5,12 @@ mod acceptance_tests {
     use tempfile::tempdir;
 
     /// Helper: create a `Command` that runs the `rustomato` binary with a clean
-    /// environment (so `RUSTOMATO_DATABASE_URL` from the host shell does not leak).
+    /// environment (so `RUSTOMATO_DATABASE_URL` or `RUSTOMATO_PROFILE` from the
+    /// host shell do not leak).
     fn rustomato() -> Command {
         let mut cmd = Command::cargo_bin("rustomato").unwrap();
         cmd.env_remove("RUSTOMATO_DATABASE_URL");
+        cmd.env_remove("RUSTOMATO_PROFILE");
         cmd
     }
 
@@ -143,7 +145,7 @@ mod acceptance_tests {
             .arg("0")
             .assert()
             .failure()
-            .code(predicate::eq(1));
+            .code(predicate::eq(4));
     }
 
     // --- break also runs hooks ----------------------------------------------
@@ -211,7 +213,7 @@ mod acceptance_tests {
             .arg("0")
             .assert()
             .failure()
-            .code(predicate::eq(1));
+            .code(predicate::eq(4));
     }
 
     // --- non-executable hook does not block ---------------------------------
@@ -283,7 +285,7 @@ mod acceptance_tests {
             .arg("interrupt")
             .assert()
             .failure()
-            .code(predicate::eq(1))
+            .code(predicate::eq(5))
             .stderr(predicate::str::contains("no entry at position -1"));
     }
 
@@ -1059,7 +1061,7 @@ mod acceptance_tests {
             .arg("cancel")
             .arg("-1")
             .assert()
-            .code(predicate::eq(1))
+            .code(predicate::eq(5))
             .stderr(predicate::str::contains("no entry at position -1"));
     }
 
@@ -1254,7 +1256,7 @@ mod acceptance_tests {
             .arg("25")
             .assert()
             .failure()
-            .code(predicate::eq(1))
+            .code(predicate::eq(6))
             .stderr(predicate::str::contains("Error"));
     }
 
@@ -1448,7 +1450,7 @@ mod acceptance_tests {
             .arg("5")
             .assert()
             .failure()
-            .code(predicate::eq(1))
+            .code(predicate::eq(6))
             .stderr(predicate::str::contains("Error"));
     }
 
@@ -2099,6 +2101,39 @@ mod acceptance_tests {
             .stdout(predicate::str::contains("Last 1 day"));
     }
 
+    #[test]
+    fn report_rejects_zero_days() {
+        let dir = tempdir().unwrap();
+        for report in ["last", "interruptions", "hours", "breaks", "chart"] {
+            rustomato()
+                .env("RUSTOMATO_ROOT", dir.path())
+                .args(["report", report, "--days", "0"])
+                .assert()
+                .code(2)
+                .stderr(predicate::str::contains("invalid value '0' for '--days"));
+        }
+    }
+
+    #[test]
+    fn report_last_active_days_follow_tz() {
+        let dir = tempdir().unwrap();
+        // May 28th and 29th at -05:00, but both on May 29th in UTC
+        log_pomodoro(dir.path(), "2026-05-28T23:30:00-05:00");
+        log_pomodoro(dir.path(), "2026-05-29T10:00:00-05:00");
+
+        let output = rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz=-05:00", "report", "--format", "json", "last"])
+            .args(["--date", "2026-05-29", "--days", "2"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["active_days"]["active"], 2);
+        assert_eq!(json["active_days"]["best_streak"], 2);
+    }
+
     #[test]
     fn report_last_defaults_to_today() {
         use chrono::Local;
@@ -2138,6 +2173,531 @@ mod acceptance_tests {
             .stdout(predicate::str::contains("completed"));
     }
 
+    #[test]
+    fn report_week_as_json() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2026-05-29T10:00:00Z");
+        log_pomodoro(dir.path(), "2026-05-22T10:00:00Z");
+
+        let output = rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "report", "week", "--date", "2026-05-29"])
+            .args(["--format", "json"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["schema_version"], 1);
+        assert_eq!(json["report"], "week");
+        assert_eq!(json["start"], "2026-05-25");
+        assert_eq!(json["days"].as_array().unwrap().len(), 7);
+        assert_eq!(json["days"][4]["date"], "2026-05-29");
+        assert_eq!(json["days"][4]["pomodori_completed"], 1);
+        assert_eq!(json["summary"]["completed"], 1);
+        assert_eq!(json["previous"]["completed"], 1);
+        assert_eq!(json["best_day"], "2026-05-29");
+        assert!(json["hints"].is_array());
+    }
+
+    #[test]
+    fn report_format_applies_to_every_report() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2026-05-29T10:00:00Z");
+
+        for report in ["day", "week", "month", "last", "interruptions"] {
+            let output = rustomato()
+                .env("RUSTOMATO_ROOT", dir.path())
+                .args(["report", "--format", "json", report, "--date", "2026-05-29"])
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "report {}", report);
+            let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+            assert_eq!(json["report"], report);
+
+            rustomato()
+                .env("RUSTOMATO_ROOT", dir.path())
+                .args(["report", report, "--date", "2026-05-29"])
+                .args(["--format", "markdown"])
+                .assert()
+                .success()
+                .stdout(predicate::str::starts_with("# "));
+        }
+    }
+
+    #[test]
+    fn report_day_as_markdown() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2026-05-29T10:00:00Z");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "report", "day", "--date", "2026-05-29"])
+            .args(["--format", "markdown"])
+            .assert()
+            .success()
+            .stdout(predicate::str::starts_with(
+                "# Report for 2026-05-29 (Friday)\n",
+            ))
+            .stdout(predicate::str::contains(
+                "| 10:00 | 10:25 | pomodoro | 25 min | finished | 0 |  |\n",
+            ))
+            .stdout(predicate::str::contains(
+                "- **Pomodori:** 1 completed · 0 cancelled · 100% completion rate\n",
+            ));
+    }
+
+    #[test]
+    fn report_week_as_html() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2026-05-25T09:00:00Z");
+        log_pomodoro(dir.path(), "2026-05-29T10:00:00Z");
+        {
+            use rustomato::persistence::Repository;
+            use rustomato::{Annotation, SqlUuid};
+            let repo = Repository::new(&dir.path().join("data.db").to_string_lossy());
+            let entry = repo.list(1).unwrap().remove(0);
+            repo.save_annotation(&Annotation {
+                uuid: SqlUuid::default(),
+                schedulable_uuid: entry.uuid,
+                body: "<b>review</b> & notes".to_string(),
+                created_at: entry.finished_at,
+            })
+            .unwrap();
+        }
+
+        let output = rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "report", "--format", "html"])
+            .args(["week", "--date", "2026-05-29"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let html = String::from_utf8(output.stdout).unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>\n"));
+        assert!(html.contains("<h1>Weekly Report: May 25 – May 31, 2026</h1>"));
+        assert!(html.contains("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(html.contains("<title>May 29 Completed: 1</title>"));
+        assert!(html.contains("<li><strong>Pomodori:</strong> 2 completed · 0 cancelled"));
+        assert!(html.contains("<h2>Interruptions</h2>"));
+        assert!(html.contains(
+            "<tr><td>Fri May 29</td><td>10:00</td><td>10:25</td><td>pomodoro</td>\
+             <td>25 min</td><td>finished</td><td>0</td>\
+             <td>&lt;b&gt;review&lt;/b&gt; &amp; notes</td></tr>"
+        ));
+        // No external assets
+        assert!(!html.contains("src="));
+        assert!(!html.contains("href="));
+        assert!(html.ends_with("</html>\n"));
+    }
+
+    #[test]
+    fn report_year_heatmap() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2026-01-01T10:00:00Z");
+        log_pomodoro(dir.path(), "2026-01-05T10:00:00Z");
+        log_pomodoro(dir.path(), "2026-01-05T11:00:00Z");
+        log_pomodoro(dir.path(), "2025-06-02T10:00:00Z");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "report", "year", "--year", "2026"])
+            .args(["--color", "never"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Year Report: 2026\n(vs 2025)\n"))
+            // Jan 1st is a Thursday; Jan 5th, the following Monday, was busiest
+            .stdout(predicate::str::contains("\nMon  █·"))
+            .stdout(predicate::str::contains("\n    ▒··"))
+            .stdout(predicate::str::contains("  Jan        3      0"))
+            .stdout(predicate::str::contains(
+                "Year over year: 3 completed vs 1 in 2025 (+200%)",
+            ))
+            .stdout(predicate::str::contains("Best week: Jan 5 (2 completed)"))
+            .stdout(predicate::str::contains("\x1b[").not());
+    }
+
+    #[test]
+    fn report_year_as_html_has_no_ansi_colours() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2026-01-05T10:00:00Z");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "report", "--format", "html", "year"])
+            .args(["--year", "2026", "--color", "always"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Year Report: 2026"))
+            .stdout(predicate::str::contains("\x1b[").not());
+    }
+
+    #[test]
+    fn report_year_rejects_year_out_of_range() {
+        let dir = tempdir().unwrap();
+        for year in ["0", "10000", "300000"] {
+            rustomato()
+                .env("RUSTOMATO_ROOT", dir.path())
+                .args(["report", "year", "--year", year])
+                .assert()
+                .code(2)
+                .stderr(predicate::str::contains(format!(
+                    "invalid value '{}' for '--year",
+                    year
+                )));
+        }
+    }
+
+    #[test]
+    fn report_year_as_json() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2024-02-29T10:00:00Z");
+
+        let output = rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "report", "--format", "json"])
+            .args(["year", "--year", "2024", "--color", "always"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["report"], "year");
+        assert_eq!(json["year"], 2024);
+        assert_eq!(json["days"].as_array().unwrap().len(), 366);
+        assert_eq!(json["days"][59]["date"], "2024-02-29");
+        assert_eq!(json["days"][59]["pomodori_completed"], 1);
+        assert_eq!(json["months"].as_array().unwrap().len(), 12);
+        assert_eq!(json["months"][1]["completed"], 1);
+        assert_eq!(json["active_days"]["active"], 1);
+        assert_eq!(json["best_week"]["week_start"], "2024-02-26");
+        assert!(json.get("colour").is_none());
+    }
+
+    #[test]
+    fn report_year_active_days_agree_with_heatmap() {
+        let dir = tempdir().unwrap();
+        // Jan 1st and 2nd at +09:00, but Dec 31st and Jan 1st in UTC
+        log_pomodoro(dir.path(), "2026-01-01T08:00:00+09:00");
+        log_pomodoro(dir.path(), "2026-01-02T07:00:00+09:00");
+
+        let output = rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "+09:00", "report", "--format", "json"])
+            .args(["year", "--year", "2026"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let heatmap_days = json["days"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|d| d["pomodori_completed"].as_u64().unwrap() > 0)
+            .count();
+        assert_eq!(heatmap_days, 2);
+        assert_eq!(json["months"][0]["completed"], 2);
+        assert_eq!(json["active_days"]["active"], 2);
+        assert_eq!(json["active_days"]["best_streak"], 2);
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "+09:00", "report", "year", "--year", "2026"])
+            .args(["--color", "never"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Best streak: 2 days"));
+    }
+
+    #[test]
+    fn report_range() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2026-05-04T10:00:00Z");
+        log_pomodoro(dir.path(), "2026-05-15T10:00:00Z");
+        log_pomodoro(dir.path(), "2026-05-16T10:00:00Z");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "report", "range"])
+            .args(["--from", "2026-05-04", "--to", "2026-05-15"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("May 04 – May 15, 2026 (12 days)"))
+            .stdout(predicate::str::contains("2 completed"))
+            .stdout(predicate::str::contains("May 16").not());
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args([
+                "report",
+                "range",
+                "--from",
+                "2026-05-15",
+                "--to",
+                "2026-05-04",
+            ])
+            .assert()
+            .failure()
+            .stderr(predicate::eq(
+                "Error: the period ends on 2026-05-04 before it starts on 2026-05-15.\n",
+            ));
+    }
+
+    #[test]
+    fn report_compare() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2026-05-04T10:00:00Z");
+        log_pomodoro(dir.path(), "2026-05-15T10:00:00Z");
+        log_pomodoro(dir.path(), "2026-05-18T10:00:00Z");
+        log_pomodoro(dir.path(), "2026-06-03T10:00:00Z");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "report", "compare"])
+            .args(["2026-05-04..2026-05-17", "2026-05-18..2026-05-31"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "A: May 04 – May 17, 2026 (14 days)",
+            ))
+            .stdout(predicate::str::is_match(r"\n  Completed +2 +1 +-1 +-50%\n").unwrap());
+
+        let output = rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "report", "--format", "json"])
+            .args(["compare", "2026-05", "2026-06-03"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["report"], "compare");
+        assert_eq!(json["a"]["end"], "2026-05-31");
+        assert_eq!(json["b"]["start"], "2026-06-03");
+        assert_eq!(json["changes"][0]["metric"], "completed");
+        assert_eq!(json["changes"][0]["a"], 3.0);
+        assert_eq!(json["changes"][0]["b"], 1.0);
+        assert_eq!(json["changes"][0]["delta"], -2.0);
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["report", "compare", "sprint-14", "2026-05"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("invalid period 'sprint-14'"));
+    }
+
+    #[test]
+    fn report_hours() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2026-05-25T09:00:00Z");
+        log_pomodoro(dir.path(), "2026-05-26T09:30:00Z");
+        log_pomodoro(dir.path(), "2026-05-26T10:00:00Z");
+        log_pomodoro(dir.path(), "2026-05-27T15:00:00Z");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "report", "hours", "--date", "2026-05-29"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "  09:00       2      0    100%  ★\n",
+            ))
+            .stdout(predicate::str::contains("  Tue    1  1  ·  ·  ·  ·  ·\n"))
+            .stdout(predicate::str::contains("Your best focus window is 09–11"));
+
+        let output = rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "report", "--format", "json"])
+            .args(["hours", "--date", "2026-05-29", "--days", "3"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["report"], "hours");
+        assert_eq!(json["start"], "2026-05-27");
+        assert_eq!(json["by_hour"].as_array().unwrap().len(), 1);
+        assert_eq!(json["by_hour"][0]["hour"], 15);
+        assert_eq!(json["by_weekday_hour"][0]["weekday"], "Wed");
+        assert_eq!(json["best_window"]["start"], 14);
+    }
+
+    #[test]
+    fn report_breaks() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2026-05-29T09:00:00Z");
+        log_pomodoro(dir.path(), "2026-05-29T09:30:00Z");
+        log_pomodoro(dir.path(), "2026-05-29T10:00:00Z");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "report", "breaks", "--date", "2026-05-29"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "Break after pomodoro:  0 of 2 within 5 min",
+            ))
+            .stdout(predicate::str::contains("Adherence score:       0%"));
+
+        let output = rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "report", "--format", "json", "breaks"])
+            .args(["--date", "2026-05-29", "--days", "1", "--within", "10"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["report"], "breaks");
+        assert_eq!(json["within"], 10);
+        assert_eq!(json["days"][0]["pomodori"], 3);
+        assert_eq!(json["summary"]["followed_by_break"], 0);
+        assert!(json["summary"].get("breaks_missed").is_none());
+    }
+
+    #[test]
+    fn report_day_timeline() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2026-05-29T09:00:00Z");
+        log_pomodoro(dir.path(), "2026-05-29T10:00:00Z");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .env("COLUMNS", "31")
+            .args(["--tz", "UTC", "report", "day", "--date", "2026-05-29"])
+            .arg("--timeline")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "       09          10\nFri 29 █████·······█████·······\n",
+            ))
+            .stdout(predicate::str::contains("(5 min each)"));
+    }
+
+    #[test]
+    fn report_week_timeline_stacks_days() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2026-05-25T09:00:00Z");
+        log_pomodoro(dir.path(), "2026-05-29T10:00:00Z");
+
+        let output = rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .env("COLUMNS", "31")
+            .args(["--tz", "UTC", "report", "week", "--date", "2026-05-29"])
+            .arg("--timeline")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let rows: Vec<&str> = stdout
+            .lines()
+            .filter(|l| l.starts_with(['M', 'T', 'W', 'F', 'S']) && l.contains('·'))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                "Mon 25 █████···················",
+                "Tue 26 ························",
+                "Wed 27 ························",
+                "Thu 28 ························",
+                "Fri 29 ············█████·······",
+                "Sat 30 ························",
+                "Sun 31 ························",
+            ]
+        );
+
+        // In JSON, the timeline lists the entries of every day
+        let output = rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["report", "--format", "json", "week", "--timeline"])
+            .args(["--date", "2026-05-29"])
+            .output()
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let timeline = json["timeline"].as_array().unwrap();
+        assert_eq!(timeline.len(), 7);
+        assert_eq!(timeline[4]["date"], "2026-05-29");
+        assert_eq!(timeline[4]["entries"][0]["kind"], "pomodoro");
+        assert_eq!(timeline[4]["entries"][0]["status"], "finished");
+        assert!(timeline[3]["entries"].as_array().unwrap().is_empty());
+
+        let output = rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["report", "--format", "json", "week"])
+            .args(["--date", "2026-05-29"])
+            .output()
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert!(json.get("timeline").is_none());
+    }
+
+    #[test]
+    fn report_chart_writes_svg() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2026-05-25T09:00:00Z");
+        log_pomodoro(dir.path(), "2026-05-29T09:00:00Z");
+        log_pomodoro(dir.path(), "2026-05-29T09:30:00Z");
+        let out = dir.path().join("chart.svg");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "report", "chart", "--kind", "daily"])
+            .args(["--date", "2026-05-29", "--days", "7", "--out"])
+            .arg(&out)
+            .assert()
+            .success()
+            .stdout(predicate::str::is_empty());
+
+        let svg = std::fs::read_to_string(&out).unwrap();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.contains("Pomodori per day – May 23 – May 29, 2026"));
+        assert!(svg.contains("<title>May 25 Completed: 1</title>"));
+        assert!(svg.contains("<title>May 29 Completed: 2</title>"));
+
+        // Without --out, the same chart goes to stdout
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "report", "chart", "--kind", "daily"])
+            .args(["--date", "2026-05-29", "--days", "7"])
+            .assert()
+            .success()
+            .stdout(svg);
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "report", "chart", "--kind", "weekly"])
+            .args(["--date", "2026-05-29"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("<polyline points="));
+    }
+
+    #[test]
+    fn report_chart_rejects_format() {
+        let dir = tempdir().unwrap();
+        for format in ["json", "markdown", "html"] {
+            rustomato()
+                .env("RUSTOMATO_ROOT", dir.path())
+                .args(["report", "--format", format, "chart"])
+                .assert()
+                .code(2)
+                .stdout(predicate::str::is_empty())
+                .stderr(predicate::str::contains("--format cannot be used"));
+        }
+    }
+
+    #[test]
+    fn report_chart_to_unwritable_path_fails() {
+        let dir = tempdir().unwrap();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["report", "chart", "--out"])
+            .arg(dir.path().join("missing").join("chart.svg"))
+            .assert()
+            .code(9)
+            .stderr(predicate::str::starts_with("Error: cannot write"));
+    }
+
     // --- missing RUSTOMATO_ROOT directory -----------------------------------
 
     #[test]
@@ -2165,4 +2725,1257 @@ mod acceptance_tests {
             "database should have been created"
         );
     }
+
+    // --- backup / restore ---------------------------------------------------
+
+    /// Helper: log a pomodoro with `--no-hooks` starting at the given time.
+    fn log_pomodoro(root: &std::path::Path, started_at: &str) {
+        rustomato()
+            .env("RUSTOMATO_ROOT", root)
+            .arg("--no-hooks")
+            .arg("pomodoro")
+            .arg("log")
+            .arg("--started-at")
+            .arg(started_at)
+            .assert()
+            .success();
+    }
+
+    #[test]
+    fn backup_to_explicit_path() {
+        let dir = tempdir().unwrap();
+        let target = dir.path().join("copy.db");
+        log_pomodoro(dir.path(), "2026-05-29T10:00:00Z");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .arg("backup")
+            .arg(&target)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Backed up to"));
+
+        assert!(target.is_file());
+    }
+
+    #[test]
+    fn backup_keep_rotates_backups_directory() {
+        let dir = tempdir().unwrap();
+        let backups = dir.path().join("backups");
+        std::fs::create_dir_all(&backups).unwrap();
+        for name in ["data-20200101T000000Z.db", "data-20200102T000000Z.db"] {
+            std::fs::write(backups.join(name), "").unwrap();
+        }
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .arg("backup")
+            .arg("--keep")
+            .arg("2")
+            .assert()
+            .success();
+
+        let mut names: Vec<_> = std::fs::read_dir(&backups)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(names.len(), 2);
+        assert_eq!(names[0], "data-20200102T000000Z.db");
+    }
+
+    #[test]
+    fn backup_keep_zero_fails() {
+        let dir = tempdir().unwrap();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .arg("backup")
+            .arg("--keep")
+            .arg("0")
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("--keep must be > 0"));
+    }
+
+    #[test]
+    fn restore_brings_back_backed_up_entries() {
+        let dir = tempdir().unwrap();
+        let target = dir.path().join("copy.db");
+        log_pomodoro(dir.path(), "2026-05-29T10:00:00Z");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .arg("backup")
+            .arg(&target)
+            .assert()
+            .success();
+
+        log_pomodoro(dir.path(), "2026-05-29T11:00:00Z");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .arg("restore")
+            .arg(&target)
+            .assert()
+            .success();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .arg("list")
+            .arg("--no-header")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("pomodoro").count(1));
+    }
+
+    #[test]
+    fn restore_missing_file_fails() {
+        let dir = tempdir().unwrap();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .arg("restore")
+            .arg(dir.path().join("nope.db"))
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Cannot restore"));
+    }
+
+    #[test]
+    fn doctor_healthy_database() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2026-05-29T10:00:00Z");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .arg("doctor")
+            .assert()
+            .success()
+            .stdout("No problems found.\n");
+    }
+
+    #[test]
+    fn doctor_json_healthy_database() {
+        let dir = tempdir().unwrap();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["doctor", "--format", "json"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("\"healthy\": true"));
+    }
+
+    #[test]
+    fn doctor_fixes_non_executable_hook() {
+        let dir = tempdir().unwrap();
+        let hooks = dir.path().join("hooks");
+        std::fs::create_dir_all(&hooks).unwrap();
+        let hook = hooks.join("after-finish-pomodoro");
+        std::fs::write(&hook, "#!/bin/sh\necho done\n").unwrap();
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .arg("doctor")
+            .assert()
+            .failure()
+            .stdout(predicate::str::contains("1 problem found"))
+            .stdout(predicate::str::contains("doctor --fix"));
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["doctor", "--fix"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("(fixed)"));
+
+        assert_ne!(hook.metadata().unwrap().permissions().mode() & 0o111, 0);
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .arg("doctor")
+            .assert()
+            .success();
+    }
+
+    #[test]
+    fn doctor_cannot_fix_unknown_hook() {
+        let dir = tempdir().unwrap();
+        let hooks = dir.path().join("hooks");
+        std::fs::create_dir_all(&hooks).unwrap();
+        std::fs::write(hooks.join("after-finish-pomodoro.sh"), "#!/bin/sh\n").unwrap();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["doctor", "--fix"])
+            .assert()
+            .failure()
+            .stdout(predicate::str::contains("after-finish-pomodoro.sh"));
+    }
+
+    #[test]
+    fn merge_imports_entries_from_other_database() {
+        let desktop = tempdir().unwrap();
+        let laptop = tempdir().unwrap();
+        log_pomodoro(desktop.path(), "2026-05-29T10:00:00Z");
+        log_pomodoro(laptop.path(), "2026-05-29T11:00:00Z");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", desktop.path())
+            .arg("merge")
+            .arg(laptop.path().join("data.db"))
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("1 entry added"))
+            .stdout(predicate::str::contains("0 duplicates skipped"));
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", desktop.path())
+            .arg("list")
+            .arg("--no-header")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("pomodoro").count(2));
+    }
+
+    #[test]
+    fn merge_interactive_takes_other_side() {
+        let desktop = tempdir().unwrap();
+        let laptop = tempdir().unwrap();
+        log_pomodoro(desktop.path(), "2026-05-29T10:00:00Z");
+        log_pomodoro(laptop.path(), "2026-05-29T10:10:00Z");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", desktop.path())
+            .arg("merge")
+            .arg(laptop.path().join("data.db"))
+            .args(["--policy", "interactive"])
+            .write_stdin("x\no\n")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("conflicts with local"))
+            .stdout(predicate::str::contains("1 taken from the other database"));
+    }
+
+    #[test]
+    fn merge_missing_file_fails() {
+        let dir = tempdir().unwrap();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .arg("merge")
+            .arg(dir.path().join("nope.db"))
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("does not exist"));
+    }
+
+    #[test]
+    fn profile_list_shows_default_only() {
+        let dir = tempdir().unwrap();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["profile", "list"])
+            .assert()
+            .success()
+            .stdout("* default\n");
+    }
+
+    #[test]
+    fn profile_create_and_list() {
+        let dir = tempdir().unwrap();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["profile", "create", "work"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Created profile work"));
+
+        assert!(dir.path().join("profiles/work/hooks").is_dir());
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--profile", "work", "profile", "list"])
+            .assert()
+            .success()
+            .stdout("  default\n* work\n");
+    }
+
+    #[test]
+    fn profile_create_invalid_name_fails() {
+        let dir = tempdir().unwrap();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["profile", "create", "../evil"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("invalid profile name"));
+    }
+
+    #[test]
+    fn profiles_have_separate_databases() {
+        let dir = tempdir().unwrap();
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["profile", "create", "work"])
+            .assert()
+            .success();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .env("RUSTOMATO_PROFILE", "work")
+            .args(["--no-hooks", "pomodoro", "log", "--started-at"])
+            .arg("2026-05-29T10:00:00Z")
+            .assert()
+            .success();
+
+        assert!(dir.path().join("profiles/work/data.db").is_file());
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--profile", "work", "list", "--no-header"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("pomodoro").count(1));
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["list", "--no-header"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("pomodoro").count(0));
+    }
+
+    #[test]
+    fn unknown_profile_fails() {
+        let dir = tempdir().unwrap();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--profile", "nope", "status"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("profile 'nope' does not exist"));
+    }
+
+    #[test]
+    fn profile_default_switches_profile() {
+        let dir = tempdir().unwrap();
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["profile", "create", "work"])
+            .assert()
+            .success();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["profile", "default", "work"])
+            .assert()
+            .success();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["profile", "default"])
+            .assert()
+            .success()
+            .stdout("work\n");
+
+        log_pomodoro(dir.path(), "2026-05-29T10:00:00Z");
+        assert!(dir.path().join("profiles/work/data.db").is_file());
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--profile", "default", "list", "--no-header"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("pomodoro").count(0));
+    }
+
+    #[test]
+    fn explicit_profile_wins_over_database_url() {
+        let dir = tempdir().unwrap();
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["profile", "create", "work"])
+            .assert()
+            .success();
+        let elsewhere = dir.path().join("elsewhere.db");
+        let url = url::Url::from_file_path(&elsewhere).unwrap();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .env("RUSTOMATO_DATABASE_URL", url.as_str())
+            .args(["--no-hooks", "--profile", "work", "pomodoro", "log"])
+            .args(["--started-at", "2026-05-29T10:00:00Z"])
+            .assert()
+            .success();
+
+        assert!(!elsewhere.exists());
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--profile", "work", "list", "--no-header"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("pomodoro"));
+    }
+
+    #[test]
+    fn report_all_profiles_aggregates() {
+        let dir = tempdir().unwrap();
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["profile", "create", "work"])
+            .assert()
+            .success();
+
+        log_pomodoro(dir.path(), "2026-05-29T08:00:00");
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--profile", "work", "--no-hooks", "pomodoro", "log"])
+            .args(["--started-at", "2026-05-29T10:00:00"])
+            .assert()
+            .success();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["report", "day", "--date", "2026-05-29"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Pomodori    1 completed"));
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["report", "--all-profiles", "day", "--date", "2026-05-29"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Pomodori    2 completed"));
+    }
+
+    // --- archive --------------------------------------------------------------
+
+    #[test]
+    fn archive_and_report_with_archive() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2025-05-29T10:00:00");
+        log_pomodoro(dir.path(), "2026-05-29T10:00:00");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["archive", "--before", "2026-01-01"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Archived 1 entry, 0 annotations"))
+            .stdout(predicate::str::contains("archive.db"));
+        assert!(dir.path().join("archive.db").is_file());
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["report", "day", "--date", "2025-05-29"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Nothing recorded"));
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["report", "--include-archive", "day", "--date", "2025-05-29"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("1 completed"));
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["report", "day", "--date", "2026-05-29"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("1 completed"));
+    }
+
+    #[test]
+    fn archive_rejects_invalid_date() {
+        let dir = tempdir().unwrap();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["archive", "--before", "last year"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("invalid --before date"));
+    }
+
+    // --- export formats -------------------------------------------------------
+
+    #[test]
+    fn export_json_and_ndjson() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2026-05-29T10:00:00Z");
+        log_pomodoro(dir.path(), "2026-05-29T11:00:00Z");
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["pomodoro", "annotate", "a \"quoted\" note"])
+            .assert()
+            .success();
+
+        let output = rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["export", "--format", "json"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["schema_version"], 1);
+        assert_eq!(json["entries"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            json["entries"][1]["annotations"][0]["body"],
+            "a \"quoted\" note"
+        );
+
+        let output = rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["export", "--format", "ndjson"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(stdout.lines().count(), 2);
+        for line in stdout.lines() {
+            let entry: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(entry["status"], "finished");
+        }
+    }
+
+    #[test]
+    fn export_ics_honours_date_filters() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2026-05-28T10:00:00Z");
+        log_pomodoro(dir.path(), "2026-05-29T10:00:00Z");
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["pomodoro", "annotate", "retro; notes"])
+            .assert()
+            .success();
+
+        let output = rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "export", "--format", "ics"])
+            .args(["--from", "2026-05-29", "--to", "2026-05-29"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let ics = String::from_utf8(output.stdout).unwrap();
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+        assert!(ics.contains("DTSTART:20260529T100000Z\r\n"));
+        assert!(ics.contains("DTEND:20260529T102500Z\r\n"));
+        assert!(ics.contains("DESCRIPTION:retro\\; notes\r\n"));
+    }
+
+    #[test]
+    fn export_filters_and_csv_dialect() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2026-05-29T10:00:00Z");
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["pomodoro", "annotate", "Reviewed the parser"])
+            .assert()
+            .success();
+        log_pomodoro(dir.path(), "2026-05-29T11:00:00Z");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args([
+                "export",
+                "--grep",
+                "PARSER",
+                "--status",
+                "finished,cancelled",
+            ])
+            .args(["--columns", "started_at,status", "--delimiter", ";"])
+            .args(["--timestamps", "utc"])
+            .assert()
+            .success()
+            .stdout("started_at;status\n2026-05-29T10:00:00Z;finished\n");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["export", "--kind", "break", "--no-header"])
+            .assert()
+            .success()
+            .stdout("");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["export", "--min-interruptions", "1", "--format", "ndjson"])
+            .assert()
+            .success()
+            .stdout("");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["export", "--columns", "uuid,project"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("unknown column 'project'"));
+    }
+
+    #[test]
+    fn export_interrupts_and_annotations_tables() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2026-05-29T10:00:00Z");
+        for args in [
+            vec!["pomodoro", "interrupt", "--kind", "internal"],
+            vec!["pomodoro", "annotate", "phone, again"],
+        ] {
+            rustomato()
+                .env("RUSTOMATO_ROOT", dir.path())
+                .arg("--no-hooks")
+                .args(args)
+                .assert()
+                .success();
+        }
+
+        let output = rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["export", "--table", "interrupts"])
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(
+            lines[0],
+            "uuid,schedulable_uuid,schedulable_kind,kind,created_at"
+        );
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].contains(",pomodoro,internal,"));
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args([
+                "export",
+                "--table",
+                "annotations",
+                "--columns",
+                "schedulable_kind,body",
+            ])
+            .assert()
+            .success()
+            .stdout("schedulable_kind,body\npomodoro,\"phone, again\"\n");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["export", "--table", "interrupts", "--format", "json"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("only available as CSV"));
+    }
+
+    #[test]
+    fn export_bundle_writes_all_tables() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2026-05-29T10:00:00Z");
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["pomodoro", "annotate", "done"])
+            .assert()
+            .success();
+        let bundle = dir.path().join("bundle");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["export", "--delimiter", "tab", "--bundle"])
+            .arg(&bundle)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("schedulables.csv"));
+
+        let schedulables = std::fs::read_to_string(bundle.join("schedulables.csv")).unwrap();
+        assert!(schedulables.starts_with("uuid\tkind\t"));
+        assert_eq!(schedulables.lines().count(), 2);
+        let interrupts = std::fs::read_to_string(bundle.join("interrupts.csv")).unwrap();
+        assert_eq!(interrupts.lines().count(), 1);
+        let annotations = std::fs::read_to_string(bundle.join("annotations.csv")).unwrap();
+        assert!(annotations.ends_with("\tdone\n"));
+    }
+
+    #[test]
+    fn export_rejects_csv_options_with_other_formats() {
+        let dir = tempdir().unwrap();
+        for (format, option) in [
+            ("json", ["--delimiter", ";"].as_slice()),
+            ("ndjson", ["--no-header"].as_slice()),
+            ("ics", ["--columns", "uuid"].as_slice()),
+            ("org", ["--timestamps", "utc"].as_slice()),
+        ] {
+            rustomato()
+                .env("RUSTOMATO_ROOT", dir.path())
+                .args(["export", "--format", format])
+                .args(option)
+                .assert()
+                .code(2)
+                .stderr(predicate::str::contains(format!(
+                    "{} only applies to --format csv",
+                    option[0]
+                )));
+        }
+    }
+
+    #[test]
+    fn export_org_groups_by_day() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2026-05-28T10:00:00Z");
+        log_pomodoro(dir.path(), "2026-05-29T10:00:00Z");
+        log_pomodoro(dir.path(), "2026-05-29T11:00:00Z");
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["pomodoro", "annotate", "wrote tests"])
+            .assert()
+            .success();
+
+        let output = rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "export", "--format", "org"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let org = String::from_utf8(output.stdout).unwrap();
+
+        assert!(org.contains("* [2026-05-28 Thu]\n** DONE Pomodoro\n"));
+        assert!(org.contains("* [2026-05-29 Fri]\n** DONE Pomodoro\n"));
+        assert_eq!(org.matches("** DONE Pomodoro").count(), 3);
+        assert!(org.contains(":INTERRUPTIONS: 0\n"));
+        assert!(
+            org.contains("CLOCK: [2026-05-29 Fri 11:00]--[2026-05-29 Fri 11:25] =>  0:25\n- [")
+        );
+        assert!(org.ends_with("] wrote tests\n"));
+    }
+
+    #[test]
+    fn export_timeclock_with_account_template() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2026-05-29T10:00:00Z");
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["pomodoro", "annotate", "#rustomato wrote tests"])
+            .assert()
+            .success();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "export", "--format", "timeclock"])
+            .args(["--account", "focus:{tag}"])
+            .assert()
+            .success()
+            .stdout(
+                "i 2026/05/29 10:00:00 focus:rustomato  #rustomato wrote tests\n\
+                 o 2026/05/29 10:25:00\n",
+            );
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["export", "--format", "timeclock", "--account", "{client}"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("unknown placeholder '{client}'"));
+    }
+
+    // --- import ---------------------------------------------------------------
+
+    fn export_csv(root: &std::path::Path) -> String {
+        let output = rustomato()
+            .env("RUSTOMATO_ROOT", root)
+            .args(["export"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    #[test]
+    fn import_round_trips_export() {
+        let source = tempdir().unwrap();
+        log_pomodoro(source.path(), "2026-05-29T10:00:00Z");
+        log_pomodoro(source.path(), "2026-05-29T11:00:00Z");
+        rustomato()
+            .env("RUSTOMATO_ROOT", source.path())
+            .args(["pomodoro", "annotate", "a \"quoted\", multi\nline note"])
+            .assert()
+            .success();
+        let exported = export_csv(source.path());
+        let file = source.path().join("export.csv");
+        std::fs::write(&file, &exported).unwrap();
+
+        let target = tempdir().unwrap();
+        rustomato()
+            .env("RUSTOMATO_ROOT", target.path())
+            .arg("import")
+            .arg(&file)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("2 entries added"))
+            .stdout(predicate::str::contains("1 annotation added"));
+        assert_eq!(export_csv(target.path()), exported);
+
+        // Importing again only finds duplicates
+        rustomato()
+            .env("RUSTOMATO_ROOT", target.path())
+            .arg("import")
+            .arg(&file)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("0 entries added"))
+            .stdout(predicate::str::contains("2 duplicates skipped"));
+    }
+
+    #[test]
+    fn imported_interruptions_survive_doctor_fix() {
+        let dir = tempdir().unwrap();
+
+        // Interrupt logging started before the imported entry
+        {
+            use rustomato::persistence::Repository;
+            use rustomato::{InterruptLog, InterruptionKind, Kind, Schedulable, SqlUuid};
+            let repo = Repository::new(&dir.path().join("data.db").to_string_lossy());
+            let mut pom = Schedulable::new(0, Kind::Pomodoro, 25);
+            pom.started_at = 1000;
+            pom.finished_at = 2500;
+            let pom = repo.save_external_finished(&pom).unwrap();
+            repo.interrupt(&InterruptLog {
+                uuid: SqlUuid::default(),
+                schedulable_uuid: pom.uuid,
+                kind: InterruptionKind::External,
+                created_at: 1100,
+            })
+            .unwrap();
+        }
+
+        let file = dir.path().join("import.csv");
+        std::fs::write(
+            &file,
+            "uuid,kind,planned_duration,started_at,finished_at,cancelled_at,status,interruptions,elapsed_min,annotations\n\
+             3153c561121a4806ab424d26262a975f,pomodoro,25,2026-05-29T10:00:00+00:00,2026-05-29T10:25:00+00:00,,finished,2,25,\n",
+        )
+        .unwrap();
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .arg("import")
+            .arg(&file)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("2 interrupts added"));
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["doctor", "--fix"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("No problems found"));
+
+        assert!(export_csv(dir.path()).contains("2026-05-29T10:25:00+00:00,,finished,2,25,"));
+    }
+
+    #[test]
+    fn import_dry_run_reports_conflicts() {
+        let source = tempdir().unwrap();
+        log_pomodoro(source.path(), "2026-05-29T10:00:00Z");
+        log_pomodoro(source.path(), "2026-05-29T11:00:00Z");
+        let file = source.path().join("export.csv");
+        std::fs::write(&file, export_csv(source.path())).unwrap();
+
+        let target = tempdir().unwrap();
+        log_pomodoro(target.path(), "2026-05-29T10:10:00Z");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", target.path())
+            .arg("import")
+            .arg("--dry-run")
+            .arg(&file)
+            .assert()
+            .code(6)
+            .stderr(predicate::str::contains("line 2:"))
+            .stderr(predicate::str::contains("Rule #1"))
+            .stdout(predicate::str::contains("1 entry added"))
+            .stdout(predicate::str::contains("Dry run; nothing was imported."));
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", target.path())
+            .arg("import")
+            .arg(&file)
+            .assert()
+            .code(6)
+            .stderr(predicate::str::contains("nothing was imported"));
+        assert_eq!(export_csv(target.path()).lines().count(), 2);
+    }
+
+    #[test]
+    fn import_rejects_unknown_header() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("other.csv");
+        std::fs::write(&file, "start,end\n").unwrap();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .arg("import")
+            .arg(&file)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("unexpected header"));
+    }
+
+    #[test]
+    fn import_from_timewarrior_skips_duplicates() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("2026-05.data");
+        std::fs::write(
+            &file,
+            "inc 20260529T100000Z - 20260529T102500Z # rustomato # \"fixed the tests\"\n\
+             inc 20260529T102500Z - 20260529T103000Z # break\n\
+             inc 20260529T110000Z # rustomato\n",
+        )
+        .unwrap();
+
+        for expected in ["2 entries added", "2 duplicates skipped"] {
+            rustomato()
+                .env("RUSTOMATO_ROOT", dir.path())
+                .args(["import", "--from", "timewarrior"])
+                .arg(&file)
+                .assert()
+                .success()
+                .stdout(predicate::str::contains(expected))
+                .stdout(predicate::str::contains("1 running entry skipped"));
+        }
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "report", "day", "--date", "2026-05-29"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("1 completed"));
+        let exported = export_csv(dir.path());
+        assert!(exported.contains("rustomato: fixed the tests"));
+        assert!(exported.contains(",break,5,"));
+    }
+
+    #[test]
+    fn import_from_generic_csv_applies_rule_one() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2026-05-29T10:10:00Z");
+        let file = dir.path().join("times.csv");
+        std::fs::write(
+            &file,
+            "start,end,kind,note\n2026-05-29T10:00:00Z,2026-05-29T10:25:00Z,pomodoro,review\n",
+        )
+        .unwrap();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["import", "--from", "csv"])
+            .arg(&file)
+            .assert()
+            .code(6)
+            .stderr(predicate::str::contains("Rule #1"));
+    }
+
+    // --- metrics --------------------------------------------------------------
+
+    #[test]
+    fn metrics_writes_prometheus_textfile() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2026-05-29T10:00:00Z");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .arg("metrics")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "# TYPE rustomato_pomodori_completed_total counter\n\
+                 rustomato_pomodori_completed_total 1\n",
+            ))
+            .stdout(predicate::str::contains(
+                "rustomato_state{state=\"idle\"} 1\n",
+            ));
+
+        let file = dir.path().join("textfile").join("rustomato.prom");
+        std::fs::create_dir(file.parent().unwrap()).unwrap();
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["metrics", "--output"])
+            .arg(&file)
+            .assert()
+            .success()
+            .stdout(predicate::str::is_empty());
+        let metrics = std::fs::read_to_string(&file).unwrap();
+        assert!(metrics.contains("rustomato_remaining_seconds 0\n"));
+        assert_eq!(
+            std::fs::read_dir(file.parent().unwrap()).unwrap().count(),
+            1
+        );
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["metrics", "--output"])
+            .arg(dir.path().join("missing").join("rustomato.prom"))
+            .assert()
+            .code(9)
+            .stderr(predicate::str::starts_with("Error: cannot write"));
+    }
+
+    #[test]
+    fn metrics_totals_include_archive() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2025-05-29T10:00:00Z");
+        log_pomodoro(dir.path(), "2026-05-29T10:00:00Z");
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["archive", "--before", "2026-01-01"])
+            .assert()
+            .success();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .arg("metrics")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(
+                "rustomato_pomodori_completed_total 2\n",
+            ));
+    }
+
+    // --- exit codes -----------------------------------------------------------
+
+    #[test]
+    fn start_while_running_exits_with_already_running_code() {
+        let dir = tempdir().unwrap();
+        let repo =
+            rustomato::persistence::Repository::new(&dir.path().join("data.db").to_string_lossy());
+        let mut running =
+            rustomato::Schedulable::new(std::process::id(), rustomato::Kind::Pomodoro, 25);
+        running.started_at = rustomato::now();
+        repo.save(&running).unwrap();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--no-hooks", "pomodoro", "start"])
+            .assert()
+            .code(predicate::eq(3))
+            .stderr(predicate::str::contains("already running"));
+    }
+
+    #[test]
+    fn invalid_duration_exits_with_constraint_violation_code() {
+        let dir = tempdir().unwrap();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--no-hooks", "pomodoro", "start", "--duration", "0"])
+            .assert()
+            .code(predicate::eq(7))
+            .stderr(predicate::str::contains("CHECK constraint failed"));
+    }
+
+    #[test]
+    fn show_unknown_entry_exits_with_not_found_code() {
+        let dir = tempdir().unwrap();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["show", "deadbeef"])
+            .assert()
+            .code(predicate::eq(5));
+    }
+
+    // --- concurrency ----------------------------------------------------------
+
+    /// Run `args` in `n` parallel processes against the same root and return
+    /// whether each succeeded.
+    fn in_parallel(
+        root: &std::path::Path,
+        n: usize,
+        args: impl Fn(usize) -> Vec<String>,
+    ) -> Vec<bool> {
+        let handles: Vec<_> = (0..n)
+            .map(|i| {
+                let mut cmd = rustomato();
+                cmd.env("RUSTOMATO_ROOT", root).args(args(i));
+                std::thread::spawn(move || cmd.output().unwrap().status.success())
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    }
+
+    #[test]
+    fn parallel_logs_all_succeed() {
+        let dir = tempdir().unwrap();
+
+        let results = in_parallel(dir.path(), 8, |i| {
+            vec![
+                "--no-hooks".to_string(),
+                "pomodoro".to_string(),
+                "log".to_string(),
+                "--started-at".to_string(),
+                format!("2026-05-29T{:02}:00:00Z", 8 + i),
+            ]
+        });
+
+        assert!(results.iter().all(|ok| *ok), "{:?}", results);
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["list", "--no-header"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("pomodoro").count(8));
+    }
+
+    #[test]
+    fn parallel_interrupts_are_all_counted_and_logged() {
+        let dir = tempdir().unwrap();
+        log_pomodoro(dir.path(), "2026-05-29T10:00:00Z");
+
+        let results = in_parallel(dir.path(), 8, |_| {
+            ["--no-hooks", "pomodoro", "interrupt", "-1"]
+                .map(String::from)
+                .to_vec()
+        });
+
+        assert!(results.iter().all(|ok| *ok), "{:?}", results);
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["list", "--no-header"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("8 interruptions"));
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .arg("doctor")
+            .assert()
+            .success();
+    }
+
+    #[test]
+    fn hook_can_call_rustomato_recursively() {
+        let dir = tempdir().unwrap();
+        let hooks = dir.path().join("hooks");
+        std::fs::create_dir_all(&hooks).unwrap();
+        let hook = hooks.join("after-log-pomodoro");
+        std::fs::write(
+            &hook,
+            format!(
+                "#!/usr/bin/env sh\nexec '{}' pomodoro annotate from the hook\n",
+                assert_cmd::cargo::cargo_bin("rustomato").display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["pomodoro", "log", "--started-at", "2026-05-29T10:00:00Z"])
+            .assert()
+            .success();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["show", "--", "-1"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("from the hook"));
+    }
+
+    // --- time zones -----------------------------------------------------------
+
+    fn log_pomodoro_in(root: &std::path::Path, tz: &str, started_at: &str) {
+        rustomato()
+            .env("RUSTOMATO_ROOT", root)
+            .args(["--no-hooks", "--tz", tz, "pomodoro", "log"])
+            .args(["--started-at", started_at])
+            .assert()
+            .success();
+    }
+
+    #[test]
+    fn export_as_viewed_and_as_recorded() {
+        let dir = tempdir().unwrap();
+        log_pomodoro_in(dir.path(), "+09:00", "2026-05-29T10:00:00Z");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "export"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("2026-05-29T10:00:00+00:00"));
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "export", "--as", "recorded"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("2026-05-29T19:00:00+09:00"))
+            .stdout(predicate::str::contains("2026-05-29T19:25:00+09:00"));
+    }
+
+    #[test]
+    fn report_day_as_recorded() {
+        let dir = tempdir().unwrap();
+        // 05:00 on May 30 in Tokyo, but still May 29 in UTC
+        log_pomodoro_in(dir.path(), "+09:00", "2026-05-29T20:00:00Z");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "report", "day", "--date", "2026-05-29"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("1 completed"))
+            .stdout(predicate::str::contains("20:00"));
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "report", "--as", "recorded", "day"])
+            .args(["--date", "2026-05-30"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("1 completed"))
+            .stdout(predicate::str::contains("05:00"));
+    }
+
+    #[test]
+    fn tz_interprets_times_without_offset() {
+        let dir = tempdir().unwrap();
+        log_pomodoro_in(dir.path(), "+09:00", "2026-05-29T19:00:00");
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "UTC", "export"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("2026-05-29T10:00:00+00:00"));
+    }
+
+    #[test]
+    fn invalid_tz_is_a_usage_error() {
+        let dir = tempdir().unwrap();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "Mars/Olympus", "list"])
+            .assert()
+            .code(2)
+            .stderr(predicate::str::contains("invalid time zone"));
+    }
+
+    #[test]
+    fn malformed_tz_offset_is_a_usage_error() {
+        let dir = tempdir().unwrap();
+
+        rustomato()
+            .env("RUSTOMATO_ROOT", dir.path())
+            .args(["--tz", "+0:900", "list"])
+            .assert()
+            .code(2)
+            .stderr(predicate::str::contains("invalid time zone '+0:900'"));
+    }
 }
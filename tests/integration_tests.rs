@@ -1,5 +1,6 @@
 mod integration_tests {
     use assert_matches::assert_matches;
+    use rustomato::merge::{Conflict, Resolution, Summary, merge, prefer_local, prefer_newer};
     use rustomato::persistence::{PersistenceError, Repository};
     use rustomato::{Annotation, Kind, Schedulable, SqlUuid};
 
@@ -458,7 +459,7 @@ mod integration_tests {
 
         match result {
             Ok(_) => panic!("Should have been covered above"),
-            Err(e) => assert_matches!(e, PersistenceError::CannotUpdate(msg) => {
+            Err(e) => assert_matches!(e, PersistenceError::ConstraintViolation(msg) => {
                 assert!(msg.starts_with("CHECK constraint failed"));
             }),
         }
@@ -714,6 +715,25 @@ mod integration_tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn save_external_finished_keeps_utc_offset() {
+        let repo = Repository::new("file::memory:");
+        let mut pom = Schedulable::new(0, Kind::Pomodoro, 25);
+        pom.started_at = 1000;
+        pom.finished_at = 2500;
+        pom.utc_offset = Some(9 * 3600);
+        repo.save_external_finished(&pom).unwrap();
+
+        let unrecorded = finished_pomodoro(&repo, 5000);
+
+        assert_eq!(repo.find_by_uuid(pom.uuid).unwrap().utc_offset, Some(32400));
+        assert_eq!(unrecorded.utc_offset, None);
+        assert_eq!(
+            repo.recorded_offsets().unwrap(),
+            vec![(1000, Some(32400)), (5000, None)]
+        );
+    }
+
     // --- entries_between -----------------------------------------------------
 
     #[test]
@@ -971,6 +991,917 @@ mod integration_tests {
             .expect("querying count");
         assert_eq!(count, 4);
     }
+
+    #[test]
+    fn consecutive_pomodoro_count_at_ignores_later_entries() {
+        let repo = Repository::new("file::memory:");
+
+        for i in 0..3 {
+            let mut pom = Schedulable::new(42, Kind::Pomodoro, 25);
+            pom.started_at = 1000 + i * 2000;
+            repo.save(&pom).expect("saving active");
+            pom.finished_at = 2000 + i * 2000;
+            repo.save(&pom).expect("finishing");
+        }
+
+        let mut brk = Schedulable::new(0, Kind::Break, 15);
+        brk.started_at = 7000;
+        brk.finished_at = 8000;
+        repo.save_external_finished(&brk)
+            .expect("saving long break");
+
+        // Neither the third pomodoro nor the long break had finished yet
+        let count = repo
+            .consecutive_pomodoro_count_at(5000)
+            .expect("querying count");
+        assert_eq!(count, 2);
+    }
+
+    // --- backup / restore -----------------------------------------------------
+
+    #[test]
+    fn backup_and_restore_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let backup = dir.path().join("backup.db");
+
+        let repo = Repository::new(&dir.path().join("data.db").to_string_lossy());
+        let mut pom = Schedulable::new(0, Kind::Pomodoro, 25);
+        pom.started_at = 1000;
+        pom.finished_at = 2500;
+        let saved = repo.save_external_finished(&pom).expect("seeding pomodoro");
+        repo.backup_to(&backup).expect("backing up");
+
+        let mut other = Repository::new(&dir.path().join("other.db").to_string_lossy());
+        other.restore_from(&backup).expect("restoring");
+
+        let restored = other.find_by_uuid(saved.uuid).expect("finding restored");
+        assert_eq!(restored.finished_at, 2500);
+    }
+
+    #[test]
+    fn restore_missing_file_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut repo = Repository::new("file::memory:");
+
+        let result = repo.restore_from(&dir.path().join("nope.db"));
+        assert_matches!(result, Err(PersistenceError::CannotRestore(_)));
+    }
+
+    #[test]
+    fn restore_rejects_non_rustomato_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let foreign = dir.path().join("foreign.db");
+        rusqlite::Connection::open(&foreign)
+            .unwrap()
+            .execute_batch("CREATE TABLE t (x INTEGER);")
+            .unwrap();
+
+        let mut repo = Repository::new("file::memory:");
+        let result = repo.restore_from(&foreign);
+        assert_matches!(result, Err(PersistenceError::CannotRestore(_)));
+    }
+
+    #[test]
+    fn restore_rejects_newer_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let newer = dir.path().join("newer.db");
+        {
+            let repo = Repository::new(&newer.to_string_lossy());
+            drop(repo);
+            rusqlite::Connection::open(&newer)
+                .unwrap()
+                .execute_batch("INSERT INTO _migrations (name) VALUES ('V999__from_the_future');")
+                .unwrap();
+        }
+
+        let mut repo = Repository::new("file::memory:");
+        let result = repo.restore_from(&newer);
+        assert_matches!(result, Err(PersistenceError::CannotRestore(msg)) if msg.contains("V999__from_the_future"));
+    }
+
+    #[test]
+    fn restore_refuses_while_active() {
+        let dir = tempfile::tempdir().unwrap();
+        let backup = dir.path().join("backup.db");
+        Repository::new(&backup.to_string_lossy());
+
+        let mut repo = Repository::new("file::memory:");
+        let own_pid = std::process::id();
+        let mut pom = Schedulable::new(own_pid, Kind::Pomodoro, 25);
+        pom.started_at = 1000;
+        repo.save(&pom).expect("saving active pomodoro");
+
+        let result = repo.restore_from(&backup);
+        assert_eq!(result, Err(PersistenceError::AlreadyRunning(own_pid)));
+    }
+
+    // --- doctor ---------------------------------------------------------------
+
+    /// Insert rows with foreign key enforcement off, as older versions could.
+    fn raw_exec(path: &std::path::Path, sql: &str) {
+        let conn = rusqlite::Connection::open(path).unwrap();
+        conn.pragma_update(None, "foreign_keys", false).unwrap();
+        conn.execute_batch(sql).unwrap();
+    }
+
+    fn finished_pomodoro(repo: &Repository, started_at: i64) -> Schedulable {
+        let mut pom = Schedulable::new(0, Kind::Pomodoro, 25);
+        pom.started_at = started_at;
+        pom.finished_at = started_at + 1500;
+        repo.save_external_finished(&pom).expect("seeding pomodoro")
+    }
+
+    #[test]
+    fn interrupt_count_mismatch_detected_after_logging_started() {
+        let repo = Repository::new("file::memory:");
+        let first = finished_pomodoro(&repo, 1000);
+        repo.record_interrupt(first.uuid).unwrap();
+        repo.save_interrupt(&rustomato::InterruptLog {
+            uuid: SqlUuid::default(),
+            schedulable_uuid: first.uuid,
+            kind: rustomato::InterruptionKind::Internal,
+            created_at: 1100,
+        })
+        .unwrap();
+
+        // Counter incremented, but the log insert never happened
+        let second = finished_pomodoro(&repo, 5000);
+        repo.record_interrupt(second.uuid).unwrap();
+
+        let mismatches = repo.interrupt_count_mismatches().unwrap();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].0.uuid.to_string(), second.uuid.to_string());
+        assert_eq!(mismatches[0].1, 0);
+    }
+
+    #[test]
+    fn interrupt_counter_from_before_the_log_is_not_a_mismatch() {
+        let repo = Repository::new("file::memory:");
+        let legacy = finished_pomodoro(&repo, 1000);
+        repo.record_interrupt(legacy.uuid).unwrap();
+
+        assert!(repo.interrupt_count_mismatches().unwrap().is_empty());
+    }
+
+    #[test]
+    fn doctor_fix_repairs_orphans_and_counters() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("data.db");
+        let repo = Repository::new(&db.to_string_lossy());
+        let pom = finished_pomodoro(&repo, 1000);
+        raw_exec(
+            &db,
+            &format!(
+                "INSERT INTO annotations VALUES ('{a}', '{missing}', 'lost', 1);
+                 INSERT INTO interrupt_log VALUES ('{i}', '{pom}', 'external', 1200);",
+                a = SqlUuid::default(),
+                missing = SqlUuid::default(),
+                i = SqlUuid::default(),
+                pom = pom.uuid,
+            ),
+        );
+
+        let findings = rustomato::doctor::run(&repo, dir.path(), true).unwrap();
+        assert_eq!(findings.len(), 2);
+        assert!(findings.iter().all(|f| f.fixed));
+
+        assert!(
+            rustomato::doctor::diagnose(&repo, dir.path())
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(repo.find_by_uuid(pom.uuid).unwrap().interruptions, 1);
+    }
+
+    #[test]
+    fn doctor_reports_empty_time_range() {
+        let repo = Repository::new("file::memory:");
+        let mut pom = Schedulable::new(0, Kind::Pomodoro, 25);
+        pom.started_at = 1000;
+        pom.finished_at = 1000;
+        repo.save_external_finished(&pom).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let problems = rustomato::doctor::diagnose(&repo, dir.path()).unwrap();
+        assert_matches!(
+            problems.as_slice(),
+            [rustomato::doctor::Problem::EmptyTimeRange {
+                elapsed_secs: 0,
+                ..
+            }]
+        );
+    }
+
+    #[test]
+    fn doctor_fix_keeps_empty_time_range() {
+        let repo = Repository::new("file::memory:");
+        let mut pom = Schedulable::new(0, Kind::Pomodoro, 25);
+        pom.started_at = 1000;
+        pom.finished_at = 1000;
+        repo.save_external_finished(&pom).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let findings = rustomato::doctor::run(&repo, dir.path(), true).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert!(!findings[0].fixable);
+        assert!(!findings[0].fixed);
+        assert!(repo.find_by_uuid(pom.uuid).is_ok());
+    }
+
+    #[test]
+    fn doctor_fix_closes_stale_entry() {
+        let repo = Repository::new("file::memory:");
+        let mut pom = Schedulable::new(u32::MAX - 1, Kind::Pomodoro, 25);
+        pom.started_at = 1000;
+        repo.save(&pom).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let findings = rustomato::doctor::run(&repo, dir.path(), true).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].fixed);
+
+        assert!(repo.active().unwrap().is_none());
+        assert_eq!(repo.find_by_uuid(pom.uuid).unwrap().cancelled_at, 2500);
+    }
+
+    // --- merge ----------------------------------------------------------------
+
+    fn annotate(repo: &Repository, s: &Schedulable, body: &str) -> Annotation {
+        repo.save_annotation(&Annotation {
+            uuid: SqlUuid::default(),
+            schedulable_uuid: s.uuid,
+            body: body.to_string(),
+            created_at: s.finished_at,
+        })
+        .expect("saving annotation")
+    }
+
+    #[test]
+    fn merge_adds_new_entries_with_annotations() {
+        let local = Repository::new("file::memory:");
+        let other = Repository::new("file::memory:");
+        finished_pomodoro(&local, 1000);
+        let pom = finished_pomodoro(&other, 5000);
+        annotate(&other, &pom, "laptop");
+
+        let summary = merge(&local, &other, &mut prefer_local).unwrap();
+
+        assert_eq!(summary.added, 1);
+        assert_eq!(summary.annotations_added, 1);
+        assert_eq!(local.list(10).unwrap().len(), 2);
+        assert_eq!(local.annotations_for(pom.uuid).unwrap()[0].body, "laptop");
+    }
+
+    #[test]
+    fn merge_twice_adds_nothing() {
+        let local = Repository::new("file::memory:");
+        let other = Repository::new("file::memory:");
+        let pom = finished_pomodoro(&other, 5000);
+        annotate(&other, &pom, "laptop");
+
+        merge(&local, &other, &mut prefer_local).unwrap();
+        let summary = merge(&local, &other, &mut prefer_local).unwrap();
+
+        assert_eq!(
+            summary,
+            Summary {
+                duplicates: 1,
+                ..Summary::default()
+            }
+        );
+    }
+
+    #[test]
+    fn merge_again_adds_annotations_of_duplicates() {
+        let local = Repository::new("file::memory:");
+        let other = Repository::new("file::memory:");
+        let pom = finished_pomodoro(&other, 5000);
+        merge(&local, &other, &mut prefer_local).unwrap();
+
+        annotate(&other, &pom, "added later");
+        let summary = merge(&local, &other, &mut prefer_local).unwrap();
+
+        assert_eq!(summary.duplicates, 1);
+        assert_eq!(summary.annotations_added, 1);
+        assert_eq!(summary.annotations_dropped, 0);
+        assert_eq!(
+            local.annotations_for(pom.uuid).unwrap()[0].body,
+            "added later"
+        );
+    }
+
+    #[test]
+    fn merge_resolves_conflicts_without_locking_the_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.db").to_string_lossy().to_string();
+        let local = Repository::new(&path);
+        let other = Repository::new("file::memory:");
+        finished_pomodoro(&local, 1000);
+        finished_pomodoro(&other, 1600);
+
+        let summary = merge(&local, &other, &mut |_: &Conflict| {
+            // Another process, e.g. a hook, writes while the user decides
+            finished_pomodoro(&Repository::new(&path), 10_000);
+            Resolution::KeepLocal
+        })
+        .unwrap();
+
+        assert_eq!(summary.kept_local, 1);
+        assert_eq!(local.list(10).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn merge_prefer_local_keeps_overlapping_local_entry() {
+        let local = Repository::new("file::memory:");
+        let other = Repository::new("file::memory:");
+        let mine = finished_pomodoro(&local, 1000);
+        let theirs = finished_pomodoro(&other, 1600);
+        annotate(&other, &theirs, "lost");
+
+        let summary = merge(&local, &other, &mut prefer_local).unwrap();
+
+        assert_eq!(summary.kept_local, 1);
+        assert_eq!(summary.added, 0);
+        assert_eq!(summary.annotations_dropped, 1);
+        let entries = local.list(10).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].uuid.to_string(), mine.uuid.to_string());
+    }
+
+    fn interrupt(repo: &Repository, s: &Schedulable) -> rustomato::InterruptLog {
+        repo.interrupt(&rustomato::InterruptLog {
+            uuid: SqlUuid::default(),
+            schedulable_uuid: s.uuid,
+            kind: rustomato::InterruptionKind::External,
+            created_at: s.started_at + 60,
+        })
+        .expect("saving interrupt");
+        repo.interrupts_for(s.uuid).unwrap().pop().unwrap()
+    }
+
+    #[test]
+    fn merge_adds_interrupts_with_matching_counter() {
+        let local = Repository::new("file::memory:");
+        let other = Repository::new("file::memory:");
+        let pom = finished_pomodoro(&other, 5000);
+        interrupt(&other, &pom);
+        interrupt(&other, &pom);
+
+        let summary = merge(&local, &other, &mut prefer_local).unwrap();
+
+        assert_eq!(summary.interrupts_added, 2);
+        assert_eq!(local.find_by_uuid(pom.uuid).unwrap().interruptions, 2);
+        assert_eq!(local.interrupts_for(pom.uuid).unwrap().len(), 2);
+        let dir = tempfile::tempdir().unwrap();
+        assert!(
+            rustomato::doctor::diagnose(&local, dir.path())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn merge_keep_local_leaves_local_entry_alone() {
+        let local = Repository::new("file::memory:");
+        let other = Repository::new("file::memory:");
+        let pom = finished_pomodoro(&local, 1000);
+        let mut diverged = pom.clone();
+        diverged.finished_at += 300;
+        other.save_external_ended(&diverged).unwrap();
+        annotate(&other, &diverged, "theirs");
+        interrupt(&other, &diverged);
+
+        let summary = merge(&local, &other, &mut prefer_local).unwrap();
+
+        assert_eq!(summary.kept_local, 1);
+        assert_eq!(summary.annotations_dropped, 1);
+        assert_eq!(summary.interrupts_dropped, 1);
+        let kept = local.find_by_uuid(pom.uuid).unwrap();
+        assert_eq!(kept.finished_at, pom.finished_at);
+        assert_eq!(kept.interruptions, 0);
+        assert!(local.annotations_for(pom.uuid).unwrap().is_empty());
+        assert!(local.interrupts_for(pom.uuid).unwrap().is_empty());
+    }
+
+    #[test]
+    fn merge_rolls_back_on_error() {
+        let local = Repository::new("file::memory:");
+        let other = Repository::new("file::memory:");
+        finished_pomodoro(&other, 1000);
+        let pom = finished_pomodoro(&other, 10_000);
+        let clash = interrupt(&other, &pom);
+        let elsewhere = finished_pomodoro(&local, 20_000);
+        local
+            .save_interrupt(&rustomato::InterruptLog {
+                schedulable_uuid: elsewhere.uuid,
+                ..clash
+            })
+            .unwrap();
+
+        assert!(merge(&local, &other, &mut prefer_local).is_err());
+        assert_eq!(local.list(10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn merge_prefer_newer_replaces_older_local_entry() {
+        let local = Repository::new("file::memory:");
+        let other = Repository::new("file::memory:");
+        finished_pomodoro(&local, 1000);
+        let theirs = finished_pomodoro(&other, 1600);
+
+        let summary = merge(&local, &other, &mut prefer_newer).unwrap();
+
+        assert_eq!(summary.took_other, 1);
+        assert_eq!(summary.replaced, 1);
+        let entries = local.list(10).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].uuid.to_string(), theirs.uuid.to_string());
+    }
+
+    #[test]
+    fn merge_resolves_diverged_entry_with_same_uuid() {
+        let local = Repository::new("file::memory:");
+        let other = Repository::new("file::memory:");
+        let pom = finished_pomodoro(&local, 1000);
+        let mut longer = pom.clone();
+        longer.finished_at += 300;
+        longer.duration = 30;
+        other.save_external_ended(&longer).unwrap();
+
+        let mut seen = 0;
+        let summary = merge(&local, &other, &mut |c: &Conflict| {
+            seen = c.local.len();
+            Resolution::TakeOther
+        })
+        .unwrap();
+
+        assert_eq!(seen, 1);
+        assert_eq!(summary.took_other, 1);
+        assert_eq!(local.find_by_uuid(pom.uuid).unwrap().duration, 30);
+    }
+
+    #[test]
+    fn merge_skips_running_entries() {
+        let local = Repository::new("file::memory:");
+        let other = Repository::new("file::memory:");
+        let mut running = Schedulable::new(u32::MAX - 1, Kind::Pomodoro, 25);
+        running.started_at = 1000;
+        other.save(&running).unwrap();
+
+        let summary = merge(&local, &other, &mut prefer_local).unwrap();
+
+        assert_eq!(summary.running, 1);
+        assert!(local.list(10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn merge_keeps_cancelled_entries_cancelled() {
+        let local = Repository::new("file::memory:");
+        let other = Repository::new("file::memory:");
+        let mut pom = Schedulable::new(0, Kind::Pomodoro, 25);
+        pom.started_at = 1000;
+        pom.cancelled_at = 1300;
+        other.save_external_ended(&pom).unwrap();
+
+        merge(&local, &other, &mut prefer_local).unwrap();
+
+        let merged = local.find_by_uuid(pom.uuid).unwrap();
+        assert_eq!(merged.cancelled_at, 1300);
+        assert_eq!(merged.finished_at, 0);
+    }
+
+    #[test]
+    fn snapshot_leaves_file_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("other.db");
+        finished_pomodoro(&Repository::new(&path.to_string_lossy()), 1000);
+        let before = std::fs::read(&path).unwrap();
+
+        let snapshot = Repository::snapshot(&path).unwrap();
+        finished_pomodoro(&snapshot, 5000);
+
+        assert_eq!(snapshot.list(10).unwrap().len(), 2);
+        assert_eq!(std::fs::read(&path).unwrap(), before);
+    }
+
+    #[test]
+    fn combined_includes_overlapping_entries_from_all_databases() {
+        let dir = tempfile::tempdir().unwrap();
+        let work = dir.path().join("work.db");
+        let personal = dir.path().join("personal.db");
+        let work_repo = Repository::new(&work.to_string_lossy());
+        let personal_repo = Repository::new(&personal.to_string_lossy());
+        finished_pomodoro(&work_repo, 1000);
+        finished_pomodoro(&personal_repo, 1600);
+        let shared = finished_pomodoro(&work_repo, 10_000);
+        personal_repo.save_external_ended(&shared).unwrap();
+
+        let combined = Repository::combined(&[work, personal]).unwrap();
+
+        assert_eq!(combined.entries_between(0, i64::MAX).unwrap().len(), 3);
+    }
+
+    // --- archive --------------------------------------------------------------
+
+    #[test]
+    fn archive_moves_ended_entries_with_annotations_and_interrupts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = rustomato::archive::path(dir.path());
+        let repo = Repository::new("file::memory:");
+        let old = finished_pomodoro(&repo, 1000);
+        annotate(&repo, &old, "old");
+        repo.interrupt(&rustomato::InterruptLog {
+            uuid: SqlUuid::default(),
+            schedulable_uuid: old.uuid,
+            kind: rustomato::InterruptionKind::External,
+            created_at: 1100,
+        })
+        .unwrap();
+        let recent = finished_pomodoro(&repo, 5000);
+
+        let summary = rustomato::archive::archive(&repo, &path, 5000).unwrap();
+        assert_eq!(
+            summary,
+            rustomato::archive::Summary {
+                entries: 1,
+                annotations: 1,
+                interrupts: 1,
+            }
+        );
+
+        assert!(repo.find_by_uuid(old.uuid).is_err());
+        assert!(repo.find_by_uuid(recent.uuid).is_ok());
+        assert!(repo.annotations_for(old.uuid).unwrap().is_empty());
+
+        let archive = Repository::new(&path.to_string_lossy());
+        assert_eq!(archive.find_by_uuid(old.uuid).unwrap().interruptions, 1);
+        assert_eq!(archive.annotations_for(old.uuid).unwrap()[0].body, "old");
+        assert_eq!(archive.interrupts_for(old.uuid).unwrap().len(), 1);
+
+        // Archiving again moves nothing
+        let again = rustomato::archive::archive(&repo, &path, 5000).unwrap();
+        assert_eq!(again, rustomato::archive::Summary::default());
+    }
+
+    #[test]
+    fn archive_keeps_running_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = rustomato::archive::path(dir.path());
+        let repo = Repository::new("file::memory:");
+        let mut pom = Schedulable::new(std::process::id(), Kind::Pomodoro, 25);
+        pom.started_at = 1000;
+        repo.save(&pom).unwrap();
+
+        let summary = rustomato::archive::archive(&repo, &path, 5000).unwrap();
+        assert_eq!(summary.entries, 0);
+        assert!(repo.active().unwrap().is_some());
+    }
+
+    #[test]
+    fn included_archive_is_visible_to_queries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = rustomato::archive::path(dir.path());
+        let repo = Repository::new(&dir.path().join("data.db").to_string_lossy());
+        let old = finished_pomodoro(&repo, 1000);
+        annotate(&repo, &old, "old");
+        finished_pomodoro(&repo, 5000);
+        rustomato::archive::archive(&repo, &path, 5000).unwrap();
+
+        let reader = Repository::new(&dir.path().join("data.db").to_string_lossy());
+        assert_eq!(reader.entries_between(0, 10000).unwrap().len(), 1);
+
+        rustomato::archive::include(&reader, &path).unwrap();
+        assert_eq!(reader.entries_between(0, 10000).unwrap().len(), 2);
+        assert_eq!(reader.annotations_between(0, 10000).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn archive_from_newer_version_is_neither_migrated_nor_included() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = rustomato::archive::path(dir.path());
+        Repository::new(&path.to_string_lossy());
+        rusqlite::Connection::open(&path)
+            .unwrap()
+            .execute("INSERT INTO _migrations (name) VALUES ('V999__future')", [])
+            .unwrap();
+        let before = std::fs::read(&path).unwrap();
+
+        let repo = Repository::new("file::memory:");
+        finished_pomodoro(&repo, 1000);
+        assert!(rustomato::archive::include(&repo, &path).is_err());
+        assert!(rustomato::archive::archive(&repo, &path, 5000).is_err());
+
+        assert_eq!(std::fs::read(&path).unwrap(), before);
+        assert_eq!(repo.entries_between(0, 10000).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn including_a_missing_archive_does_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::new("file::memory:");
+        finished_pomodoro(&repo, 1000);
+
+        rustomato::archive::include(&repo, &rustomato::archive::path(dir.path())).unwrap();
+        assert_eq!(repo.entries_between(0, 10000).unwrap().len(), 1);
+    }
+
+    // --- import ---------------------------------------------------------------
+
+    fn record(line: usize, started_at: i64) -> rustomato::import::Record {
+        let mut entry = Schedulable::new(0, Kind::Pomodoro, 25);
+        entry.started_at = started_at;
+        entry.finished_at = started_at + 1500;
+        rustomato::import::Record {
+            line,
+            entry,
+            annotations: vec![],
+        }
+    }
+
+    #[test]
+    fn import_keeps_uuids_and_adds_annotations() {
+        let repo = Repository::new("file::memory:");
+        let mut first = record(2, 1000);
+        first.annotations.push(Annotation {
+            uuid: SqlUuid::default(),
+            schedulable_uuid: first.entry.uuid,
+            body: "imported".to_string(),
+            created_at: 2500,
+        });
+        let records = vec![first, record(3, 5000)];
+
+        let summary = rustomato::import::import(&repo, &records, false).unwrap();
+        assert_eq!(summary.added, 2);
+        assert_eq!(summary.annotations_added, 1);
+        assert!(summary.conflicts.is_empty());
+
+        let annotations = repo.annotations_for(records[0].entry.uuid).unwrap();
+        assert_eq!(
+            annotations[0].uuid.to_string(),
+            records[0].annotations[0].uuid.to_string()
+        );
+        assert!(repo.find_by_uuid(records[1].entry.uuid).is_ok());
+    }
+
+    #[test]
+    fn import_with_conflicts_imports_nothing() {
+        let repo = Repository::new("file::memory:");
+        // The second record overlaps the first one
+        let records = vec![record(2, 1000), record(3, 2000), record(4, 5000)];
+
+        let summary = rustomato::import::import(&repo, &records, false).unwrap();
+        assert_eq!(summary.conflicts.len(), 1);
+        assert_eq!(summary.conflicts[0].line, 3);
+        assert!(
+            summary.conflicts[0]
+                .reason
+                .contains(&records[0].entry.uuid.to_string())
+        );
+        assert!(repo.entries_between(0, 10000).unwrap().is_empty());
+    }
+
+    #[test]
+    fn import_dry_run_changes_nothing() {
+        let repo = Repository::new("file::memory:");
+        let records = vec![record(2, 1000)];
+
+        let summary = rustomato::import::import(&repo, &records, true).unwrap();
+        assert_eq!(summary.added, 1);
+        assert!(repo.entries_between(0, 10000).unwrap().is_empty());
+    }
+
+    // --- report ---------------------------------------------------------------
+
+    #[test]
+    fn week_report_is_computed_without_printing() {
+        let repo = Repository::new("file::memory:");
+        let monday = chrono::NaiveDate::from_ymd_opt(2026, 5, 25).unwrap();
+        let (start, _) = rustomato::timezone::clock().day_bounds(monday);
+        finished_pomodoro(&repo, start + 3600);
+        finished_pomodoro(&repo, start + 7200);
+
+        let report = rustomato::report::week_report(&repo, monday).unwrap();
+
+        assert_eq!(report.start, monday);
+        assert_eq!(report.days.len(), 7);
+        assert_eq!(report.days[0].pomodori_completed, 2);
+        assert_eq!(report.summary.completed, 2);
+        assert_eq!(report.summary.max_focus_block, 2);
+        assert_eq!(report.best_day, Some(monday));
+    }
+
+    #[test]
+    fn breaks_report_checks_each_pomodoro_against_the_technique() {
+        let repo = Repository::new("file::memory:");
+        let monday = chrono::NaiveDate::from_ymd_opt(2026, 5, 25).unwrap();
+        let (start, _) = rustomato::timezone::clock().day_bounds(monday);
+        let at = |h: i64, m: i64| start + h * 3600 + m * 60;
+        let brk = |started_at: i64, duration: i64, ended_at: i64, cancelled: bool| {
+            let mut brk = Schedulable::new(0, Kind::Break, duration);
+            brk.started_at = started_at;
+            if cancelled {
+                brk.cancelled_at = ended_at;
+            } else {
+                brk.finished_at = ended_at;
+            }
+            repo.save_external_ended(&brk).expect("seeding break");
+        };
+
+        finished_pomodoro(&repo, at(9, 0));
+        brk(at(9, 26), 5, at(9, 31), false);
+        finished_pomodoro(&repo, at(9, 33));
+        brk(at(10, 0), 5, at(10, 2), true); // cut short
+        finished_pomodoro(&repo, at(10, 5));
+        brk(at(10, 31), 5, at(10, 36), false);
+        finished_pomodoro(&repo, at(10, 40));
+        brk(at(11, 6), 5, at(11, 11), false); // should have been a long one
+        finished_pomodoro(&repo, at(11, 30)); // after the break ran over
+        // The last pomodoro of a session needs no break
+        finished_pomodoro(&repo, at(14, 0));
+
+        let report = rustomato::report::breaks_report(&repo, monday, 1, 5).unwrap();
+        let day = &report.days[0].adherence;
+
+        assert_eq!(day.pomodori, 6);
+        assert_eq!(day.followed_by_anything, 4);
+        assert_eq!(day.followed_by_break, 4);
+        assert_eq!(day.long_breaks_due, 1);
+        assert_eq!(day.long_breaks_taken, 0);
+        assert_eq!(day.breaks, 4);
+        assert_eq!(day.cut_short, 1);
+        assert_eq!(day.ran_over, 1);
+        assert_eq!(day.average_gap_before, Some(1.25));
+        assert_eq!(day.average_gap_after, Some(7.0));
+        // 4 breaks after pomodori, 0 of 1 long breaks, 2 of 4 breaks as planned
+        assert_eq!(day.score, Some(66));
+        assert_eq!(report.summary.score, Some(66));
+        assert_eq!(report.previous.score, None);
+    }
+
+    // --- metrics --------------------------------------------------------------
+
+    #[test]
+    fn metrics_of_running_pomodoro() {
+        let repo = Repository::new("file::memory:");
+        let now = 1_780_050_000;
+        finished_pomodoro(&repo, now - 3600);
+        let mut pom = Schedulable::new(std::process::id(), Kind::Pomodoro, 25);
+        pom.started_at = now - 600;
+        repo.save(&pom).unwrap();
+
+        let metrics = rustomato::metrics::collect(&repo, now).unwrap().to_string();
+
+        assert!(metrics.contains("rustomato_state{state=\"active\"} 1\n"));
+        assert!(metrics.contains("rustomato_state{state=\"idle\"} 0\n"));
+        assert!(metrics.contains("rustomato_remaining_seconds 900\n"));
+        assert!(metrics.contains("rustomato_pomodori_completed_total 1\n"));
+    }
+
+    // --- exit codes -----------------------------------------------------------
+
+    #[test]
+    fn exit_codes_are_distinct_per_cause() {
+        use rustomato::exit_code;
+
+        let cases = [
+            (
+                PersistenceError::AlreadyRunning(1),
+                exit_code::ALREADY_RUNNING,
+            ),
+            (
+                PersistenceError::CannotFind(String::new()),
+                exit_code::NOT_FOUND,
+            ),
+            (PersistenceError::OverlappingTimeRange, exit_code::OVERLAP),
+            (
+                PersistenceError::ConstraintViolation(String::new()),
+                exit_code::CONSTRAINT_VIOLATION,
+            ),
+            (
+                PersistenceError::Locked(String::new()),
+                exit_code::DATABASE_LOCKED,
+            ),
+            (PersistenceError::Io(String::new()), exit_code::IO),
+        ];
+        for (error, code) in cases {
+            assert_eq!(error.exit_code(), code, "{:?}", error);
+        }
+
+        let mut codes: Vec<i32> = [
+            exit_code::SUCCESS,
+            exit_code::ERROR,
+            exit_code::USAGE,
+            exit_code::ALREADY_RUNNING,
+            exit_code::HOOK_REJECTED,
+            exit_code::NOT_FOUND,
+            exit_code::OVERLAP,
+            exit_code::CONSTRAINT_VIOLATION,
+            exit_code::DATABASE_LOCKED,
+            exit_code::IO,
+        ]
+        .to_vec();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), 10);
+    }
+
+    #[test]
+    fn scheduling_error_keeps_persistence_cause() {
+        use rustomato::scheduling::SchedulingError;
+
+        let error = SchedulingError::from(PersistenceError::OverlappingTimeRange);
+        assert_eq!(
+            error,
+            SchedulingError::Persistence(PersistenceError::OverlappingTimeRange)
+        );
+        assert_eq!(error.exit_code(), rustomato::exit_code::OVERLAP);
+
+        assert_eq!(
+            SchedulingError::from(PersistenceError::AlreadyRunning(42)),
+            SchedulingError::AlreadyRunning(42)
+        );
+    }
+
+    #[test]
+    fn save_active_with_invalid_duration_is_constraint_violation() {
+        let repo = Repository::new("file::memory:");
+        let mut pom = Schedulable::new(4711, Kind::Pomodoro, 0);
+        pom.started_at = 12;
+
+        assert_matches!(
+            repo.save(&pom),
+            Err(PersistenceError::ConstraintViolation(_))
+        );
+    }
+
+    // --- concurrency ----------------------------------------------------------
+
+    #[test]
+    fn file_database_uses_write_ahead_logging() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.db");
+        Repository::new(&path.to_string_lossy());
+
+        let mode: String = rusqlite::Connection::open(&path)
+            .unwrap()
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(mode, "wal");
+    }
+
+    #[test]
+    fn interrupt_updates_counter_and_log_together() {
+        let repo = Repository::new("file::memory:");
+        let pom = finished_pomodoro(&repo, 1000);
+        let log = rustomato::InterruptLog {
+            uuid: SqlUuid::default(),
+            schedulable_uuid: pom.uuid,
+            kind: rustomato::InterruptionKind::Internal,
+            created_at: 1100,
+        };
+
+        assert_eq!(repo.interrupt(&log).unwrap().interruptions, 1);
+
+        // Saving the same log entry again fails, and so must the counter update
+        assert!(repo.interrupt(&log).is_err());
+        assert_eq!(repo.find_by_uuid(pom.uuid).unwrap().interruptions, 1);
+        assert_eq!(repo.interrupts_for(pom.uuid).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn transaction_rolls_back_on_error() {
+        let repo = Repository::new("file::memory:");
+        let pom = finished_pomodoro(&repo, 1000);
+
+        let result: Result<(), PersistenceError> = repo.transaction(|| {
+            repo.delete(pom.uuid)?;
+            Err(PersistenceError::CannotSave("abort".to_string()))
+        });
+
+        assert!(result.is_err());
+        assert!(repo.find_by_uuid(pom.uuid).is_ok());
+    }
+
+    #[test]
+    fn locked_database_is_reported_after_busy_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_path_buf().join("data.db");
+        let holder = Repository::new(&path.to_string_lossy());
+        let waiter = Repository::new(&path.to_string_lossy());
+        waiter
+            .set_busy_timeout(std::time::Duration::from_millis(50))
+            .unwrap();
+
+        let result = holder.transaction(|| {
+            finished_pomodoro(&holder, 1000);
+            let mut pom = Schedulable::new(0, Kind::Pomodoro, 25);
+            pom.started_at = 5000;
+            pom.finished_at = 6500;
+            Ok(waiter.save_external_finished(&pom))
+        });
+
+        assert_matches!(result, Ok(Err(PersistenceError::Locked(_))));
+    }
 }
 
 // --- parse_timestamp ---------------------------------------------------------